@@ -0,0 +1,545 @@
+//! `sync-saves-cli`: headless access to the same database and cloud account as
+//! the desktop app, for scripts, servers and Steam Deck game mode.
+//!
+//! Exit codes: 0 success, 1 failure, 2 bad usage, 3 not signed in,
+//! 4 game or version not found, 5 some games failed in `sync --all`.
+
+use clap::{Parser, Subcommand};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use sync_saves_core::db;
+use sync_saves_core::device;
+use sync_saves_core::games::{self, LocalGame, NewGameLocation};
+use sync_saves_core::paths::AppPaths;
+use sync_saves_core::session::{self, CloudSession, APP_SESSION_KEY, CLI_SESSION_KEY};
+use sync_saves_core::sync::{self, AuthConfig, SyncResult};
+use sync_saves_core::versions::{self, CloudVersion, VersionMeta};
+
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+const EXIT_AUTH: i32 = 3;
+const EXIT_NOT_FOUND: i32 = 4;
+const EXIT_PARTIAL: i32 = 5;
+
+#[derive(Parser)]
+#[command(name = "sync-saves-cli", version, about = "Sync game saves without the desktop app")]
+struct Cli {
+    /// Print results as JSON
+    #[arg(long, global = true)]
+    json: bool,
+    /// Database to use instead of the desktop app's
+    #[arg(long, global = true, env = "SYNC_SAVES_DB")]
+    db: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List configured games
+    Games,
+    /// Add a game
+    Add {
+        name: String,
+        /// Save folder or file; path templates like {{p|appdata}}/Game work too
+        path: String,
+        #[arg(long, default_value = "other")]
+        platform: String,
+        #[arg(long)]
+        steam_app_id: Option<u32>,
+        /// Extra save location, as LABEL=PATH (repeatable)
+        #[arg(long = "location", value_name = "LABEL=PATH")]
+        locations: Vec<String>,
+    },
+    /// Upload a game's saves as a new version
+    Sync {
+        /// Game id, slug or name
+        #[arg(required_unless_present = "all")]
+        game: Option<String>,
+        /// Sync every game with sync enabled
+        #[arg(long, conflicts_with_all = ["game", "label", "notes", "pin"])]
+        all: bool,
+        #[arg(long)]
+        label: Option<String>,
+        #[arg(long)]
+        notes: Option<String>,
+        /// Keep the version through pruning
+        #[arg(long)]
+        pin: bool,
+    },
+    /// Restore a game's saves from the cloud
+    Restore {
+        game: String,
+        /// Version id or label; the latest version by default
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// List a game's cloud versions
+    Versions { game: String },
+    /// Show this device, the signed-in account and each game's sync state
+    Status,
+    /// Sign in with email and password (password from SYNC_SAVES_PASSWORD or stdin)
+    Login {
+        #[arg(long)]
+        email: String,
+        /// Supabase project URL; defaults to the desktop app's
+        #[arg(long, env = "SYNC_SAVES_URL")]
+        url: Option<String>,
+        /// Supabase anon key; defaults to the desktop app's
+        #[arg(long, env = "SYNC_SAVES_ANON_KEY")]
+        key: Option<String>,
+    },
+    /// Forget the CLI's sign-in
+    Logout,
+}
+
+struct CliError {
+    code: i32,
+    message: String,
+}
+
+impl CliError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        Self::new(EXIT_FAILURE, message)
+    }
+}
+
+type CliResult<T> = Result<T, CliError>;
+
+fn main() {
+    std::process::exit(run())
+}
+
+/// Parses the arguments, runs the command and returns the process exit code.
+fn run() -> i32 {
+    let cli = Cli::parse();
+    let json = cli.json;
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => return report_error(json, CliError::new(EXIT_FAILURE, e.to_string())),
+    };
+    match runtime.block_on(execute(cli)) {
+        Ok(code) => code,
+        Err(e) => report_error(json, e),
+    }
+}
+
+fn report_error(json: bool, error: CliError) -> i32 {
+    if json {
+        print_json(&serde_json::json!({ "error": error.message, "code": error.code }));
+    } else {
+        eprintln!("error: {}", error.message);
+    }
+    error.code
+}
+
+fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(out) => println!("{}", out),
+        Err(e) => eprintln!("error: {}", e),
+    }
+}
+
+async fn execute(cli: Cli) -> CliResult<i32> {
+    let db_path = match cli.db {
+        Some(path) => path,
+        None => AppPaths::default_dirs()
+            .map(|paths| paths.db_path())
+            .ok_or_else(|| "Can't find the app data directory".to_string())?,
+    };
+    db::init(&db_path).map_err(|e| e.to_string())?;
+    let json = cli.json;
+
+    match cli.command {
+        Command::Games => {
+            let conn = open(&db_path)?;
+            let list = games::list_games(&conn)?;
+            if json {
+                print_json(&list);
+            } else {
+                for game in &list {
+                    println!(
+                        "{}  {:<24} {:<8} {}{}",
+                        game.id,
+                        game.name,
+                        game.platform,
+                        game.local_path,
+                        if game.sync_enabled { "" } else { "  (sync off)" }
+                    );
+                }
+            }
+        }
+        Command::Add {
+            name,
+            path,
+            platform,
+            steam_app_id,
+            locations,
+        } => {
+            let locations = locations
+                .iter()
+                .map(|l| parse_location(l))
+                .collect::<CliResult<Vec<_>>>()?;
+            let mut conn = open(&db_path)?;
+            let game = games::create_game(&mut conn, name, path, platform, None, locations, steam_app_id)?;
+            if json {
+                print_json(&game);
+            } else {
+                println!("Added {} ({})", game.name, game.id);
+            }
+        }
+        Command::Sync {
+            game,
+            all,
+            label,
+            notes,
+            pin,
+        } => {
+            let auth = signed_in(&db_path).await?;
+            let targets: Vec<LocalGame> = {
+                let conn = open(&db_path)?;
+                if all {
+                    games::list_games(&conn)?.into_iter().filter(|g| g.sync_enabled).collect()
+                } else {
+                    vec![find_game(&conn, game.as_deref().unwrap_or_default())?]
+                }
+            };
+            let metadata = (label.is_some() || notes.is_some() || pin).then_some(VersionMeta {
+                label,
+                notes,
+                is_pinned: pin,
+            });
+            return sync_games(&db_path, &auth, &targets, metadata, json).await;
+        }
+        Command::Restore { game, version } => {
+            let auth = signed_in(&db_path).await?;
+            let game = find_game(&open(&db_path)?, &game)?;
+            let restored = versions::restore_cloud_version(&db_path, &game.id, &auth, version.as_deref())
+                .await
+                .map_err(|e| {
+                    let code = if e.starts_with("No version") { EXIT_NOT_FOUND } else { EXIT_FAILURE };
+                    CliError::new(code, e)
+                })?;
+            if json {
+                print_json(&restored);
+            } else {
+                println!(
+                    "Restored {} from {}{}",
+                    game.name,
+                    restored.created_at.format("%Y-%m-%d %H:%M"),
+                    restored.label.map(|l| format!(" ({})", l)).unwrap_or_default()
+                );
+            }
+        }
+        Command::Versions { game } => {
+            let auth = signed_in(&db_path).await?;
+            let game = find_game(&open(&db_path)?, &game)?;
+            let client = reqwest::Client::new();
+            let list: Vec<CloudVersion> = match sync::find_cloud_game(&client, &auth, &game.slug).await? {
+                Some(cloud_game_id) => versions::list_cloud_versions(&client, &auth, &cloud_game_id).await?,
+                None => vec![],
+            };
+            if json {
+                print_json(&list);
+            } else {
+                for v in &list {
+                    let mut flags = Vec::new();
+                    if v.is_latest {
+                        flags.push("latest");
+                    }
+                    if v.is_pinned {
+                        flags.push("pinned");
+                    }
+                    println!(
+                        "{}  {}  {:>10}  {:<7} {}",
+                        v.id,
+                        v.created_at.format("%Y-%m-%d %H:%M"),
+                        v.file_size,
+                        flags.join(","),
+                        v.label.as_deref().unwrap_or("")
+                    );
+                }
+            }
+        }
+        Command::Status => {
+            let status = load_status(&db_path)?;
+            if json {
+                print_json(&status);
+            } else {
+                println!("Device:   {}", status.device_id);
+                println!("Database: {}", status.database);
+                match &status.account {
+                    Some(a) => println!(
+                        "Account:  {} ({} session{})",
+                        a.user_id,
+                        a.source,
+                        if a.expired { ", expired" } else { "" }
+                    ),
+                    None => println!("Account:  not signed in"),
+                }
+                for g in &status.games {
+                    println!(
+                        "{:<24} {:<8} last sync {}{}",
+                        g.name,
+                        g.status,
+                        g.last_synced_at.as_deref().unwrap_or("never"),
+                        if g.pending > 0 { format!(", {} queued", g.pending) } else { String::new() }
+                    );
+                }
+            }
+        }
+        Command::Login { email, url, key } => {
+            let conn = open(&db_path)?;
+            let app_session = session::load_session(&conn, APP_SESSION_KEY)?;
+            let url = url
+                .or_else(|| app_session.as_ref().map(|s| s.url.clone()))
+                .ok_or_else(|| CliError::new(EXIT_FAILURE, "No Supabase URL: pass --url or sign in to the app once"))?;
+            let key = key
+                .or_else(|| app_session.as_ref().map(|s| s.key.clone()))
+                .ok_or_else(|| CliError::new(EXIT_FAILURE, "No Supabase key: pass --key or sign in to the app once"))?;
+            let password = read_password()?;
+
+            let session = session::password_login(&url, &key, &email, &password)
+                .await
+                .map_err(|e| CliError::new(EXIT_AUTH, e))?;
+            session::store_session(&conn, CLI_SESSION_KEY, Some(&session))?;
+            if json {
+                print_json(&serde_json::json!({ "user_id": session.user_id }));
+            } else {
+                println!("Signed in as {}", email);
+            }
+        }
+        Command::Logout => {
+            session::store_session(&open(&db_path)?, CLI_SESSION_KEY, None)?;
+            if json {
+                print_json(&serde_json::json!({ "signed_out": true }));
+            } else {
+                println!("Signed out");
+            }
+        }
+    }
+    Ok(0)
+}
+
+fn open(db_path: &Path) -> CliResult<Connection> {
+    db::open(db_path).map_err(|e| CliError::from(e.to_string()))
+}
+
+fn parse_location(raw: &str) -> CliResult<NewGameLocation> {
+    let (label, path) = raw
+        .split_once('=')
+        .ok_or_else(|| CliError::new(EXIT_USAGE, format!("Expected LABEL=PATH, got '{}'", raw)))?;
+    Ok(NewGameLocation {
+        label: label.trim().to_string(),
+        path: path.trim().to_string(),
+        file_pattern: None,
+    })
+}
+
+/// Matches a game by id, slug or (case-insensitive) name.
+fn find_game(conn: &Connection, query: &str) -> CliResult<LocalGame> {
+    let all = games::list_games(conn)?;
+    let lower = query.to_lowercase();
+    let mut matches: Vec<LocalGame> = Vec::new();
+    for game in all {
+        if game.id == query || game.slug == query {
+            return Ok(game);
+        }
+        if game.name.to_lowercase() == lower {
+            matches.push(game);
+        }
+    }
+    match matches.len() {
+        0 => Err(CliError::new(EXIT_NOT_FOUND, format!("No game matches '{}'", query))),
+        1 => Ok(matches.remove(0)),
+        _ => Err(CliError::new(
+            EXIT_FAILURE,
+            format!("Several games are named '{}'; use the id", query),
+        )),
+    }
+}
+
+/// The CLI's own session (refreshed when expired), else the app's while still valid.
+async fn signed_in(db_path: &Path) -> CliResult<AuthConfig> {
+    let (cli_session, app_session) = {
+        let conn = open(db_path)?;
+        (
+            session::load_session(&conn, CLI_SESSION_KEY)?,
+            session::load_session(&conn, APP_SESSION_KEY)?,
+        )
+    };
+
+    if let Some(session) = cli_session {
+        if !session.is_expired() {
+            return Ok(session.auth());
+        }
+        let refreshed = session::refresh_session(&session)
+            .await
+            .map_err(|e| CliError::new(EXIT_AUTH, format!("Session expired, run `sync-saves-cli login`: {}", e)))?;
+        session::store_session(&open(db_path)?, CLI_SESSION_KEY, Some(&refreshed))?;
+        return Ok(refreshed.auth());
+    }
+
+    match app_session {
+        Some(session) if !session.is_expired() => Ok(session.auth()),
+        Some(_) => Err(CliError::new(
+            EXIT_AUTH,
+            "The app's session has expired; open the app or run `sync-saves-cli login`",
+        )),
+        None => Err(CliError::new(
+            EXIT_AUTH,
+            "Not signed in; sign in to the app or run `sync-saves-cli login`",
+        )),
+    }
+}
+
+fn read_password() -> CliResult<String> {
+    if let Ok(password) = std::env::var("SYNC_SAVES_PASSWORD") {
+        return Ok(password);
+    }
+    eprint!("Password: ");
+    let _ = io::stderr().flush();
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line).map_err(|e| e.to_string())?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[derive(Serialize)]
+struct GameSyncOutcome {
+    game_id: String,
+    name: String,
+    #[serde(flatten)]
+    result: Option<SyncResult>,
+    error: Option<String>,
+}
+
+async fn sync_games(
+    db_path: &Path,
+    auth: &AuthConfig,
+    targets: &[LocalGame],
+    metadata: Option<VersionMeta>,
+    json: bool,
+) -> CliResult<i32> {
+    let mut outcomes = Vec::new();
+    for game in targets {
+        let outcome = match sync::run_sync(db_path, &game.id, auth, metadata.clone()).await {
+            Ok(result) => {
+                if !json {
+                    println!("{}: {}", game.name, result.message);
+                }
+                GameSyncOutcome {
+                    game_id: game.id.clone(),
+                    name: game.name.clone(),
+                    result: Some(result),
+                    error: None,
+                }
+            }
+            Err(e) => {
+                if !json {
+                    eprintln!("{}: {}", game.name, e);
+                }
+                GameSyncOutcome {
+                    game_id: game.id.clone(),
+                    name: game.name.clone(),
+                    result: None,
+                    error: Some(e),
+                }
+            }
+        };
+        outcomes.push(outcome);
+    }
+
+    if json {
+        print_json(&outcomes);
+    }
+    let failed = outcomes.iter().filter(|o| o.error.is_some()).count();
+    Ok(match failed {
+        0 => 0,
+        n if n == outcomes.len() => EXIT_FAILURE,
+        _ => EXIT_PARTIAL,
+    })
+}
+
+#[derive(Serialize)]
+struct Account {
+    user_id: String,
+    /// `cli` or `app`
+    source: &'static str,
+    expired: bool,
+}
+
+#[derive(Serialize)]
+struct GameStatus {
+    id: String,
+    name: String,
+    sync_enabled: bool,
+    status: String,
+    last_synced_at: Option<String>,
+    last_synced_id: Option<String>,
+    /// Entries waiting in `sync_queue`
+    pending: u32,
+}
+
+#[derive(Serialize)]
+struct Status {
+    device_id: String,
+    database: String,
+    account: Option<Account>,
+    games: Vec<GameStatus>,
+}
+
+fn load_status(db_path: &Path) -> CliResult<Status> {
+    let conn = open(db_path)?;
+    let account = |session: Option<CloudSession>, source| {
+        session.map(|s| Account {
+            expired: s.is_expired() && s.refresh_token.is_none(),
+            user_id: s.user_id,
+            source,
+        })
+    };
+    let account = account(session::load_session(&conn, CLI_SESSION_KEY)?, "cli")
+        .or(account(session::load_session(&conn, APP_SESSION_KEY)?, "app"));
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT g.id, g.name, g.sync_enabled, g.status, g.last_synced_at, g.last_synced_id,
+                    (SELECT COUNT(*) FROM sync_queue q WHERE q.game_id = g.id AND q.status IN ('pending', 'processing'))
+             FROM games_cache g
+             ORDER BY g.name",
+        )
+        .map_err(|e| e.to_string())?;
+    let games = stmt
+        .query_map([], |row| {
+            Ok(GameStatus {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                sync_enabled: row.get::<_, i32>(2)? != 0,
+                status: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                last_synced_at: row.get(4)?,
+                last_synced_id: row.get(5)?,
+                pending: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(Status {
+        device_id: device::device_id(&conn)?,
+        database: db_path.display().to_string(),
+        account,
+        games,
+    })
+}
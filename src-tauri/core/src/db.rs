@@ -0,0 +1,221 @@
+use rusqlite::{Connection, Result};
+use std::fs;
+use std::path::Path;
+
+/// Creates the database and runs migrations.
+pub fn init(db_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    // Create app dir if not exists
+    if let Some(app_dir) = db_path.parent() {
+        fs::create_dir_all(app_dir)?;
+    }
+
+    let conn = Connection::open(db_path)?;
+
+    // Enable foreign keys
+    conn.execute("PRAGMA foreign_keys = ON;", [])?;
+
+    // Create tables
+    create_tables(&conn)?;
+
+    Ok(())
+}
+
+fn create_tables(conn: &Connection) -> Result<()> {
+    // Device config table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS device_config (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Games cache table (local mirror of some remote data + local paths)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS games_cache (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            slug TEXT NOT NULL,
+            cover_url TEXT,
+            platform TEXT,
+            local_path TEXT,
+            sync_enabled INTEGER DEFAULT 1,
+            last_synced_at TEXT,
+            last_synced_id TEXT,
+            status TEXT DEFAULT 'idle',
+            completion_percentage REAL DEFAULT 0,
+            play_time_seconds INTEGER DEFAULT 0,
+            last_analyzed_at TEXT,
+            custom_script_path TEXT,
+            analysis_config TEXT,
+            steam_app_id INTEGER,
+            cover_cache_path TEXT,
+            emulator_profile TEXT,
+            retention_policy TEXT,
+            executable_path TEXT,
+            sync_paused INTEGER DEFAULT 0,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // Version analysis results table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS version_analysis (
+            version_id TEXT PRIMARY KEY,
+            analysis_data TEXT NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            game_id TEXT REFERENCES games_cache(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Add new columns if they don't exist (migration for existing DBs)
+    let _ = conn.execute(
+        "ALTER TABLE games_cache ADD COLUMN completion_percentage REAL DEFAULT 0",
+        [],
+    );
+    let _ = conn.execute(
+        "ALTER TABLE games_cache ADD COLUMN play_time_seconds INTEGER DEFAULT 0",
+        [],
+    );
+    let _ = conn.execute(
+        "ALTER TABLE games_cache ADD COLUMN last_analyzed_at TEXT",
+        [],
+    );
+    let _ = conn.execute("ALTER TABLE games_cache ADD COLUMN last_synced_id TEXT", []);
+    let _ = conn.execute("ALTER TABLE games_cache ADD COLUMN custom_script_path TEXT", []);
+    let _ = conn.execute("ALTER TABLE games_cache ADD COLUMN analysis_config TEXT", []);
+    let _ = conn.execute("ALTER TABLE games_cache ADD COLUMN steam_app_id INTEGER", []);
+    let _ = conn.execute("ALTER TABLE games_cache ADD COLUMN cover_cache_path TEXT", []);
+    let _ = conn.execute("ALTER TABLE games_cache ADD COLUMN emulator_profile TEXT", []);
+    let _ = conn.execute("ALTER TABLE games_cache ADD COLUMN retention_policy TEXT", []);
+    let _ = conn.execute("ALTER TABLE games_cache ADD COLUMN executable_path TEXT", []);
+    let _ = conn.execute("ALTER TABLE games_cache ADD COLUMN sync_paused INTEGER DEFAULT 0", []);
+    // Progress reported by the analyzer for each version
+    let _ = conn.execute("ALTER TABLE version_analysis ADD COLUMN completion_percentage REAL", []);
+    let _ = conn.execute("ALTER TABLE version_analysis ADD COLUMN play_time_seconds INTEGER", []);
+    let _ = conn.execute("ALTER TABLE version_analysis ADD COLUMN analyzer TEXT", []);
+    // Analyses go away with their game; rows from before this column are linked
+    // where possible and otherwise left to collect_garbage
+    let _ = conn.execute(
+        "ALTER TABLE version_analysis ADD COLUMN game_id TEXT REFERENCES games_cache(id) ON DELETE CASCADE",
+        [],
+    );
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_version_analysis_game ON version_analysis(game_id)",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE version_analysis SET game_id = (SELECT id FROM games_cache WHERE last_synced_id = version_analysis.version_id)
+         WHERE game_id IS NULL",
+        [],
+    )?;
+
+    // Labels, notes and pins of cloud versions, cached so they show up offline
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS version_labels (
+            version_id TEXT PRIMARY KEY,
+            game_id TEXT NOT NULL,
+            label TEXT,
+            notes TEXT,
+            is_pinned INTEGER DEFAULT 0,
+            created_at TEXT, -- when the version was uploaded
+            FOREIGN KEY(game_id) REFERENCES games_cache(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_version_labels_game ON version_labels(game_id)",
+        [],
+    )?;
+
+    // Save locations table (a game can keep state in several places).
+    // The location with sort_order 0 mirrors games_cache.local_path.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS game_locations (
+            id TEXT PRIMARY KEY,
+            game_id TEXT NOT NULL,
+            label TEXT NOT NULL,
+            path TEXT NOT NULL,
+            template TEXT, -- portable form of path, e.g. {{p|appdata}}/Game
+            file_pattern TEXT, -- e.g. 'Zelda*.srm' to sync one game out of a shared folder
+            sort_order INTEGER DEFAULT 0,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(game_id, label),
+            FOREIGN KEY(game_id) REFERENCES games_cache(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    let _ = conn.execute("ALTER TABLE game_locations ADD COLUMN template TEXT", []);
+    let _ = conn.execute("ALTER TABLE game_locations ADD COLUMN file_pattern TEXT", []);
+
+    // Backfill a primary location for games created before game_locations existed
+    conn.execute(
+        "INSERT INTO game_locations (id, game_id, label, path, sort_order)
+         SELECT lower(hex(randomblob(16))), id, 'saves', local_path, 0
+         FROM games_cache
+         WHERE local_path IS NOT NULL
+           AND id NOT IN (SELECT game_id FROM game_locations)",
+        [],
+    )?;
+
+    // Offline save-location database imported from a Ludusavi manifest
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS manifest_games (
+            name TEXT PRIMARY KEY,
+            name_lower TEXT NOT NULL,
+            steam_app_id INTEGER,
+            data TEXT NOT NULL -- JSON ManifestEntry
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_manifest_games_name_lower ON manifest_games(name_lower)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_manifest_games_steam_app_id ON manifest_games(steam_app_id)",
+        [],
+    )?;
+
+    // Steam store metadata, kept so covers and details work offline
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS steam_app_cache (
+            app_id INTEGER PRIMARY KEY,
+            data TEXT, -- JSON SteamAppDetails, NULL when Steam has no store page
+            cover_path TEXT,
+            cover_source_url TEXT,
+            fetched_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Sync queue table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS sync_queue (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            game_id TEXT NOT NULL,
+            action TEXT NOT NULL, -- 'upload' or 'download'
+            status TEXT DEFAULT 'pending', -- 'pending', 'processing', 'failed'
+            file_path TEXT,
+            priority INTEGER DEFAULT 0,
+            retry_count INTEGER DEFAULT 0,
+            metadata TEXT, -- label, notes and pin of the version, as JSON
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY(game_id) REFERENCES games_cache(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    let _ = conn.execute("ALTER TABLE sync_queue ADD COLUMN metadata TEXT", []);
+
+    Ok(())
+}
+
+pub fn open(db_path: &Path) -> Result<Connection> {
+    let conn = Connection::open(db_path)?;
+    // Foreign keys are per-connection in SQLite; needed for ON DELETE CASCADE
+    conn.execute("PRAGMA foreign_keys = ON;", [])?;
+    Ok(conn)
+}
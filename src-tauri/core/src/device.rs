@@ -0,0 +1,26 @@
+use rusqlite::Connection;
+use uuid::Uuid;
+
+/// This device's id, generated on first use.
+pub fn device_id(conn: &Connection) -> Result<String, String> {
+    // Try to get existing device ID
+    let mut stmt = conn
+        .prepare("SELECT value FROM device_config WHERE key = 'device_id'")
+        .map_err(|e| e.to_string())?;
+
+    let existing_id: Result<String, _> = stmt.query_row([], |row| row.get(0));
+
+    match existing_id {
+        Ok(id) => Ok(id),
+        Err(_) => {
+            // Generate a new UUID
+            let new_id = Uuid::new_v4().to_string();
+            conn.execute(
+                "INSERT INTO device_config (key, value) VALUES ('device_id', ?1)",
+                [&new_id],
+            )
+            .map_err(|e| e.to_string())?;
+            Ok(new_id)
+        }
+    }
+}
@@ -0,0 +1,615 @@
+use crate::utils::file_pattern::{self, FilePattern};
+use crate::utils::path_template::{self, PathContext};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LocalGame {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+    pub cover_url: Option<String>,
+    pub platform: String,
+    pub local_path: String,
+    pub sync_enabled: bool,
+    pub last_synced_id: Option<String>,
+    /// RFC 3339 time of the last sync or restore
+    pub last_synced_at: Option<String>,
+    /// Held back from syncing until resumed
+    pub sync_paused: bool,
+    pub status: String,
+    pub custom_script_path: Option<String>,
+    pub analysis_config: Option<serde_json::Value>,
+    pub steam_app_id: Option<u32>,
+    /// Emulator profile id for games synced out of an emulator's folders
+    pub emulator_profile: Option<String>,
+    /// Started by `launch` when set; otherwise Steam games start through Steam
+    pub executable_path: Option<String>,
+}
+
+/// Label given to the location that mirrors `games_cache.local_path`.
+pub const PRIMARY_LOCATION_LABEL: &str = "saves";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameLocation {
+    pub id: String,
+    pub game_id: String,
+    pub label: String,
+    pub path: String,
+    pub template: Option<String>,
+    pub sort_order: i64,
+    /// Only matching files are synced, for folders shared by several games
+    pub file_pattern: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct NewGameLocation {
+    pub label: String,
+    pub path: String,
+    #[serde(default)]
+    pub file_pattern: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct GameSaveStats {
+    pub path: String,
+    pub exists: bool,
+    pub is_dir: bool,
+    pub file_count: u64,
+    pub total_bytes: u64,
+    pub newest_mtime_ms: Option<i64>,
+    /// Per-location breakdown; top-level counters are the sum over all locations.
+    pub locations: Vec<LocationSaveStats>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct LocationSaveStats {
+    pub label: String,
+    pub path: String,
+    pub exists: bool,
+    pub is_dir: bool,
+    pub file_count: u64,
+    pub total_bytes: u64,
+    pub newest_mtime_ms: Option<i64>,
+}
+
+fn system_time_to_ms(st: SystemTime) -> i64 {
+    st.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+const LOCAL_GAME_COLUMNS: &str = "id, name, slug, cover_url, platform, local_path, sync_enabled, last_synced_id, status, custom_script_path, analysis_config, steam_app_id, emulator_profile, executable_path, last_synced_at, sync_paused";
+
+fn local_game_from_row(row: &rusqlite::Row) -> rusqlite::Result<LocalGame> {
+    let config_json: Option<String> = row.get(10)?;
+    let analysis_config = config_json
+        .and_then(|s| serde_json::from_str(&s).ok());
+
+    Ok(LocalGame {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        slug: row.get(2)?,
+        cover_url: row.get(3)?,
+        platform: row.get(4)?,
+        local_path: row.get(5)?,
+        sync_enabled: row.get::<_, i32>(6)? != 0,
+        last_synced_id: row.get(7)?,
+        status: row.get(8)?,
+        custom_script_path: row.get(9)?,
+        analysis_config,
+        steam_app_id: row.get(11)?,
+        emulator_profile: row.get(12)?,
+        executable_path: row.get(13)?,
+        last_synced_at: row.get(14)?,
+        sync_paused: row.get::<_, Option<i32>>(15)?.unwrap_or(0) != 0,
+    })
+}
+
+pub fn get_local_game(conn: &Connection, game_id: &str) -> Result<LocalGame, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM games_cache WHERE id = ?1", LOCAL_GAME_COLUMNS),
+        [game_id],
+        local_game_from_row,
+    )
+    .map_err(|e| format!("Game not found: {}", e))
+}
+
+pub fn list_games(conn: &Connection) -> Result<Vec<LocalGame>, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM games_cache", LOCAL_GAME_COLUMNS))
+        .map_err(|e| e.to_string())?;
+
+    let games_iter = stmt
+        .query_map([], local_game_from_row)
+        .map_err(|e| e.to_string())?;
+
+    let mut games = Vec::new();
+    for game in games_iter {
+        games.push(game.map_err(|e| e.to_string())?);
+    }
+
+    Ok(games)
+}
+
+pub fn create_game(
+    conn: &mut Connection,
+    name: String,
+    local_path: String,
+    platform: String,
+    cover_url: Option<String>,
+    extra_locations: Vec<NewGameLocation>,
+    steam_app_id: Option<u32>,
+) -> Result<LocalGame, String> {
+    let id = Uuid::new_v4().to_string();
+    let slug = name.to_lowercase().replace(" ", "-"); // Simple slug for now
+
+    for loc in &extra_locations {
+        validate_location_label(&loc.label)?;
+        if loc.label == PRIMARY_LOCATION_LABEL {
+            return Err(format!("Location label '{}' is reserved", PRIMARY_LOCATION_LABEL));
+        }
+    }
+
+    // local_path may be a template (e.g. synced from another device); the game
+    // keeps its resolved form and the location the template as given
+    let ctx = PathContext::current(steam_app_id);
+    let given_path = local_path;
+    let (local_path, _) = resolve_with_template(&given_path, &ctx)?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    tx.execute(
+        "INSERT INTO games_cache (id, name, slug, platform, local_path, sync_enabled, cover_url, status, steam_app_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6, 'idle', ?7)",
+        rusqlite::params![&id, &name, &slug, &platform, &local_path, &cover_url, &steam_app_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    insert_location(&tx, &id, PRIMARY_LOCATION_LABEL, &given_path, 0, &ctx, None)?;
+    for (i, loc) in extra_locations.iter().enumerate() {
+        insert_location(
+            &tx,
+            &id,
+            &loc.label,
+            &loc.path,
+            i as i64 + 1,
+            &ctx,
+            loc.file_pattern.as_deref(),
+        )?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(LocalGame {
+        id,
+        name,
+        slug,
+        cover_url, // Use the provided cover_url
+        platform,
+        local_path,
+        sync_enabled: true,
+        last_synced_id: None,
+        last_synced_at: None,
+        sync_paused: false,
+        status: "idle".to_string(),
+        custom_script_path: None,
+        analysis_config: None,
+        steam_app_id,
+        emulator_profile: None,
+        executable_path: None,
+    })
+}
+
+/// Sizes and file counts of the game's save locations on this device.
+pub fn save_stats(conn: &Connection, game_id: &str) -> Result<GameSaveStats, String> {
+    let mut stmt = conn
+        .prepare("SELECT local_path FROM games_cache WHERE id = ?1")
+        .map_err(|e| e.to_string())?;
+
+    let local_path: String = stmt
+        .query_row([game_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    let locations = load_game_locations(conn, game_id).map_err(|e| e.to_string())?;
+
+    let primary = Path::new(&local_path);
+    let exists = primary.exists();
+
+    let mut out = GameSaveStats {
+        path: local_path.clone(),
+        exists,
+        is_dir: exists && primary.is_dir(),
+        ..Default::default()
+    };
+
+    for loc in locations {
+        let pattern = file_pattern::parse_optional(loc.file_pattern.as_deref());
+        let stats = collect_location_stats(&loc.label, &loc.path, pattern.as_ref());
+        out.file_count += stats.file_count;
+        out.total_bytes = out.total_bytes.saturating_add(stats.total_bytes);
+        out.newest_mtime_ms = match (out.newest_mtime_ms, stats.newest_mtime_ms) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        out.locations.push(stats);
+    }
+
+    Ok(out)
+}
+
+pub fn collect_location_stats(
+    label: &str,
+    location_path: &str,
+    pattern: Option<&FilePattern>,
+) -> LocationSaveStats {
+    let path = Path::new(location_path);
+    let exists = path.exists();
+    let is_dir = exists && path.is_dir();
+
+    let mut out = LocationSaveStats {
+        label: label.to_string(),
+        path: location_path.to_string(),
+        exists,
+        is_dir,
+        ..Default::default()
+    };
+
+    if !exists {
+        // Path validation failed; return stats object (no error) so UI can decide what to show.
+        return out;
+    }
+
+    let mut newest_mtime_ms: Option<i64> = None;
+    let mut file_count: u64 = 0;
+    let mut total_bytes: u64 = 0;
+
+    // WalkDir on a file yields just that file, so single-file locations work too
+    for entry in WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if let Some(pattern) = pattern {
+            let rel = entry.path().strip_prefix(path).unwrap_or(entry.path());
+            if !pattern.matches(rel) {
+                continue;
+            }
+        }
+
+        file_count += 1;
+        if let Ok(md) = entry.metadata() {
+            total_bytes = total_bytes.saturating_add(md.len());
+            if let Ok(modified) = md.modified() {
+                let ms = system_time_to_ms(modified);
+                newest_mtime_ms = Some(newest_mtime_ms.map(|x| x.max(ms)).unwrap_or(ms));
+            }
+        }
+    }
+
+    out.file_count = file_count;
+    out.total_bytes = total_bytes;
+    out.newest_mtime_ms = newest_mtime_ms;
+
+    out
+}
+
+pub fn delete_game(conn: &Connection, game_id: &str) -> Result<(), String> {
+    // Delete from games_cache
+    conn.execute("DELETE FROM games_cache WHERE id = ?1", [game_id])
+        .map_err(|e| e.to_string())?;
+
+    // Remove from sync queue if any pending
+    conn.execute("DELETE FROM sync_queue WHERE game_id = ?1", [game_id])
+        .map_err(|e| e.to_string())?;
+
+    // Remove save locations
+    conn.execute("DELETE FROM game_locations WHERE game_id = ?1", [game_id])
+        .map_err(|e| e.to_string())?;
+
+    // version_analysis rows linked to the game are removed by ON DELETE CASCADE.
+    // Unlinked rows from older versions of the app are left to collect_garbage.
+
+    Ok(())
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct UpdateGameParams {
+    pub name: Option<String>,
+    pub local_path: Option<String>,
+    pub platform: Option<String>,
+    pub sync_enabled: Option<bool>,
+    pub cover_url: Option<String>,
+    pub custom_script_path: Option<String>,
+    pub analysis_config: Option<serde_json::Value>,
+    pub steam_app_id: Option<u32>,
+    /// An empty string clears it
+    pub executable_path: Option<String>,
+    pub sync_paused: Option<bool>,
+}
+
+pub fn update_game(conn: &Connection, game_id: &str, updates: UpdateGameParams) -> Result<LocalGame, String> {
+    // First, get the current game data
+    let current_game = get_local_game(conn, game_id)?;
+
+    // Apply updates
+    let new_name = updates.name.unwrap_or(current_game.name);
+    let new_slug = new_name.to_lowercase().replace(" ", "-");
+    let new_steam_app_id = updates.steam_app_id.or(current_game.steam_app_id);
+    // Without a new path the stored template stays, tokens and all
+    let (new_local_path, new_template) = match updates.local_path {
+        Some(path) => {
+            let (path, template) = resolve_with_template(&path, &PathContext::current(new_steam_app_id))?;
+            (path, Some(template))
+        }
+        None => (current_game.local_path, None),
+    };
+    let new_platform = updates.platform.unwrap_or(current_game.platform);
+    let new_sync_enabled = updates.sync_enabled.unwrap_or(current_game.sync_enabled);
+    let new_cover_url = updates.cover_url.or(current_game.cover_url);
+    let new_custom_script_path = updates.custom_script_path.or(current_game.custom_script_path);
+    let new_executable_path = match updates.executable_path {
+        Some(path) if path.trim().is_empty() => None,
+        Some(path) => Some(path.trim().to_string()),
+        None => current_game.executable_path,
+    };
+    let new_sync_paused = updates.sync_paused.unwrap_or(current_game.sync_paused);
+    
+    // For analysis config, if update is provided, use it, otherwise keep current
+    // Note: If update is provided as explicit null (Option<Value>), it means we want to clear it? 
+    // Or does serde skip missing fields?
+    // In UpdateGameParams, fields are Option<T>. If missing (None), we shouldn't change.
+    // If we want to unset, we'd need Option<Option<T>> but that's complex.
+    // Assuming None means "no change".
+    let new_analysis_config = updates.analysis_config.or(current_game.analysis_config);
+    
+    let analysis_config_str = new_analysis_config
+        .as_ref()
+        .and_then(|v| serde_json::to_string(v).ok());
+
+    // Update the database
+    conn.execute(
+        "UPDATE games_cache 
+         SET name = ?1, slug = ?2, local_path = ?3, platform = ?4, sync_enabled = ?5, cover_url = ?6, custom_script_path = ?7, analysis_config = ?8, steam_app_id = ?9, executable_path = ?10, sync_paused = ?11
+         WHERE id = ?12",
+        rusqlite::params![
+            &new_name,
+            &new_slug,
+            &new_local_path,
+            &new_platform,
+            if new_sync_enabled { 1 } else { 0 },
+            &new_cover_url,
+            &new_custom_script_path,
+            &analysis_config_str,
+            &new_steam_app_id,
+            &new_executable_path,
+            new_sync_paused,
+            game_id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    if let Some(template) = &new_template {
+        conn.execute(
+            "UPDATE game_locations SET path = ?1, template = ?2 WHERE game_id = ?3 AND sort_order = 0",
+            [new_local_path.as_str(), template.as_str(), game_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(LocalGame {
+        id: game_id.to_string(),
+        name: new_name,
+        slug: new_slug,
+        cover_url: new_cover_url,
+        platform: new_platform,
+        local_path: new_local_path,
+        sync_enabled: new_sync_enabled,
+        last_synced_id: current_game.last_synced_id,
+        last_synced_at: current_game.last_synced_at,
+        sync_paused: new_sync_paused,
+        status: current_game.status,
+        custom_script_path: new_custom_script_path,
+        analysis_config: new_analysis_config,
+        steam_app_id: new_steam_app_id,
+        emulator_profile: current_game.emulator_profile,
+        executable_path: new_executable_path,
+    })
+}
+
+/// Loads every save location of a game, primary first.
+pub fn load_game_locations(
+    conn: &Connection,
+    game_id: &str,
+) -> rusqlite::Result<Vec<GameLocation>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, game_id, label, path, template, sort_order, file_pattern FROM game_locations
+         WHERE game_id = ?1 ORDER BY sort_order, created_at",
+    )?;
+
+    let rows = stmt.query_map([game_id], |row| {
+        Ok(GameLocation {
+            id: row.get(0)?,
+            game_id: row.get(1)?,
+            label: row.get(2)?,
+            path: row.get(3)?,
+            template: row.get(4)?,
+            sort_order: row.get(5)?,
+            file_pattern: row.get(6)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Labels become folder prefixes inside the archive, so they must be a single safe path component.
+pub fn validate_location_label(label: &str) -> Result<(), String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("Location label is required".to_string());
+    }
+    if label == "." || label == ".." || label.starts_with("__SYNC") {
+        return Err(format!("Invalid location label: {}", label));
+    }
+    if label.chars().any(|c| matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')) {
+        return Err(format!("Location label contains invalid characters: {}", label));
+    }
+    Ok(())
+}
+
+/// Returns `(absolute_path, template)` for a path that may be given in either form.
+pub fn resolve_with_template(path: &str, ctx: &PathContext) -> Result<(String, String), String> {
+    if path_template::is_template(path) {
+        let resolved = ctx.resolve(path)?;
+        Ok((resolved.to_string_lossy().to_string(), path.to_string()))
+    } else {
+        Ok((path.to_string(), ctx.to_template(path)))
+    }
+}
+
+pub fn insert_location(
+    conn: &Connection,
+    game_id: &str,
+    label: &str,
+    path: &str,
+    sort_order: i64,
+    ctx: &PathContext,
+    file_pattern: Option<&str>,
+) -> Result<GameLocation, String> {
+    let id = Uuid::new_v4().to_string();
+    let (path, template) = resolve_with_template(path, ctx)?;
+    let file_pattern = file_pattern.map(str::trim).filter(|p| !p.is_empty());
+    conn.execute(
+        "INSERT INTO game_locations (id, game_id, label, path, template, sort_order, file_pattern)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![&id, game_id, label.trim(), &path, &template, sort_order, file_pattern],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(GameLocation {
+        id,
+        game_id: game_id.to_string(),
+        label: label.trim().to_string(),
+        path,
+        template: Some(template),
+        sort_order,
+        file_pattern: file_pattern.map(str::to_string),
+    })
+}
+
+/// Adds a save location after the game's existing ones.
+pub fn add_location(
+    conn: &Connection,
+    game_id: &str,
+    label: &str,
+    path: &str,
+    file_pattern: Option<&str>,
+) -> Result<GameLocation, String> {
+    validate_location_label(label)?;
+
+    let (next_order, steam_app_id): (i64, Option<u32>) = conn
+        .query_row(
+            "SELECT (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM game_locations WHERE game_id = ?1), steam_app_id
+             FROM games_cache WHERE id = ?1",
+            [game_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Game not found: {}", e))?;
+
+    insert_location(conn, game_id, label, path, next_order, &PathContext::current(steam_app_id), file_pattern)
+}
+
+pub fn remove_location(conn: &Connection, location_id: &str) -> Result<bool, String> {
+    // The primary location is tied to games_cache.local_path and can only be changed via update_game
+    let deleted = conn
+        .execute(
+            "DELETE FROM game_locations WHERE id = ?1 AND sort_order <> 0",
+            [location_id],
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(deleted > 0)
+}
+
+#[derive(Serialize, Debug)]
+pub struct ResolvedPath {
+    pub template: String,
+    pub path: Option<String>,
+    pub exists: bool,
+    pub error: Option<String>,
+}
+
+/// Resolves path templates (e.g. fetched from the cloud) for this device.
+pub fn resolve_templates(templates: Vec<String>, steam_app_id: Option<u32>) -> Vec<ResolvedPath> {
+    let ctx = PathContext::current(steam_app_id);
+    templates
+        .into_iter()
+        .map(|template| match ctx.resolve(&template) {
+            Ok(path) => ResolvedPath {
+                exists: path.exists(),
+                path: Some(path.to_string_lossy().to_string()),
+                template,
+                error: None,
+            },
+            Err(e) => ResolvedPath {
+                template,
+                path: None,
+                exists: false,
+                error: Some(e),
+            },
+        })
+        .collect()
+}
+
+/// The stored analysis JSON of a version.
+pub fn get_analysis(conn: &Connection, version_id: &str) -> Result<Option<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT analysis_data FROM version_analysis WHERE version_id = ?1")
+        .map_err(|e| e.to_string())?;
+
+    let analysis_data: Option<String> = stmt
+        .query_row([version_id], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    Ok(analysis_data)
+}
+
+pub fn save_analysis(
+    conn: &Connection,
+    version_id: &str,
+    analysis_data: &str,
+    game_id: Option<&str>,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO version_analysis (version_id, analysis_data, game_id) VALUES (?1, ?2, ?3)
+         ON CONFLICT(version_id) DO UPDATE SET
+            analysis_data = excluded.analysis_data,
+            game_id = COALESCE(excluded.game_id, version_analysis.game_id)",
+        rusqlite::params![version_id, analysis_data, game_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Deletes the analyses of `version_ids` in batches, returning how many rows went.
+pub fn delete_analyses(conn: &Connection, version_ids: &[String]) -> rusqlite::Result<u32> {
+    let mut deleted_count = 0u32;
+    // Stay well under SQLite's bound parameter limit
+    for chunk in version_ids.chunks(500) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        deleted_count += conn.execute(
+            &format!("DELETE FROM version_analysis WHERE version_id IN ({})", placeholders),
+            rusqlite::params_from_iter(chunk),
+        )? as u32;
+    }
+    Ok(deleted_count)
+}
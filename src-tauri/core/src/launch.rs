@@ -0,0 +1,254 @@
+//! Launching games through the app: pull the cloud's latest save first, start
+//! the game, and wait for it to exit so the caller can upload right after.
+
+use crate::db;
+use crate::games::{self, LocalGame};
+use crate::services::steam_library;
+use crate::sync::{find_cloud_game, AuthConfig};
+use crate::utils::path_template::find_steam_root;
+use crate::versions::{list_cloud_versions, restore_cloud_version};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+/// How long a game started through Steam may take to show up as a process.
+const STEAM_START_TIMEOUT: Duration = Duration::from_secs(180);
+const PROCESS_POLL: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PullAction {
+    /// The local saves are the cloud's latest version
+    UpToDate,
+    /// The cloud was ahead and its latest version was restored
+    Pulled,
+    /// The cloud has a newer version, but the local saves changed since the
+    /// last sync too; they are kept and will be uploaded as a new version
+    KeptLocal,
+    NoCloudVersions,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PullReport {
+    pub action: PullAction,
+    /// The cloud's latest version
+    pub version_id: Option<String>,
+}
+
+/// Whether any save file changed after the last sync or restore.
+fn changed_since_sync(conn: &rusqlite::Connection, game_id: &str) -> Result<bool, String> {
+    let last_synced_at: Option<String> = conn
+        .query_row("SELECT last_synced_at FROM games_cache WHERE id = ?1", [game_id], |row| row.get(0))
+        .map_err(|e| format!("Game not found: {}", e))?;
+    let stats = games::save_stats(conn, game_id)?;
+
+    let synced_ms = last_synced_at
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(&t).ok())
+        .map(|t| t.timestamp_millis());
+    Ok(match (synced_ms, stats.newest_mtime_ms) {
+        (Some(synced), Some(modified)) => modified > synced,
+        // Never synced here: any existing save is local progress
+        (None, _) => stats.file_count > 0,
+        (Some(_), None) => false,
+    })
+}
+
+/// Restores the cloud's latest version when it is ahead of the local saves.
+/// Local saves changed since the last sync are never overwritten.
+pub async fn pull_latest(db_path: &Path, game_id: &str, auth: &AuthConfig) -> Result<PullReport, String> {
+    let (slug, last_synced_id): (String, Option<String>) = {
+        let conn = db::open(db_path).map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT slug, last_synced_id FROM games_cache WHERE id = ?1",
+            [game_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Game not found: {}", e))?
+    };
+
+    let client = reqwest::Client::new();
+    let latest = match find_cloud_game(&client, auth, &slug).await? {
+        Some(cloud_game_id) => {
+            let versions = list_cloud_versions(&client, auth, &cloud_game_id).await?;
+            // Newest first, in case no row is flagged as latest
+            versions.iter().find(|v| v.is_latest).or(versions.first()).map(|v| v.id.clone())
+        }
+        None => None,
+    };
+    let Some(latest) = latest else {
+        return Ok(PullReport {
+            action: PullAction::NoCloudVersions,
+            version_id: None,
+        });
+    };
+
+    if last_synced_id.as_deref() == Some(latest.as_str()) {
+        return Ok(PullReport {
+            action: PullAction::UpToDate,
+            version_id: Some(latest),
+        });
+    }
+
+    let changed = {
+        let conn = db::open(db_path).map_err(|e| e.to_string())?;
+        changed_since_sync(&conn, game_id)?
+    };
+    if changed {
+        eprintln!("Keeping local saves of {}; they changed since the last sync", slug);
+        return Ok(PullReport {
+            action: PullAction::KeptLocal,
+            version_id: Some(latest),
+        });
+    }
+
+    restore_cloud_version(db_path, game_id, auth, Some(&latest)).await?;
+    Ok(PullReport {
+        action: PullAction::Pulled,
+        version_id: Some(latest),
+    })
+}
+
+/// What starts a game.
+#[derive(Debug, Clone)]
+pub enum LaunchTarget {
+    /// A full command line, e.g. the one Steam passes as `%command%`
+    Command(Vec<String>),
+    Executable(PathBuf),
+    /// Started through `steam://rungameid/<appid>`
+    Steam(u32),
+}
+
+impl LaunchTarget {
+    /// The given command, else the game's executable, else its Steam app.
+    pub fn for_game(game: &LocalGame, command: Option<Vec<String>>) -> Result<Self, String> {
+        if let Some(command) = command.filter(|c| !c.is_empty()) {
+            return Ok(LaunchTarget::Command(command));
+        }
+        if let Some(exe) = game.executable_path.as_deref().filter(|p| !p.trim().is_empty()) {
+            return Ok(LaunchTarget::Executable(PathBuf::from(exe)));
+        }
+        if let Some(app_id) = game.steam_app_id {
+            return Ok(LaunchTarget::Steam(app_id));
+        }
+        Err(format!("No executable or Steam app id set for {}", game.name))
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            LaunchTarget::Command(command) => command.join(" "),
+            LaunchTarget::Executable(path) => path.display().to_string(),
+            LaunchTarget::Steam(app_id) => format!("steam://rungameid/{}", app_id),
+        }
+    }
+}
+
+/// A started game, to wait on.
+pub enum RunningGame {
+    Child(Child),
+    /// Processes running from this folder belong to the game
+    InstallDir(PathBuf),
+    /// Started, but there's no way to tell when it exits
+    Untracked,
+}
+
+impl RunningGame {
+    /// Whether `wait` blocks until the game exits.
+    pub fn is_tracked(&self) -> bool {
+        !matches!(self, RunningGame::Untracked)
+    }
+
+    /// Blocks until the game has exited.
+    pub fn wait(self) {
+        match self {
+            RunningGame::Child(mut child) => {
+                let _ = child.wait();
+            }
+            RunningGame::InstallDir(dir) => wait_for_processes(&dir),
+            RunningGame::Untracked => {}
+        }
+    }
+}
+
+pub fn start(target: &LaunchTarget) -> Result<RunningGame, String> {
+    match target {
+        LaunchTarget::Command(command) => {
+            let child = Command::new(&command[0])
+                .args(&command[1..])
+                .spawn()
+                .map_err(|e| format!("Failed to start {}: {}", command[0], e))?;
+            Ok(RunningGame::Child(child))
+        }
+        LaunchTarget::Executable(path) => {
+            let mut cmd = Command::new(path);
+            if let Some(dir) = path.parent() {
+                // Many games load their data relative to the working directory
+                cmd.current_dir(dir);
+            }
+            let child = cmd
+                .spawn()
+                .map_err(|e| format!("Failed to start {}: {}", path.display(), e))?;
+            Ok(RunningGame::Child(child))
+        }
+        LaunchTarget::Steam(app_id) => {
+            open_url(&format!("steam://rungameid/{}", app_id))?;
+            Ok(steam_install_dir(*app_id).map_or(RunningGame::Untracked, RunningGame::InstallDir))
+        }
+    }
+}
+
+fn steam_install_dir(app_id: u32) -> Option<PathBuf> {
+    let root = find_steam_root()?;
+    steam_library::library_folders(&root)
+        .iter()
+        .find_map(|library| steam_library::read_app_manifest(library, app_id))
+        .map(|app| app.install_dir)
+        .filter(|dir| dir.is_dir())
+}
+
+fn open_url(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "start", "", url]);
+        cmd
+    };
+    #[cfg(target_os = "macos")]
+    let mut cmd = {
+        let mut cmd = Command::new("open");
+        cmd.arg(url);
+        cmd
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut cmd = {
+        let mut cmd = Command::new("xdg-open");
+        cmd.arg(url);
+        cmd
+    };
+    cmd.spawn().map_err(|e| format!("Failed to open {}: {}", url, e))?;
+    Ok(())
+}
+
+fn game_running(sys: &mut System, dir: &Path) -> bool {
+    sys.refresh_processes();
+    sys.processes()
+        .values()
+        .any(|p| p.exe().is_some_and(|exe| exe.starts_with(dir)))
+}
+
+/// Waits for a process from `dir` to appear, then for all of them to exit.
+fn wait_for_processes(dir: &Path) {
+    let mut sys = System::new();
+    let started = Instant::now();
+    while !game_running(&mut sys, dir) {
+        if started.elapsed() > STEAM_START_TIMEOUT {
+            eprintln!("No process from {} showed up", dir.display());
+            return;
+        }
+        std::thread::sleep(PROCESS_POLL);
+    }
+    while game_running(&mut sys, dir) {
+        std::thread::sleep(PROCESS_POLL);
+    }
+}
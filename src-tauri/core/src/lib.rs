@@ -0,0 +1,18 @@
+//! Everything Sync Saves does that doesn't need a window: the local database,
+//! save locations, archives, the file watcher and the cloud sync engine. The
+//! Tauri commands and the CLI are thin layers over this crate.
+
+pub mod db;
+pub mod device;
+pub mod games;
+pub mod launch;
+pub mod maintenance;
+pub mod notifications;
+pub mod paths;
+pub mod queue;
+pub mod services;
+pub mod session;
+pub mod settings;
+pub mod sync;
+pub mod utils;
+pub mod versions;
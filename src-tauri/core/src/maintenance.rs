@@ -0,0 +1,171 @@
+use crate::db;
+use crate::games::delete_analyses;
+use crate::paths::AppPaths;
+use crate::queue;
+use crate::services::compression;
+use crate::sync::{list_cloud_version_ids, AuthConfig};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// Temp files younger than this may belong to a sync or analysis still running.
+const STALE_AFTER: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Serialize, Debug, Default)]
+pub struct GcReport {
+    pub analyses_removed: u32,
+    pub temp_files_removed: u32,
+    pub bytes_freed: u64,
+    /// Whether analyses were checked against the cloud's versions
+    pub cloud_checked: bool,
+}
+
+/// Removes analyses whose game is gone, for rows written while foreign keys were off.
+fn remove_orphan_analyses(conn: &Connection) -> rusqlite::Result<u32> {
+    conn.execute(
+        "DELETE FROM version_analysis
+         WHERE game_id IS NOT NULL AND game_id NOT IN (SELECT id FROM games_cache)",
+        [],
+    )
+    .map(|n| n as u32)
+}
+
+/// Removes analyses of versions that are neither in the cloud nor a game's last synced version.
+fn remove_unknown_analyses(conn: &Connection, cloud_ids: &HashSet<String>) -> Result<u32, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT version_id FROM version_analysis
+             WHERE version_id NOT IN (SELECT last_synced_id FROM games_cache WHERE last_synced_id IS NOT NULL)",
+        )
+        .map_err(|e| e.to_string())?;
+    let unknown: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .filter(|id: &String| !cloud_ids.contains(id))
+        .collect();
+
+    delete_analyses(conn, &unknown).map_err(|e| e.to_string())
+}
+
+fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .map(|age| age > STALE_AFTER)
+        .unwrap_or(false)
+}
+
+fn remove_if_stale(path: &Path, report: &mut GcReport) {
+    if !path.exists() || !is_stale(path) {
+        return;
+    }
+
+    let size: u64 = WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum();
+
+    let removed = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    if removed.is_ok() {
+        report.temp_files_removed += 1;
+        report.bytes_freed += size;
+    }
+}
+
+fn children(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+/// Archives left behind by failed syncs and restores, and abandoned analysis sandboxes.
+fn clean_temp_files(paths: &AppPaths, conn: &Connection, report: &mut GcReport) -> Result<(), String> {
+    for path in children(&compression::temp_zip_dir()) {
+        remove_if_stale(&path, report);
+    }
+
+    // Older builds wrote archives straight into the temp dir
+    let mut stmt = conn.prepare("SELECT slug FROM games_cache").map_err(|e| e.to_string())?;
+    let slugs: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    let temp = std::env::temp_dir();
+    for slug in slugs {
+        remove_if_stale(&temp.join(format!("{}.zip", slug)), report);
+        remove_if_stale(&temp.join(format!("{}_restore.zip", slug)), report);
+    }
+
+    for path in children(&paths.analysis_dir()) {
+        remove_if_stale(&path, report);
+    }
+
+    // Queued archives of deleted games or discarded uploads
+    let mut stmt = conn
+        .prepare("SELECT file_path FROM sync_queue WHERE file_path IS NOT NULL")
+        .map_err(|e| e.to_string())?;
+    let queued: HashSet<PathBuf> = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .map(PathBuf::from)
+        .collect();
+    for path in children(&queue::archive_dir(&paths.db_path())) {
+        if !queued.contains(&path) {
+            remove_if_stale(&path, report);
+        }
+    }
+    Ok(())
+}
+
+/// The part of the cleanup that doesn't need the cloud; also run at startup.
+pub fn cleanup_local(paths: &AppPaths) -> Result<GcReport, String> {
+    let conn = db::open(&paths.db_path()).map_err(|e| e.to_string())?;
+    let mut report = GcReport {
+        analyses_removed: remove_orphan_analyses(&conn).map_err(|e| e.to_string())?,
+        ..Default::default()
+    };
+    clean_temp_files(paths, &conn, &mut report)?;
+    Ok(report)
+}
+
+/// Cleans up local data: analyses of deleted games and of versions no longer
+/// in the cloud (when signed in), plus stale temp archives and sandboxes.
+pub async fn collect_garbage(paths: &AppPaths, auth: Option<&AuthConfig>) -> Result<GcReport, String> {
+    let local = paths.clone();
+    let mut report = tokio::task::spawn_blocking(move || cleanup_local(&local))
+        .await
+        .map_err(|e| format!("Cleanup failed: {}", e))??;
+
+    if let Some(auth) = auth {
+        // Without the cloud's list every unknown analysis would look orphaned, so skip on errors
+        match list_cloud_version_ids(&reqwest::Client::new(), auth).await {
+            Ok(cloud_ids) => {
+                let conn = db::open(&paths.db_path()).map_err(|e| e.to_string())?;
+                report.analyses_removed += remove_unknown_analyses(&conn, &cloud_ids)?;
+                report.cloud_checked = true;
+            }
+            Err(e) => eprintln!("Skipping cloud check during cleanup: {}", e),
+        }
+    }
+
+    eprintln!(
+        "Cleanup removed {} analyses and {} temp files ({} bytes)",
+        report.analyses_removed, report.temp_files_removed, report.bytes_freed
+    );
+    Ok(report)
+}
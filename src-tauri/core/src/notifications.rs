@@ -0,0 +1,101 @@
+//! Which sync outcomes are worth a desktop notification. Notices are rate
+//! limited per game: uploads inside the interval are grouped into the next
+//! notice, and failures only show once they repeat.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Uploads of one game closer together than this are grouped
+pub const UPLOAD_INTERVAL: Duration = Duration::from_secs(5 * 60);
+pub const CONFLICT_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// Failures in a row before the first notice
+pub const FAILURE_THRESHOLD: u32 = 3;
+/// How often a game that keeps failing is reported again
+pub const FAILURE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Clone)]
+pub enum SyncEvent {
+    Uploaded,
+    /// A cloud version was restored over the local saves
+    Restored { label: Option<String> },
+    /// The cloud has a newer version but the local saves changed too
+    Conflict,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notice {
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Default)]
+struct GameState {
+    last_upload: Option<Instant>,
+    grouped_uploads: u32,
+    last_conflict: Option<Instant>,
+    failures: u32,
+    last_failure: Option<Instant>,
+}
+
+fn elapsed(since: Option<Instant>, now: Instant, interval: Duration) -> bool {
+    since.map_or(true, |t| now.saturating_duration_since(t) >= interval)
+}
+
+#[derive(Default)]
+pub struct Throttle {
+    games: HashMap<String, GameState>,
+}
+
+impl Throttle {
+    /// The notice to show for `event`, if any.
+    pub fn notice(&mut self, game_id: &str, game_name: &str, event: &SyncEvent, now: Instant) -> Option<Notice> {
+        let state = self.games.entry(game_id.to_string()).or_default();
+        let notice = |body: String| {
+            Some(Notice {
+                title: game_name.to_string(),
+                body,
+            })
+        };
+
+        match event {
+            SyncEvent::Uploaded => {
+                state.failures = 0;
+                if !elapsed(state.last_upload, now, UPLOAD_INTERVAL) {
+                    state.grouped_uploads += 1;
+                    return None;
+                }
+                let count = state.grouped_uploads + 1;
+                state.last_upload = Some(now);
+                state.grouped_uploads = 0;
+                if count == 1 {
+                    notice("Saves uploaded to the cloud".to_string())
+                } else {
+                    notice(format!("{} save versions uploaded to the cloud", count))
+                }
+            }
+            SyncEvent::Restored { label } => {
+                state.failures = 0;
+                match label {
+                    Some(label) => notice(format!("Restored \"{}\" from the cloud", label)),
+                    None => notice("Restored saves from the cloud".to_string()),
+                }
+            }
+            SyncEvent::Conflict => {
+                if !elapsed(state.last_conflict, now, CONFLICT_INTERVAL) {
+                    return None;
+                }
+                state.last_conflict = Some(now);
+                notice("The cloud has a newer save, but the local saves changed too. Kept the local saves".to_string())
+            }
+            SyncEvent::Failed(error) => {
+                state.failures += 1;
+                if state.failures < FAILURE_THRESHOLD || !elapsed(state.last_failure, now, FAILURE_INTERVAL) {
+                    return None;
+                }
+                state.last_failure = Some(now);
+                notice(format!("Sync failed {} times in a row: {}", state.failures, error))
+            }
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+/// Bundle identifier from tauri.conf.json; Tauri keeps app data in folders named after it.
+pub const APP_IDENTIFIER: &str = "com.sync.saves.app";
+const DB_FILE: &str = "sync_saves.db";
+
+/// Where the app keeps its database and scratch files.
+#[derive(Debug, Clone)]
+pub struct AppPaths {
+    pub data_dir: PathBuf,
+    pub cache_dir: PathBuf,
+}
+
+impl AppPaths {
+    pub fn new(data_dir: PathBuf, cache_dir: PathBuf) -> Self {
+        Self { data_dir, cache_dir }
+    }
+
+    /// The folders the desktop app uses, resolved the way Tauri does, for tools running without it.
+    pub fn default_dirs() -> Option<Self> {
+        Some(Self::new(
+            dirs::data_dir()?.join(APP_IDENTIFIER),
+            dirs::cache_dir()?.join(APP_IDENTIFIER),
+        ))
+    }
+
+    pub fn db_path(&self) -> PathBuf {
+        self.data_dir.join(DB_FILE)
+    }
+
+    /// Sandboxes for analysis scripts, one folder per run.
+    pub fn analysis_dir(&self) -> PathBuf {
+        self.cache_dir.join("analysis")
+    }
+}
@@ -0,0 +1,289 @@
+//! Uploads waiting for the network. A sync that can't reach the backend keeps
+//! its archive in `sync_queue`, and [`flush`] sends them oldest first once the
+//! backend answers again. Entries that keep failing are set aside, archive
+//! included, until the user retries or discards them.
+
+use crate::db;
+use crate::sync::{self, Archive, AuthConfig, SyncResult};
+use crate::versions::VersionMeta;
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// How long the connectivity probe waits for an answer
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Failed uploads, with the backend reachable, before an entry is set aside
+pub const MAX_RETRIES: i32 = 5;
+/// Message of syncs queued behind entries that were set aside
+pub const HELD_BY_FAILED: &str = "Queued behind uploads that failed; retry or discard them";
+
+#[derive(Debug)]
+pub struct QueuedSync {
+    pub game_id: String,
+    pub result: Result<SyncResult, String>,
+}
+
+#[derive(Debug, Default)]
+pub struct FlushReport {
+    /// Every upload attempted, in order
+    pub results: Vec<QueuedSync>,
+    /// The backend couldn't be reached, so the rest is still queued
+    pub offline: bool,
+    /// Entries still pending
+    pub remaining: u32,
+    /// Entries set aside by this flush after [`MAX_RETRIES`] failures
+    pub given_up: u32,
+}
+
+struct Entry {
+    id: i64,
+    game_id: String,
+    file_path: String,
+    metadata: Option<String>,
+    retry_count: i32,
+}
+
+/// Where queued archives are kept, next to the database.
+pub fn archive_dir(db_path: &Path) -> PathBuf {
+    db_path.parent().unwrap_or(Path::new(".")).join("queue")
+}
+
+/// Whether the backend at `url` answers at all; any HTTP status counts.
+pub async fn is_online(url: &str) -> bool {
+    let Ok(client) = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() else {
+        return false;
+    };
+    client.get(format!("{}/auth/v1/health", url)).send().await.is_ok()
+}
+
+/// Pending uploads, of `game_id` or of every game, counting those being sent.
+pub fn pending_count(conn: &Connection, game_id: Option<&str>) -> Result<u32, String> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sync_queue WHERE status IN ('pending', 'processing') AND (?1 IS NULL OR game_id = ?1)",
+        [game_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Moves the archive into the queue, behind the game's earlier entries.
+pub fn enqueue(
+    db_path: &Path,
+    conn: &Connection,
+    game_id: &str,
+    archive: &Archive,
+    metadata: Option<&VersionMeta>,
+) -> Result<(), String> {
+    let dir = archive_dir(db_path);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.zip", Uuid::new_v4()));
+    // The temp dir may be on another drive
+    if fs::rename(&archive.path, &path).is_err() {
+        fs::copy(&archive.path, &path).map_err(|e| format!("Failed to queue archive: {}", e))?;
+        let _ = fs::remove_file(&archive.path);
+    }
+
+    let metadata = metadata
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO sync_queue (game_id, action, status, file_path, metadata)
+         VALUES (?1, 'upload', 'pending', ?2, ?3)",
+        params![game_id, path.to_string_lossy(), metadata],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Returns entries left claimed by a flush that never finished to the queue.
+/// Only call this before any flush can be running, e.g. at startup.
+pub fn release_claims(conn: &Connection) -> Result<(), String> {
+    conn.execute("UPDATE sync_queue SET status = 'pending' WHERE status = 'processing'", [])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Uploads set aside after failing [`MAX_RETRIES`] times, of `game_id` or of every game.
+pub fn failed_count(conn: &Connection, game_id: Option<&str>) -> Result<u32, String> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sync_queue WHERE status = 'failed' AND (?1 IS NULL OR game_id = ?1)",
+        [game_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Puts the failed entries back in the queue with a fresh retry budget.
+/// Returns how many were.
+pub fn retry_failed(conn: &Connection, game_id: Option<&str>) -> Result<u32, String> {
+    conn.execute(
+        "UPDATE sync_queue SET status = 'pending', retry_count = 0
+         WHERE status = 'failed' AND (?1 IS NULL OR game_id = ?1)",
+        [game_id],
+    )
+    .map(|n| n as u32)
+    .map_err(|e| e.to_string())
+}
+
+/// Drops the failed entries and their archives; those saves won't reach the cloud.
+/// Returns how many were.
+pub fn discard_failed(conn: &Connection, game_id: Option<&str>) -> Result<u32, String> {
+    let mut stmt = conn
+        .prepare("SELECT file_path FROM sync_queue WHERE status = 'failed' AND (?1 IS NULL OR game_id = ?1)")
+        .map_err(|e| e.to_string())?;
+    let paths = stmt
+        .query_map([game_id], |row| row.get::<_, Option<String>>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let n = conn
+        .execute(
+            "DELETE FROM sync_queue WHERE status = 'failed' AND (?1 IS NULL OR game_id = ?1)",
+            [game_id],
+        )
+        .map_err(|e| e.to_string())?;
+    for path in paths.into_iter().flatten() {
+        let _ = fs::remove_file(path);
+    }
+    Ok(n as u32)
+}
+
+/// Games with entries set aside; their later entries wait behind them.
+fn failed_games(conn: &Connection, game_id: Option<&str>) -> Result<HashSet<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT game_id FROM sync_queue WHERE status = 'failed' AND (?1 IS NULL OR game_id = ?1)")
+        .map_err(|e| e.to_string())?;
+    let games = stmt
+        .query_map([game_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<HashSet<String>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(games)
+}
+
+/// Marks the entry as being sent; false if another flush got to it first.
+fn claim(conn: &Connection, id: i64) -> Result<bool, String> {
+    conn.execute(
+        "UPDATE sync_queue SET status = 'processing' WHERE id = ?1 AND status = 'pending'",
+        [id],
+    )
+    .map(|n| n == 1)
+    .map_err(|e| e.to_string())
+}
+
+fn unclaim(conn: &Connection, id: i64) -> Result<(), String> {
+    conn.execute("UPDATE sync_queue SET status = 'pending' WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn pending(conn: &Connection, game_id: Option<&str>) -> Result<Vec<Entry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, game_id, file_path, metadata, retry_count FROM sync_queue
+             WHERE status IN ('pending', 'processing') AND action = 'upload' AND (?1 IS NULL OR game_id = ?1)
+             ORDER BY id",
+        )
+        .map_err(|e| e.to_string())?;
+    let entries = stmt
+        .query_map([game_id], |row| {
+            Ok(Entry {
+                id: row.get(0)?,
+                game_id: row.get(1)?,
+                file_path: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                metadata: row.get(3)?,
+                retry_count: row.get::<_, Option<i32>>(4)?.unwrap_or(0),
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(entries)
+}
+
+async fn upload(db_path: &Path, entry: &Entry, auth: &AuthConfig) -> Result<SyncResult, String> {
+    let archive = Archive::from_file(PathBuf::from(&entry.file_path))
+        .map_err(|e| format!("Queued archive unreadable: {}", e))?;
+    let metadata: Option<VersionMeta> = entry
+        .metadata
+        .as_deref()
+        .map(serde_json::from_str)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    sync::upload_archive(db_path, &entry.game_id, &archive, auth, metadata.as_ref(), Instant::now()).await
+}
+
+/// Records a failed attempt; past [`MAX_RETRIES`] the entry is set aside,
+/// keeping its archive. Returns whether it's still pending.
+fn record_failure(conn: &Connection, entry: &Entry) -> Result<bool, String> {
+    let retries = entry.retry_count + 1;
+    let status = if retries >= MAX_RETRIES { "failed" } else { "pending" };
+    conn.execute(
+        "UPDATE sync_queue SET retry_count = ?1, status = ?2 WHERE id = ?3",
+        params![retries, status, entry.id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(status == "pending")
+}
+
+/// Uploads the pending entries, of `game_id` or of every game, oldest first.
+/// A game's later entries wait while an earlier one fails, is set aside or the
+/// game is paused, so its versions reach the cloud in the order they were made.
+pub async fn flush(db_path: &Path, auth: &AuthConfig, game_id: Option<&str>) -> Result<FlushReport, String> {
+    let conn = db::open(db_path).map_err(|e| e.to_string())?;
+    let mut report = FlushReport::default();
+
+    let entries = pending(&conn, game_id)?;
+    if entries.is_empty() {
+        return Ok(report);
+    }
+    if !is_online(&auth.url).await {
+        report.offline = true;
+        report.remaining = entries.len() as u32;
+        return Ok(report);
+    }
+
+    let mut held = failed_games(&conn, game_id)?;
+    for entry in entries {
+        if held.contains(&entry.game_id) {
+            continue;
+        }
+        // Another flush sending the entry keeps the game's later ones waiting too
+        if !matches!(sync::sync_blocked(&conn, &entry.game_id), Ok(None)) || !claim(&conn, entry.id)? {
+            held.insert(entry.game_id);
+            continue;
+        }
+
+        let result = upload(db_path, &entry, auth).await;
+        match &result {
+            Ok(_) => {
+                conn.execute("DELETE FROM sync_queue WHERE id = ?1", [entry.id])
+                    .map_err(|e| e.to_string())?;
+                let _ = fs::remove_file(&entry.file_path);
+            }
+            Err(_) if !is_online(&auth.url).await => {
+                unclaim(&conn, entry.id)?;
+                report.offline = true;
+                break;
+            }
+            Err(e) => {
+                eprintln!("Queued sync of {} failed: {}", entry.game_id, e);
+                if !record_failure(&conn, &entry)? {
+                    report.given_up += 1;
+                }
+                held.insert(entry.game_id.clone());
+            }
+        }
+        report.results.push(QueuedSync {
+            game_id: entry.game_id,
+            result,
+        });
+    }
+
+    report.remaining = pending_count(&conn, game_id)?;
+    Ok(report)
+}
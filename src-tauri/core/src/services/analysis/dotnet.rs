@@ -0,0 +1,144 @@
+//! Saves written with .NET's `BinaryFormatter`, including Hollow Knight's
+//! encrypted `user*.dat` files.
+
+use super::{strip_bom, Progress, SaveDecoder};
+use aes::cipher::{generic_array::GenericArray, BlockDecrypt, KeyInit};
+use aes::Aes256;
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::Value;
+use std::path::Path;
+
+/// `SerializationHeaderRecord` (17 bytes) followed by a `BinaryObjectString` record.
+const HEADER_LEN: usize = 17;
+const RECORD_OBJECT_STRING: u8 = 0x06;
+const HOLLOW_KNIGHT_KEY: &[u8; 32] = b"UKu52ePUBwetZ9wNX88o54dnfKRu0T1l";
+
+fn has_serialization_header(bytes: &[u8]) -> bool {
+    bytes.len() > HEADER_LEN
+        && bytes[0] == 0x00
+        && bytes[9..13] == [1, 0, 0, 0]
+        && bytes[13..17] == [0, 0, 0, 0]
+}
+
+fn read_7bit_varint(bytes: &[u8], mut offset: usize) -> Result<(usize, usize), String> {
+    let mut value = 0usize;
+    let mut shift = 0;
+    loop {
+        let b = *bytes.get(offset).ok_or("Unexpected end of file while reading length")?;
+        offset += 1;
+        value |= ((b & 0x7f) as usize) << shift;
+        if b & 0x80 == 0 {
+            return Ok((value, offset));
+        }
+        shift += 7;
+        if shift > 35 {
+            return Err("Length prefix too large".to_string());
+        }
+    }
+}
+
+/// Returns the string stored by a `BinaryFormatter` stream whose root object is a string.
+fn read_root_string(bytes: &[u8]) -> Result<&[u8], String> {
+    if !has_serialization_header(bytes) {
+        return Err("Missing BinaryFormatter header".to_string());
+    }
+    if bytes[HEADER_LEN] != RECORD_OBJECT_STRING {
+        return Err("Root object is not a string".to_string());
+    }
+    // Record type, then a 4-byte object id, then the length-prefixed string
+    let (len, start) = read_7bit_varint(bytes, HEADER_LEN + 5)?;
+    bytes
+        .get(start..start + len)
+        .ok_or_else(|| "Truncated string record".to_string())
+}
+
+fn parse_string_payload(payload: &[u8]) -> Value {
+    let text = String::from_utf8_lossy(payload);
+    serde_json::from_str(strip_bom(&text)).unwrap_or_else(|_| Value::String(text.to_string()))
+}
+
+/// Any `BinaryFormatter` file whose root is a string, decoded as JSON when it is JSON.
+pub struct BinaryFormatterString;
+
+impl SaveDecoder for BinaryFormatterString {
+    fn id(&self) -> &'static str {
+        "dotnet-string"
+    }
+
+    fn name(&self) -> &'static str {
+        ".NET BinaryFormatter string"
+    }
+
+    fn detect(&self, _path: &Path, bytes: &[u8]) -> bool {
+        read_root_string(bytes).is_ok()
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, String> {
+        Ok(parse_string_payload(read_root_string(bytes)?))
+    }
+}
+
+/// Hollow Knight: a `BinaryFormatter` string holding base64 of AES-256-ECB
+/// encrypted, PKCS7-padded JSON. Plain JSON saves (as written by save editors)
+/// are accepted too.
+pub struct HollowKnight;
+
+impl HollowKnight {
+    fn decrypt(payload: &[u8]) -> Result<Vec<u8>, String> {
+        let mut data = general_purpose::STANDARD
+            .decode(payload)
+            .map_err(|e| format!("Invalid save payload: {}", e))?;
+        if data.is_empty() || data.len() % 16 != 0 {
+            return Err("Encrypted payload is not a whole number of blocks".to_string());
+        }
+
+        let cipher = Aes256::new(GenericArray::from_slice(HOLLOW_KNIGHT_KEY));
+        for block in data.chunks_exact_mut(16) {
+            cipher.decrypt_block(GenericArray::from_mut_slice(block));
+        }
+
+        let pad = *data.last().unwrap_or(&0) as usize;
+        if pad == 0 || pad > 16 || pad > data.len() {
+            return Err("Invalid padding, wrong key or not a Hollow Knight save".to_string());
+        }
+        data.truncate(data.len() - pad);
+        Ok(data)
+    }
+}
+
+impl SaveDecoder for HollowKnight {
+    fn id(&self) -> &'static str {
+        "hollow-knight"
+    }
+
+    fn name(&self) -> &'static str {
+        "Hollow Knight"
+    }
+
+    fn detect(&self, _path: &Path, bytes: &[u8]) -> bool {
+        read_root_string(bytes)
+            .map(|payload| {
+                payload
+                    .iter()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'='))
+            })
+            .unwrap_or(false)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, String> {
+        let plain = match read_root_string(bytes) {
+            Ok(payload) => Self::decrypt(payload)?,
+            Err(_) => bytes.to_vec(),
+        };
+        let text = String::from_utf8_lossy(&plain);
+        serde_json::from_str(strip_bom(&text)).map_err(|e| format!("Decrypted save is not JSON: {}", e))
+    }
+
+    fn progress(&self, decoded: &Value) -> Progress {
+        let player = &decoded["playerData"];
+        Progress {
+            completion_percentage: player["completionPercentage"].as_f64(),
+            play_time_seconds: player["playTime"].as_f64().map(|t| t.round() as i64),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Native save decoders used by `builtin:<id>` analyzers.
+//!
+//! Each decoder turns a save file into JSON that the analysis runner can pick
+//! fields from. `builtin:auto` tries every decoder's `detect` in order.
+
+mod dotnet;
+mod select;
+mod structured;
+mod unity;
+
+pub use select::select;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+/// Progress values a decoder knows how to read from its own format.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Progress {
+    pub completion_percentage: Option<f64>,
+    pub play_time_seconds: Option<i64>,
+}
+
+pub trait SaveDecoder: Send + Sync {
+    /// Used in `builtin:<id>`
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    /// Whether the file looks like this decoder's format
+    fn detect(&self, path: &Path, bytes: &[u8]) -> bool;
+    fn decode(&self, bytes: &[u8]) -> Result<Value, String>;
+    fn progress(&self, _decoded: &Value) -> Progress {
+        Progress::default()
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct DecoderInfo {
+    pub id: String,
+    pub name: String,
+}
+
+/// Registered decoders, most specific first since `auto` takes the first match.
+fn decoders() -> Vec<Box<dyn SaveDecoder>> {
+    vec![
+        Box::new(dotnet::HollowKnight),
+        Box::new(dotnet::BinaryFormatterString),
+        Box::new(unity::PlayerPrefs),
+        Box::new(structured::Json),
+        Box::new(structured::Xml),
+        Box::new(structured::Ini),
+    ]
+}
+
+pub fn list_decoders() -> Vec<DecoderInfo> {
+    decoders()
+        .iter()
+        .map(|d| DecoderInfo {
+            id: d.id().to_string(),
+            name: d.name().to_string(),
+        })
+        .collect()
+}
+
+fn pick_decoder(id: &str, path: &Path, bytes: &[u8]) -> Result<Box<dyn SaveDecoder>, String> {
+    let mut all = decoders().into_iter();
+    if id == "auto" {
+        all.find(|d| d.detect(path, bytes))
+            .ok_or_else(|| format!("No built-in decoder recognizes {}", path.display()))
+    } else {
+        all.find(|d| d.id() == id)
+            .ok_or_else(|| format!("Unknown built-in analyzer: {}", id))
+    }
+}
+
+/// Decodes a save file with the decoder `id` (or `auto`) and returns the raw JSON.
+pub fn decode_file(id: &str, path: &Path) -> Result<Value, String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    pick_decoder(id, path, &bytes)?.decode(&bytes)
+}
+
+/// Decodes a save file into the analysis runner's output schema, with the
+/// decoder's known progress fields filled in.
+pub fn run(id: &str, path: &Path) -> Result<Value, String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let decoder = pick_decoder(id, path, &bytes)?;
+    let data = decoder.decode(&bytes)?;
+    let progress = decoder.progress(&data);
+
+    Ok(json!({
+        "completion_percentage": progress.completion_percentage,
+        "play_time_seconds": progress.play_time_seconds,
+        "data": data,
+    }))
+}
+
+/// Parses an INI/XML text value into a JSON bool or number when it looks like one.
+fn scalar(raw: &str) -> Value {
+    let s = raw.trim();
+    if s.eq_ignore_ascii_case("true") {
+        return Value::Bool(true);
+    }
+    if s.eq_ignore_ascii_case("false") {
+        return Value::Bool(false);
+    }
+    if let Ok(i) = s.parse::<i64>() {
+        return Value::from(i);
+    }
+    if let Some(f) = s.parse::<f64>().ok().filter(|f| f.is_finite()) {
+        return Value::from(f);
+    }
+    Value::String(s.to_string())
+}
+
+fn strip_bom(text: &str) -> &str {
+    text.trim_start_matches('\u{feff}')
+}
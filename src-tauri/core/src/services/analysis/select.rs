@@ -0,0 +1,100 @@
+//! A small JSONPath subset for picking fields out of decoded saves:
+//! `$.playerData.geo`, `scenes[0]`, `items['Key.With.Dots']`, `bosses[*].defeated`,
+//! `stats.*` and `list[-1]`. The leading `$` is optional.
+
+use serde_json::Value;
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(i64),
+    Wildcard,
+}
+
+fn parse(path: &str) -> Result<Vec<Segment>, String> {
+    let path = path.trim();
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let chars: Vec<char> = path.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                i += 1;
+                if chars.get(i) == Some(&'*') {
+                    segments.push(Segment::Wildcard);
+                    i += 1;
+                }
+            }
+            '[' => {
+                let close = chars[i..]
+                    .iter()
+                    .position(|c| *c == ']')
+                    .map(|p| p + i)
+                    .ok_or_else(|| format!("Unclosed '[' in {}", path))?;
+                let inner: String = chars[i + 1..close].iter().collect();
+                let inner = inner.trim();
+
+                let segment = if inner == "*" {
+                    Segment::Wildcard
+                } else if let Some(quoted) = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+                {
+                    Segment::Key(quoted.to_string())
+                } else {
+                    Segment::Index(inner.parse().map_err(|_| format!("Invalid index '{}' in {}", inner, path))?)
+                };
+                segments.push(segment);
+                i = close + 1;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                    i += 1;
+                }
+                segments.push(Segment::Key(chars[start..i].iter().collect()));
+            }
+        }
+    }
+
+    Ok(segments)
+}
+
+fn step<'a>(node: &'a Value, segment: &Segment, out: &mut Vec<&'a Value>) {
+    match (segment, node) {
+        (Segment::Key(key), Value::Object(map)) => out.extend(map.get(key)),
+        (Segment::Index(index), Value::Array(items)) => {
+            let index = if *index < 0 { items.len() as i64 + index } else { *index };
+            if index >= 0 {
+                out.extend(items.get(index as usize));
+            }
+        }
+        (Segment::Wildcard, Value::Object(map)) => out.extend(map.values()),
+        (Segment::Wildcard, Value::Array(items)) => out.extend(items.iter()),
+        _ => {}
+    }
+}
+
+/// Selects `path` from `value`. Paths with a wildcard return an array of every
+/// match; other paths return the single value, or `None` when it's missing.
+pub fn select(value: &Value, path: &str) -> Result<Option<Value>, String> {
+    let segments = parse(path)?;
+    let has_wildcard = segments.contains(&Segment::Wildcard);
+
+    let mut current = vec![value];
+    for segment in &segments {
+        let mut next = Vec::new();
+        for node in current {
+            step(node, segment, &mut next);
+        }
+        current = next;
+    }
+
+    if has_wildcard {
+        return Ok(Some(Value::Array(current.into_iter().cloned().collect())));
+    }
+    Ok(current.first().map(|v| (*v).clone()))
+}
@@ -0,0 +1,151 @@
+//! Plain-text formats: JSON, XML and INI.
+
+use super::{scalar, strip_bom, SaveDecoder};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn text(bytes: &[u8]) -> Result<&str, String> {
+    std::str::from_utf8(bytes)
+        .map(strip_bom)
+        .map_err(|_| "Save is not UTF-8 text".to_string())
+}
+
+pub struct Json;
+
+impl SaveDecoder for Json {
+    fn id(&self) -> &'static str {
+        "json"
+    }
+
+    fn name(&self) -> &'static str {
+        "JSON"
+    }
+
+    fn detect(&self, _path: &Path, bytes: &[u8]) -> bool {
+        self.decode(bytes).is_ok()
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, String> {
+        serde_json::from_str(text(bytes)?).map_err(|e| format!("Save is not valid JSON: {}", e))
+    }
+}
+
+/// Elements become objects keyed by child tag (arrays when a tag repeats),
+/// attributes become `@name` and mixed text becomes `#text`.
+pub struct Xml;
+
+fn xml_node(node: roxmltree::Node) -> Value {
+    let mut obj = Map::new();
+    for attr in node.attributes() {
+        obj.insert(format!("@{}", attr.name()), scalar(attr.value()));
+    }
+
+    let mut text = String::new();
+    for child in node.children() {
+        if child.is_element() {
+            let name = child.tag_name().name().to_string();
+            let value = xml_node(child);
+            match obj.get_mut(&name) {
+                Some(Value::Array(items)) => items.push(value),
+                Some(existing) => {
+                    let first = existing.take();
+                    *existing = Value::Array(vec![first, value]);
+                }
+                None => {
+                    obj.insert(name, value);
+                }
+            }
+        } else if child.is_text() {
+            text.push_str(child.text().unwrap_or_default());
+        }
+    }
+
+    let text = text.trim();
+    if obj.is_empty() {
+        return if text.is_empty() { Value::Null } else { scalar(text) };
+    }
+    if !text.is_empty() {
+        obj.insert("#text".to_string(), scalar(text));
+    }
+    Value::Object(obj)
+}
+
+impl SaveDecoder for Xml {
+    fn id(&self) -> &'static str {
+        "xml"
+    }
+
+    fn name(&self) -> &'static str {
+        "XML"
+    }
+
+    fn detect(&self, _path: &Path, bytes: &[u8]) -> bool {
+        text(bytes)
+            .map(|t| t.trim_start().starts_with('<'))
+            .unwrap_or(false)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, String> {
+        let doc = roxmltree::Document::parse(text(bytes)?).map_err(|e| format!("Save is not valid XML: {}", e))?;
+        let root = doc.root_element();
+        let mut obj = Map::new();
+        obj.insert(root.tag_name().name().to_string(), xml_node(root));
+        Ok(Value::Object(obj))
+    }
+}
+
+/// Sections become objects; keys before the first section stay at the top level.
+pub struct Ini;
+
+impl SaveDecoder for Ini {
+    fn id(&self) -> &'static str {
+        "ini"
+    }
+
+    fn name(&self) -> &'static str {
+        "INI"
+    }
+
+    fn detect(&self, path: &Path, _bytes: &[u8]) -> bool {
+        matches!(extension(path).as_str(), "ini" | "cfg" | "conf")
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, String> {
+        let mut root = Map::new();
+        let mut section: Option<String> = None;
+
+        for line in text(bytes)?.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let name = name.trim().to_string();
+                root.entry(name.clone()).or_insert_with(|| Value::Object(Map::new()));
+                section = Some(name);
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=').or_else(|| line.split_once(':')) else {
+                continue;
+            };
+            let value = value.trim().trim_matches('"');
+
+            let target = match &section {
+                Some(name) => match root.get_mut(name) {
+                    Some(Value::Object(map)) => map,
+                    _ => continue,
+                },
+                None => &mut root,
+            };
+            target.insert(key.trim().to_string(), scalar(value));
+        }
+
+        Ok(Value::Object(root))
+    }
+}
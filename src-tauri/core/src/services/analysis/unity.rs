@@ -0,0 +1,49 @@
+//! Unity `PlayerPrefs` as stored on Linux (`~/.config/unity3d/<company>/<product>/prefs`).
+//! Windows keeps them in the registry and macOS in a binary plist, neither of
+//! which is a save file this app syncs.
+
+use super::{scalar, strip_bom, SaveDecoder};
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+pub struct PlayerPrefs;
+
+impl SaveDecoder for PlayerPrefs {
+    fn id(&self) -> &'static str {
+        "unity-prefs"
+    }
+
+    fn name(&self) -> &'static str {
+        "Unity PlayerPrefs"
+    }
+
+    fn detect(&self, _path: &Path, bytes: &[u8]) -> bool {
+        let head = &bytes[..bytes.len().min(256)];
+        String::from_utf8_lossy(head).contains("<unity_prefs")
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, String> {
+        let text = std::str::from_utf8(bytes).map_err(|_| "PlayerPrefs file is not UTF-8".to_string())?;
+        let doc = roxmltree::Document::parse(strip_bom(text))
+            .map_err(|e| format!("Invalid PlayerPrefs file: {}", e))?;
+
+        let mut prefs = Map::new();
+        for pref in doc.root_element().children().filter(|n| n.has_tag_name("pref")) {
+            let Some(name) = pref.attribute("name") else { continue };
+            let raw = pref.text().unwrap_or_default().trim();
+
+            let value = match pref.attribute("type") {
+                // Strings are stored base64-encoded
+                Some("string") => general_purpose::STANDARD
+                    .decode(raw)
+                    .map(|b| Value::String(String::from_utf8_lossy(&b).to_string()))
+                    .unwrap_or_else(|_| Value::String(raw.to_string())),
+                _ => scalar(raw),
+            };
+            prefs.insert(name.to_string(), value);
+        }
+
+        Ok(Value::Object(prefs))
+    }
+}
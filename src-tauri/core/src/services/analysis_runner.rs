@@ -0,0 +1,372 @@
+//! Runs a game's save analyzer against a copy of its save files.
+//!
+//! An analyzer is either a script (`custom_script_path`) or a built-in
+//! decoder named `builtin:<id>` (see [`crate::services::analysis`]). Scripts are called with the target save
+//! file as their only argument, from a throwaway working directory that also
+//! serves as their temp dir, and must produce a JSON object in one of these
+//! ways (first match wins):
+//!
+//! 1. writing it to the file named by `SYNC_SAVES_OUTPUT`;
+//! 2. printing `Decoded JSON written to: <path>` (what `hollow.py` does);
+//! 3. printing it to stdout.
+//!
+//! The object may use the reserved keys `completion_percentage`,
+//! `play_time_seconds` and `data`; without `data` the whole object is the data.
+
+use crate::services::analysis;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+pub const BUILTIN_PREFIX: &str = "builtin:";
+pub const DEFAULT_TIMEOUT_SECS: u64 = 60;
+const OUTPUT_ENV: &str = "SYNC_SAVES_OUTPUT";
+const LEGACY_OUTPUT_MARKER: &str = "Decoded JSON written to:";
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// `games_cache.analysis_config`.
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct AnalysisConfig {
+    /// File name or relative path of the save file to analyze
+    pub target_path: String,
+    /// Flattened keys (`a.b.c`) to keep; everything is kept when empty
+    pub tracked_keys: Vec<String>,
+    /// Extra values to store, as output name -> path (`$.playerData.geo`, `bosses[*].name`)
+    pub fields: BTreeMap<String, String>,
+    /// Flattened key or path holding the completion percentage, if the analyzer doesn't report it
+    pub completion_key: Option<String>,
+    /// Flattened key or path holding the play time in seconds
+    pub play_time_key: Option<String>,
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AnalysisOutput {
+    pub analyzer: String,
+    pub target_file: String,
+    /// Flattened tracked values, the shape stored in `version_analysis`
+    pub data: Map<String, Value>,
+    pub completion_percentage: Option<f64>,
+    pub play_time_seconds: Option<i64>,
+    pub duration_ms: u64,
+}
+
+/// Finds the save file to analyze: an exact relative path first, then the
+/// first file whose name contains the target's file name.
+pub fn find_target_file(save_dir: &Path, target_path: &str) -> Option<PathBuf> {
+    let target_path = target_path.trim().replace('\\', "/");
+    if target_path.is_empty() {
+        return None;
+    }
+
+    let exact = save_dir.join(&target_path);
+    if exact.is_file() {
+        return Some(exact);
+    }
+
+    let needle = target_path
+        .rsplit('/')
+        .next()
+        .unwrap_or(&target_path)
+        .to_lowercase();
+
+    let mut matches: Vec<PathBuf> = WalkDir::new(save_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_lowercase();
+            name == needle || name.contains(&needle)
+        })
+        .map(|e| e.into_path())
+        .collect();
+
+    // Exact file name beats partial, then the shortest path
+    matches.sort_by_key(|p| {
+        let name = p.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+        (name != needle, p.components().count())
+    });
+    matches.into_iter().next()
+}
+
+/// Runs `analyzer` on `target` with `sandbox` as working directory and returns its raw JSON.
+pub fn run_analyzer(
+    analyzer: &str,
+    target: &Path,
+    sandbox: &Path,
+    timeout: Duration,
+) -> Result<Value, String> {
+    if let Some(name) = analyzer.strip_prefix(BUILTIN_PREFIX) {
+        return analysis::run(name, target);
+    }
+    run_script(Path::new(analyzer), target, sandbox, timeout)
+}
+
+fn script_command(script: &Path) -> Command {
+    let ext = script
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let mut cmd = match ext.as_str() {
+        "py" => Command::new(if cfg!(windows) { "python" } else { "python3" }),
+        "ps1" => {
+            let mut c = Command::new(if cfg!(windows) { "powershell" } else { "pwsh" });
+            c.args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File"]);
+            c
+        }
+        "js" | "mjs" => Command::new("node"),
+        "sh" => Command::new("sh"),
+        _ => return Command::new(script),
+    };
+    cmd.arg(script);
+    cmd
+}
+
+fn run_script(script: &Path, target: &Path, sandbox: &Path, timeout: Duration) -> Result<Value, String> {
+    if !script.is_file() {
+        return Err(format!("Analysis script not found: {}", script.display()));
+    }
+
+    let output_file = sandbox.join("result.json");
+    let mut cmd = script_command(script);
+    cmd.arg(target)
+        .current_dir(sandbox)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // Only pass through what interpreters need to start; temp files land in the sandbox
+    cmd.env_clear();
+    for key in [
+        "PATH", "PATHEXT", "SYSTEMROOT", "WINDIR", "COMSPEC", "HOME", "USERPROFILE",
+        "APPDATA", "LOCALAPPDATA", "LANG", "LC_ALL",
+    ] {
+        if let Some(value) = std::env::var_os(key) {
+            cmd.env(key, value);
+        }
+    }
+    for key in ["TMP", "TEMP", "TMPDIR"] {
+        cmd.env(key, sandbox);
+    }
+    cmd.env("PYTHONIOENCODING", "utf-8");
+    cmd.env(OUTPUT_ENV, &output_file);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    // Its own process group, so whatever the script starts can be killed with it
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start analysis script: {}", e))?;
+
+    // Drain the pipes on their own threads so a chatty script can't block on a full pipe
+    let stdout_reader = child.stdout.take().map(|mut out| {
+        std::thread::spawn(move || {
+            let mut buf = String::new();
+            let _ = out.read_to_string(&mut buf);
+            buf
+        })
+    });
+    let stderr_reader = child.stderr.take().map(|mut err| {
+        std::thread::spawn(move || {
+            let mut buf = String::new();
+            let _ = err.read_to_string(&mut buf);
+            buf
+        })
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break status,
+            None if started.elapsed() > timeout => {
+                kill_tree(&mut child);
+                return Err(format!("Analysis script timed out after {}s", timeout.as_secs()));
+            }
+            None => std::thread::sleep(Duration::from_millis(50)),
+        }
+    };
+    // Leftover background processes would hold the pipes open
+    #[cfg(unix)]
+    kill_group(child.id());
+
+    let stdout = stdout_reader.and_then(|h| h.join().ok()).unwrap_or_default();
+    let stderr = stderr_reader.and_then(|h| h.join().ok()).unwrap_or_default();
+
+    if !status.success() {
+        let detail = if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() };
+        return Err(format!("Analysis script failed ({}): {}", status, detail));
+    }
+
+    parse_script_output(&stdout, &output_file, sandbox)
+}
+
+#[cfg(unix)]
+fn kill_group(pgid: u32) {
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", pgid)])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// Kills the script along with the processes it started.
+fn kill_tree(child: &mut Child) {
+    #[cfg(unix)]
+    kill_group(child.id());
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        let _ = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &child.id().to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .creation_flags(CREATE_NO_WINDOW)
+            .status();
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// `path`, resolved against the sandbox, if it exists and doesn't leave it.
+fn inside_sandbox(sandbox: &Path, path: &Path) -> Option<PathBuf> {
+    let root = sandbox.canonicalize().ok()?;
+    let path = root.join(path).canonicalize().ok()?;
+    path.starts_with(&root).then_some(path)
+}
+
+fn parse_script_output(stdout: &str, output_file: &Path, sandbox: &Path) -> Result<Value, String> {
+    if output_file.is_file() {
+        let content = fs::read_to_string(output_file).map_err(|e| e.to_string())?;
+        return serde_json::from_str(&content).map_err(|e| format!("Invalid analysis output: {}", e));
+    }
+
+    if let Some(line) = stdout.lines().rev().find(|l| l.contains(LEGACY_OUTPUT_MARKER)) {
+        let path = line
+            .split_once(LEGACY_OUTPUT_MARKER)
+            .map(|(_, p)| p.trim())
+            .unwrap_or_default();
+        // The script names the file, so only one it wrote to its sandbox is read and removed
+        let file = inside_sandbox(sandbox, Path::new(path))
+            .ok_or_else(|| format!("Analysis output {} is missing or outside the sandbox", path))?;
+        let content = fs::read_to_string(&file)
+            .map_err(|e| format!("Failed to read analysis output {}: {}", path, e))?;
+        let _ = fs::remove_file(&file);
+        return serde_json::from_str(&content).map_err(|e| format!("Invalid analysis output: {}", e));
+    }
+
+    // Scripts may log before printing the result; take the last JSON-looking line as a fallback
+    let trimmed = stdout.trim();
+    serde_json::from_str(trimmed)
+        .or_else(|_| {
+            trimmed
+                .lines()
+                .rev()
+                .find(|l| l.trim_start().starts_with('{'))
+                .map(|l| serde_json::from_str(l.trim()))
+                .unwrap_or_else(|| serde_json::from_str("<no output>"))
+        })
+        .map_err(|_| "Analysis script produced no JSON output".to_string())
+}
+
+/// Flattens nested objects into dot-notation keys, like `flattenObject` on the frontend.
+pub fn flatten(value: &Value) -> Map<String, Value> {
+    let mut out = Map::new();
+    flatten_into(value, "", &mut out);
+    out
+}
+
+fn flatten_into(value: &Value, prefix: &str, out: &mut Map<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map {
+                let key = if prefix.is_empty() { k.clone() } else { format!("{}.{}", prefix, k) };
+                flatten_into(v, &key, out);
+            }
+        }
+        _ if !prefix.is_empty() => {
+            out.insert(prefix.to_string(), value.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Applies the output schema and the game's config to an analyzer's raw JSON.
+pub fn build_output(
+    raw: Value,
+    config: &AnalysisConfig,
+    analyzer: &str,
+    target_file: &Path,
+    duration: Duration,
+) -> Result<AnalysisOutput, String> {
+    let Value::Object(mut root) = raw else {
+        return Err("Analysis output must be a JSON object".to_string());
+    };
+
+    let reported_completion = root.get("completion_percentage").and_then(Value::as_f64);
+    let reported_play_time = root.get("play_time_seconds").and_then(as_seconds);
+
+    let data = match root.remove("data") {
+        Some(data @ Value::Object(_)) => data,
+        _ => Value::Object(root),
+    };
+    let flat = flatten(&data);
+
+    // Config keys may be flattened keys or paths into the unflattened data
+    let lookup = |key: &Option<String>| -> Option<Value> {
+        let key = key.as_deref()?;
+        flat.get(key)
+            .cloned()
+            .or_else(|| analysis::select(&data, key).ok().flatten())
+    };
+    let completion_percentage =
+        reported_completion.or_else(|| lookup(&config.completion_key).and_then(|v| v.as_f64()));
+    let play_time_seconds =
+        reported_play_time.or_else(|| lookup(&config.play_time_key).as_ref().and_then(as_seconds));
+
+    let keep_all = config.tracked_keys.is_empty() && config.fields.is_empty();
+    let mut selected: Map<String, Value> = if keep_all {
+        flat
+    } else {
+        config
+            .tracked_keys
+            .iter()
+            .filter_map(|k| flat.get(k).map(|v| (k.clone(), v.clone())))
+            .collect()
+    };
+    for (name, path) in &config.fields {
+        if let Some(value) = analysis::select(&data, path)? {
+            selected.insert(name.clone(), value);
+        }
+    }
+
+    Ok(AnalysisOutput {
+        analyzer: analyzer.to_string(),
+        target_file: target_file.to_string_lossy().to_string(),
+        data: selected,
+        completion_percentage: completion_percentage.map(|c| c.clamp(0.0, 100.0)),
+        play_time_seconds,
+        duration_ms: duration.as_millis() as u64,
+    })
+}
+
+fn as_seconds(value: &Value) -> Option<i64> {
+    value.as_i64().or_else(|| value.as_f64().map(|f| f.round() as i64))
+}
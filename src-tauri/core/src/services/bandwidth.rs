@@ -0,0 +1,65 @@
+//! Upload and download rate limits, applied to the HTTP bodies as they stream.
+
+use futures_util::stream;
+use reqwest::{Body, Response};
+use std::time::{Duration, Instant};
+
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// Paces a transfer to `kbps` KB/s on average.
+pub struct RateLimiter {
+    bytes_per_sec: f64,
+    started: Instant,
+    transferred: u64,
+}
+
+impl RateLimiter {
+    /// `None` for a limit of 0, which means unlimited.
+    pub fn new(kbps: u32) -> Option<Self> {
+        (kbps > 0).then(|| RateLimiter {
+            bytes_per_sec: kbps as f64 * 1024.0,
+            started: Instant::now(),
+            transferred: 0,
+        })
+    }
+
+    /// Counts `bytes` and sleeps until the transfer is back under the limit.
+    pub async fn consume(&mut self, bytes: usize) {
+        self.transferred += bytes as u64;
+        let due = Duration::from_secs_f64(self.transferred as f64 / self.bytes_per_sec);
+        let elapsed = self.started.elapsed();
+        if due > elapsed {
+            tokio::time::sleep(due - elapsed).await;
+        }
+    }
+}
+
+/// A request body sending `data` no faster than `kbps` KB/s (0 for no limit).
+pub fn upload_body(data: Vec<u8>, kbps: u32) -> Body {
+    let Some(limiter) = RateLimiter::new(kbps) else {
+        return Body::from(data);
+    };
+    let chunks = stream::unfold((data, 0usize, limiter), |(data, offset, mut limiter)| async move {
+        if offset >= data.len() {
+            return None;
+        }
+        let end = (offset + CHUNK_SIZE).min(data.len());
+        let chunk = data[offset..end].to_vec();
+        limiter.consume(chunk.len()).await;
+        Some((Ok::<_, std::io::Error>(chunk), (data, end, limiter)))
+    });
+    Body::wrap_stream(chunks)
+}
+
+/// Reads a response body no faster than `kbps` KB/s (0 for no limit).
+pub async fn download(mut res: Response, kbps: u32) -> Result<Vec<u8>, String> {
+    let Some(mut limiter) = RateLimiter::new(kbps) else {
+        return res.bytes().await.map(|b| b.to_vec()).map_err(|e| e.to_string());
+    };
+    let mut data = Vec::new();
+    while let Some(chunk) = res.chunk().await.map_err(|e| e.to_string())? {
+        data.extend_from_slice(&chunk);
+        limiter.consume(chunk.len()).await;
+    }
+    Ok(data)
+}
@@ -0,0 +1,164 @@
+use crate::utils::file_pattern::FilePattern;
+use chrono::{Datelike, Timelike};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+/// Entry name used when the archived location is a single file rather than a folder.
+pub const SINGLE_FILE_MARKER: &str = "__SYNC_SINGLE_FILE__";
+
+/// Manifest present in archives built from several save locations.
+/// Each location's content lives under a `<label>/` prefix.
+pub const LOCATIONS_MANIFEST: &str = "__SYNC_LOCATIONS__.json";
+
+pub fn compress_path(src_path: &Path, dst_file: &Path) -> io::Result<()> {
+    if !src_path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "Source path not found",
+        ));
+    }
+
+    let file = File::create(dst_file)?;
+    let mut zip = ZipWriter::new(file);
+
+    write_path(&mut zip, src_path, "", None)?;
+
+    zip.finish()?;
+    Ok(())
+}
+
+/// A save location to archive under its label.
+pub struct ArchiveLocation {
+    pub label: String,
+    pub path: PathBuf,
+    /// Only files matching this pattern are archived (shared folders)
+    pub pattern: Option<FilePattern>,
+}
+
+/// Compresses several labeled locations into one archive.
+/// Locations that don't exist on disk are left out of the manifest.
+pub fn compress_locations(locations: &[ArchiveLocation], dst_file: &Path) -> io::Result<()> {
+    let present: Vec<&ArchiveLocation> = locations.iter().filter(|l| l.path.exists()).collect();
+    if present.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "None of the save locations exist",
+        ));
+    }
+
+    let file = File::create(dst_file)?;
+    let mut zip = ZipWriter::new(file);
+
+    let labels: Vec<&str> = present.iter().map(|l| l.label.as_str()).collect();
+    let manifest = serde_json::json!({ "version": 1, "locations": labels });
+    zip.start_file(LOCATIONS_MANIFEST, default_options())?;
+    zip.write_all(manifest.to_string().as_bytes())?;
+
+    for location in present {
+        write_path(
+            &mut zip,
+            &location.path,
+            &format!("{}/", location.label),
+            location.pattern.as_ref(),
+        )?;
+    }
+
+    zip.finish()?;
+    Ok(())
+}
+
+/// Entries get a fixed timestamp rather than the current time, so archiving
+/// unchanged saves gives the same bytes and checksum.
+fn default_options() -> FileOptions<'static, ()> {
+    FileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o755)
+        .last_modified_time(zip::DateTime::default())
+}
+
+/// Options for a file entry, stamped with the file's own modification time.
+fn file_options(path: &Path) -> FileOptions<'static, ()> {
+    let modified = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(|t| chrono::DateTime::<chrono::Local>::from(t).naive_local())
+        .and_then(|t| {
+            zip::DateTime::from_date_and_time(
+                t.year().try_into().ok()?,
+                t.month() as u8,
+                t.day() as u8,
+                t.hour() as u8,
+                t.minute() as u8,
+                t.second() as u8,
+            )
+            .ok()
+        })
+        .unwrap_or_default();
+    default_options().last_modified_time(modified)
+}
+
+fn write_path(
+    zip: &mut ZipWriter<File>,
+    src_path: &Path,
+    prefix: &str,
+    pattern: Option<&FilePattern>,
+) -> io::Result<()> {
+    let options = default_options();
+
+    if src_path.is_file() {
+        // Single file mode: store as special marker
+        zip.start_file(format!("{}{}", prefix, SINGLE_FILE_MARKER), file_options(src_path))?;
+        let mut f = File::open(src_path)?;
+        io::copy(&mut f, zip)?;
+    } else {
+        // Folder mode
+        if !prefix.is_empty() {
+            zip.add_directory(prefix, options)?;
+        }
+        // Sorted, so the entry order doesn't depend on the filesystem
+        let walk = WalkDir::new(src_path).sort_by_file_name();
+        for entry in walk.into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let rel = path.strip_prefix(src_path).unwrap();
+            // Zip entries always use forward slashes, regardless of host OS
+            let name = format!("{}{}", prefix, rel.to_string_lossy().replace('\\', "/"));
+
+            if let Some(pattern) = pattern {
+                // Other games' files in a shared folder stay out; parent
+                // folders are recreated from the file entries on extract
+                if path.is_file() && pattern.matches(rel) {
+                    zip.start_file(name, file_options(path))?;
+                    let mut f = File::open(path)?;
+                    io::copy(&mut f, zip)?;
+                }
+                continue;
+            }
+
+            if path.is_file() {
+                zip.start_file(name, file_options(path))?;
+                let mut f = File::open(path)?;
+                io::copy(&mut f, zip)?;
+            } else if name.len() > prefix.len() {
+                zip.add_directory(name, options)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Folder for in-flight archives, so leftovers from failed syncs can be found and removed.
+pub fn temp_zip_dir() -> PathBuf {
+    std::env::temp_dir().join("sync-saves")
+}
+
+/// A fresh archive path; overlapping syncs of one game each get their own.
+pub fn get_temp_zip_path(game_slug: &str) -> PathBuf {
+    let dir = temp_zip_dir();
+    let _ = std::fs::create_dir_all(&dir);
+    dir.join(format!("{}-{}.zip", game_slug, uuid::Uuid::new_v4()))
+}
@@ -0,0 +1,155 @@
+use crate::services::compression::{LOCATIONS_MANIFEST, SINGLE_FILE_MARKER};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+pub fn extract_zip(zip_path: &Path, target_path: &Path) -> io::Result<()> {
+    let file = fs::File::open(zip_path)?;
+    let mut archive = ZipArchive::new(file)?;
+
+    // Check for single file marker
+    if !archive.is_empty() {
+        let file_names: Vec<String> = (0..archive.len())
+            .filter_map(|i| archive.by_index(i).ok().map(|f| f.name().to_string()))
+            .collect();
+
+        if file_names.contains(&SINGLE_FILE_MARKER.to_string()) {
+            // Single file mode
+            let mut file = archive.by_name(SINGLE_FILE_MARKER)?;
+            write_entry(&mut file, target_path)?;
+            return Ok(());
+        }
+    }
+
+    // Normal folder restore
+    // Ensure target directory exists
+    if !target_path.exists() {
+        fs::create_dir_all(target_path)?;
+    }
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let outpath = match file.enclosed_name() {
+            Some(path) => target_path.join(path),
+            None => continue,
+        };
+
+        if (*file.name()).ends_with('/') {
+            fs::create_dir_all(&outpath)?;
+        } else {
+            write_entry(&mut file, &outpath)?;
+        }
+
+        set_unix_mode(&file, &outpath)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the location labels stored in a multi-location archive,
+/// or `None` for archives holding a single location.
+pub fn read_location_labels(zip_path: &Path) -> io::Result<Option<Vec<String>>> {
+    let file = fs::File::open(zip_path)?;
+    let mut archive = ZipArchive::new(file)?;
+
+    let mut manifest = match archive.by_name(LOCATIONS_MANIFEST) {
+        Ok(f) => f,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut content = String::new();
+    manifest.read_to_string(&mut content)?;
+    let json: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let labels = json["locations"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(Some(labels))
+}
+
+/// Extracts a multi-location archive, sending each `<label>/` prefix to its
+/// matching target. Returns the labels found in the archive that had no target.
+pub fn extract_locations(zip_path: &Path, targets: &[(String, PathBuf)]) -> io::Result<Vec<String>> {
+    let labels = read_location_labels(zip_path)?.unwrap_or_default();
+
+    let file = fs::File::open(zip_path)?;
+    let mut archive = ZipArchive::new(file)?;
+
+    let unmatched: Vec<String> = labels
+        .iter()
+        .filter(|l| !targets.iter().any(|(label, _)| label == *l))
+        .cloned()
+        .collect();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let name = file.name().to_string();
+        if name == LOCATIONS_MANIFEST {
+            continue;
+        }
+
+        let Some((label, rel)) = name.split_once('/') else {
+            continue;
+        };
+        let Some((_, target)) = targets.iter().find(|(l, _)| l == label) else {
+            continue;
+        };
+
+        if rel == SINGLE_FILE_MARKER {
+            write_entry(&mut file, target)?;
+            continue;
+        }
+
+        // Re-check the relative part so entries can't escape the target folder
+        let outpath = match file.enclosed_name() {
+            Some(path) => match path.strip_prefix(label) {
+                Ok(rel_path) => target.join(rel_path),
+                Err(_) => continue,
+            },
+            None => continue,
+        };
+
+        if name.ends_with('/') {
+            fs::create_dir_all(&outpath)?;
+        } else {
+            write_entry(&mut file, &outpath)?;
+        }
+
+        set_unix_mode(&file, &outpath)?;
+    }
+
+    Ok(unmatched)
+}
+
+fn write_entry(file: &mut zip::read::ZipFile<'_>, outpath: &Path) -> io::Result<()> {
+    if let Some(p) = outpath.parent() {
+        if !p.exists() {
+            fs::create_dir_all(p)?;
+        }
+    }
+    let mut outfile = fs::File::create(outpath)?;
+    io::copy(file, &mut outfile)?;
+    Ok(())
+}
+
+#[allow(unused_variables)]
+fn set_unix_mode(file: &zip::read::ZipFile<'_>, outpath: &Path) -> io::Result<()> {
+    // Set permissions on unix systems
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Some(mode) = file.unix_mode() {
+            fs::set_permissions(outpath, fs::Permissions::from_mode(mode))?;
+        }
+    }
+    Ok(())
+}
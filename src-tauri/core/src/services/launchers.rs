@@ -0,0 +1,348 @@
+//! Installed-game discovery for non-Steam launchers.
+//!
+//! Reads each launcher's own config files and databases: Heroic (GOG and
+//! Epic through Legendary), Lutris, GOG Galaxy and the Epic Games Launcher.
+//! Everything is read-only; a launcher that isn't installed yields nothing.
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const LAUNCHERS: &[&str] = &["heroic", "lutris", "gog-galaxy", "epic"];
+
+#[derive(Serialize, Debug, Clone)]
+pub struct LauncherGame {
+    /// "heroic", "lutris", "gog-galaxy" or "epic"
+    pub launcher: String,
+    /// Value for `games_cache.platform`: "gog", "epic", "steam" or "other"
+    pub platform: String,
+    /// The launcher's own id for the game
+    pub launcher_id: String,
+    pub title: String,
+    pub install_path: Option<PathBuf>,
+    /// Wine prefix (the folder containing `drive_c`) for Windows games on Linux
+    pub wine_prefix: Option<PathBuf>,
+    /// Save folders the launcher itself knows about (Heroic cloud saves, Legendary)
+    pub save_paths: Vec<PathBuf>,
+}
+
+/// Lists installed games for the given launchers (all of them when empty).
+pub fn installed_games(launchers: &[String]) -> Vec<LauncherGame> {
+    let wants = |name: &str| launchers.is_empty() || launchers.iter().any(|l| l == name);
+    let mut games = vec![];
+
+    if wants("heroic") {
+        for dir in heroic_config_dirs() {
+            games.extend(heroic_gog_games(&dir));
+            games.extend(heroic_legendary_games(&dir));
+        }
+    }
+    if wants("lutris") {
+        games.extend(lutris_games());
+    }
+    if wants("gog-galaxy") {
+        games.extend(gog_galaxy_games());
+    }
+    if wants("epic") {
+        games.extend(epic_launcher_games());
+    }
+
+    // The same game can show up twice (e.g. Heroic native and flatpak configs)
+    let mut seen = std::collections::HashSet::new();
+    games.retain(|g| seen.insert((g.launcher.clone(), g.launcher_id.clone())));
+    games.sort_by_key(|g| g.title.to_lowercase());
+    games
+}
+
+fn heroic_config_dirs() -> Vec<PathBuf> {
+    let mut dirs_found = vec![];
+    if let Some(config) = dirs::config_dir() {
+        dirs_found.push(config.join("heroic"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        dirs_found.push(home.join(".var/app/com.heroicgameslauncher.hgl/config/heroic"));
+    }
+    dirs_found.retain(|d| d.is_dir());
+    dirs_found
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn non_empty_path(value: Option<&Value>) -> Option<PathBuf> {
+    value
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .map(PathBuf::from)
+}
+
+/// Per-game settings Heroic keeps in `GamesConfig/<appName>.json`.
+fn heroic_game_config(heroic_dir: &Path, app_name: &str) -> Option<Value> {
+    let config = read_json(&heroic_dir.join("GamesConfig").join(format!("{}.json", app_name)))?;
+    config.get(app_name).cloned()
+}
+
+fn heroic_save_paths(config: Option<&Value>) -> Vec<PathBuf> {
+    let Some(config) = config else {
+        return vec![];
+    };
+    let mut paths: Vec<PathBuf> = non_empty_path(config.get("savesPath")).into_iter().collect();
+    if let Some(gog_saves) = config.get("gogSaves").and_then(|v| v.as_array()) {
+        paths.extend(gog_saves.iter().filter_map(|s| non_empty_path(s.get("location"))));
+    }
+    paths
+}
+
+fn heroic_gog_games(heroic_dir: &Path) -> Vec<LauncherGame> {
+    let Some(installed) = read_json(&heroic_dir.join("gog_store").join("installed.json")) else {
+        return vec![];
+    };
+
+    // Titles live in the library cache, which moved between Heroic versions
+    let mut titles: HashMap<String, String> = HashMap::new();
+    for library in [
+        heroic_dir.join("store_cache").join("gog_library.json"),
+        heroic_dir.join("gog_store").join("library.json"),
+    ] {
+        let Some(library) = read_json(&library) else { continue };
+        for game in library.get("games").and_then(|g| g.as_array()).into_iter().flatten() {
+            if let (Some(id), Some(title)) = (
+                game.get("app_name").and_then(|v| v.as_str()),
+                game.get("title").and_then(|v| v.as_str()),
+            ) {
+                titles.entry(id.to_string()).or_insert_with(|| title.to_string());
+            }
+        }
+    }
+
+    installed
+        .get("installed")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let app_name = entry.get("appName")?.as_str()?.to_string();
+            let install_path = non_empty_path(entry.get("install_path"));
+            let title = titles.get(&app_name).cloned().or_else(|| {
+                install_path
+                    .as_ref()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+            })?;
+            let config = heroic_game_config(heroic_dir, &app_name);
+
+            Some(LauncherGame {
+                launcher: "heroic".to_string(),
+                platform: "gog".to_string(),
+                launcher_id: app_name,
+                title,
+                install_path,
+                wine_prefix: non_empty_path(config.as_ref().and_then(|c| c.get("winePrefix"))),
+                save_paths: heroic_save_paths(config.as_ref()),
+            })
+        })
+        .collect()
+}
+
+fn heroic_legendary_games(heroic_dir: &Path) -> Vec<LauncherGame> {
+    let path = heroic_dir
+        .join("legendaryConfig")
+        .join("legendary")
+        .join("installed.json");
+    let Some(Value::Object(installed)) = read_json(&path) else {
+        return vec![];
+    };
+
+    installed
+        .into_iter()
+        .filter_map(|(app_name, entry)| {
+            let title = entry.get("title")?.as_str()?.to_string();
+            let config = heroic_game_config(heroic_dir, &app_name);
+            let mut save_paths = heroic_save_paths(config.as_ref());
+            save_paths.extend(non_empty_path(entry.get("save_path")));
+
+            Some(LauncherGame {
+                launcher: "heroic".to_string(),
+                platform: "epic".to_string(),
+                install_path: non_empty_path(entry.get("install_path")),
+                wine_prefix: non_empty_path(config.as_ref().and_then(|c| c.get("winePrefix"))),
+                launcher_id: app_name,
+                title,
+                save_paths,
+            })
+        })
+        .collect()
+}
+
+fn open_read_only(path: &Path) -> Option<Connection> {
+    if !path.is_file() {
+        return None;
+    }
+    Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX).ok()
+}
+
+fn lutris_games() -> Vec<LauncherGame> {
+    let (Some(data), Some(config)) = (dirs::data_dir(), dirs::config_dir()) else {
+        return vec![];
+    };
+    let Some(conn) = open_read_only(&data.join("lutris").join("pga.db")) else {
+        return vec![];
+    };
+
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT slug, name, runner, directory, configpath, service FROM games WHERE installed = 1",
+    ) else {
+        return vec![];
+    };
+
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<String>>(4)?,
+            row.get::<_, Option<String>>(5)?,
+        ))
+    });
+    let Ok(rows) = rows else {
+        return vec![];
+    };
+
+    rows.flatten()
+        .map(|(slug, name, runner, directory, configpath, service)| {
+            // Game configs moved from ~/.config to ~/.local/share in Lutris 0.5.17
+            let wine_prefix = configpath
+                .filter(|_| runner.as_deref() == Some("wine"))
+                .and_then(|cp| {
+                    [config.join("lutris/games"), data.join("lutris/games")]
+                        .iter()
+                        .find_map(|dir| lutris_wine_prefix(&dir.join(format!("{}.yml", cp))))
+                });
+
+            LauncherGame {
+                launcher: "lutris".to_string(),
+                platform: match service.as_deref() {
+                    Some("gog") => "gog",
+                    Some("egs") => "epic",
+                    Some("steam") => "steam",
+                    _ => "other",
+                }
+                .to_string(),
+                launcher_id: slug,
+                title: name,
+                install_path: directory.filter(|d| !d.is_empty()).map(PathBuf::from),
+                wine_prefix,
+                save_paths: vec![],
+            }
+        })
+        .collect()
+}
+
+fn lutris_wine_prefix(config_file: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(config_file).ok()?;
+    let yaml: serde_yaml::Value = serde_yaml::from_str(&content).ok()?;
+    yaml.get("game")?
+        .get("prefix")?
+        .as_str()
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+}
+
+fn gog_galaxy_games() -> Vec<LauncherGame> {
+    let program_data = std::env::var_os("PROGRAMDATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("C:\\ProgramData"));
+    let db_path = program_data
+        .join("GOG.com")
+        .join("Galaxy")
+        .join("storage")
+        .join("galaxy-2.0.db");
+    let Some(conn) = open_read_only(&db_path) else {
+        return vec![];
+    };
+
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT ibp.productId, ibp.installationPath, ld.title
+         FROM InstalledBaseProducts ibp
+         LEFT JOIN LimitedDetails ld ON ld.productId = ibp.productId",
+    ) else {
+        return vec![];
+    };
+
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+        ))
+    });
+    let Ok(rows) = rows else {
+        return vec![];
+    };
+
+    rows.flatten()
+        .filter_map(|(product_id, install_path, title)| {
+            let install_path = install_path.filter(|p| !p.is_empty()).map(PathBuf::from);
+            let title = title.or_else(|| {
+                install_path
+                    .as_ref()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+            })?;
+            Some(LauncherGame {
+                launcher: "gog-galaxy".to_string(),
+                platform: "gog".to_string(),
+                launcher_id: product_id.to_string(),
+                title,
+                install_path,
+                wine_prefix: None,
+                save_paths: vec![],
+            })
+        })
+        .collect()
+}
+
+fn epic_launcher_games() -> Vec<LauncherGame> {
+    let manifests_dir = if cfg!(target_os = "macos") {
+        dirs::home_dir().map(|h| h.join("Library/Application Support/Epic/EpicGamesLauncher/Data/Manifests"))
+    } else {
+        Some(
+            std::env::var_os("PROGRAMDATA")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("C:\\ProgramData"))
+                .join("Epic")
+                .join("EpicGamesLauncher")
+                .join("Data")
+                .join("Manifests"),
+        )
+    };
+    let Some(Ok(entries)) = manifests_dir.map(fs::read_dir) else {
+        return vec![];
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().map(|x| x == "item").unwrap_or(false))
+        .filter_map(|e| {
+            let item = read_json(&e.path())?;
+            if item.get("bIsIncompleteInstall").and_then(|v| v.as_bool()).unwrap_or(false) {
+                return None;
+            }
+            Some(LauncherGame {
+                launcher: "epic".to_string(),
+                platform: "epic".to_string(),
+                launcher_id: item.get("AppName")?.as_str()?.to_string(),
+                title: item.get("DisplayName")?.as_str()?.to_string(),
+                install_path: non_empty_path(item.get("InstallLocation")),
+                wine_prefix: None,
+                save_paths: vec![],
+            })
+        })
+        .collect()
+}
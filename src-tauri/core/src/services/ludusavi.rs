@@ -0,0 +1,299 @@
+//! Reader for the Ludusavi `manifest.yaml` save-location database.
+//!
+//! The manifest maps game names to file paths written with Ludusavi
+//! placeholders (`<winAppData>`, `<xdgConfig>`, `<base>`, ...). We convert
+//! them to our `{{p|...}}` path templates so they resolve like PCGW paths.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ManifestGame {
+    pub files: BTreeMap<String, Option<ManifestFile>>,
+    pub registry: BTreeMap<String, Option<ManifestFile>>,
+    #[serde(rename = "installDir")]
+    pub install_dir: BTreeMap<String, serde_yaml::Value>,
+    pub steam: Option<ManifestSteam>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ManifestFile {
+    pub tags: Vec<String>,
+    pub when: Vec<ManifestWhen>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct ManifestWhen {
+    pub os: Option<String>,
+    pub store: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ManifestSteam {
+    pub id: Option<u32>,
+}
+
+/// A manifest file entry converted to a path template.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ManifestPath {
+    pub template: String,
+    /// "windows", "linux", "macos" or None when the entry applies everywhere
+    pub os: Option<String>,
+    pub store: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// Everything we keep from a manifest entry.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub steam_app_id: Option<u32>,
+    pub paths: Vec<ManifestPath>,
+    pub registry_keys: Vec<String>,
+}
+
+pub fn load_manifest(path: &Path) -> Result<BTreeMap<String, ManifestGame>, String> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open manifest {}: {}", path.display(), e))?;
+    serde_yaml::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| format!("Failed to parse manifest: {}", e))
+}
+
+pub fn to_entry(name: &str, game: &ManifestGame) -> ManifestEntry {
+    let mut paths = vec![];
+
+    for (raw, file) in &game.files {
+        let Some(template) = to_template(raw, game) else {
+            continue;
+        };
+        let tags = file.as_ref().map(|f| f.tags.clone()).unwrap_or_default();
+        let whens = file.as_ref().map(|f| f.when.clone()).unwrap_or_default();
+
+        if whens.is_empty() {
+            paths.push(ManifestPath {
+                os: infer_os(&template),
+                template,
+                store: None,
+                tags,
+            });
+            continue;
+        }
+
+        for when in whens {
+            paths.push(ManifestPath {
+                template: template.clone(),
+                os: when.os.as_deref().map(normalize_os).or_else(|| infer_os(&template)),
+                store: when.store,
+                tags: tags.clone(),
+            });
+        }
+    }
+
+    paths.dedup_by(|a, b| a.template == b.template && a.os == b.os && a.store == b.store);
+
+    ManifestEntry {
+        name: name.to_string(),
+        steam_app_id: game.steam.as_ref().and_then(|s| s.id),
+        paths,
+        registry_keys: game.registry.keys().cloned().collect(),
+    }
+}
+
+/// Converts a Ludusavi path to a `{{p|...}}` template. Returns `None` for
+/// entries we can't express as a single folder or file (e.g. mid-path globs).
+fn to_template(raw: &str, game: &ManifestGame) -> Option<String> {
+    let install_dir = game.install_dir.keys().next().cloned();
+
+    let replacements: [(&str, Option<String>); 14] = [
+        ("<base>", Some("{{p|game}}".to_string())),
+        ("<root>", Some("{{p|steam}}".to_string())),
+        ("<game>", install_dir),
+        ("<home>", Some("{{p|home}}".to_string())),
+        ("<storeUserId>", Some("{{p|uid}}".to_string())),
+        ("<osUserName>", Some("{{p|username}}".to_string())),
+        ("<winAppData>", Some("{{p|appdata}}".to_string())),
+        ("<winLocalAppDataLow>", Some("{{p|localappdatalow}}".to_string())),
+        ("<winLocalAppData>", Some("{{p|localappdata}}".to_string())),
+        ("<winDocuments>", Some("{{p|userprofile\\Documents}}".to_string())),
+        ("<winPublic>", Some("{{p|public}}".to_string())),
+        ("<winProgramData>", Some("{{p|programdata}}".to_string())),
+        ("<xdgData>", Some("{{p|xdgdatahome}}".to_string())),
+        ("<xdgConfig>", Some("{{p|xdgconfighome}}".to_string())),
+    ];
+
+    let mut template = raw.to_string();
+    for (placeholder, value) in replacements {
+        if template.contains(placeholder) {
+            template = template.replace(placeholder, value.as_deref()?);
+        }
+    }
+
+    // Anything left is a placeholder we don't support (<winDir>, <regHkcu>, ...)
+    if template.contains('<') {
+        return None;
+    }
+
+    // Trailing globs ("Saves/*.sav") are covered by syncing their parent folder
+    let mut parts: Vec<&str> = template.split('/').collect();
+    while parts.last().map(|p| is_glob(p)).unwrap_or(false) {
+        parts.pop();
+    }
+    if parts.iter().any(|p| is_glob(p)) || parts.len() < 2 {
+        return None;
+    }
+
+    Some(parts.join("/"))
+}
+
+fn is_glob(segment: &str) -> bool {
+    segment.contains('*') || segment.contains('?') || segment.contains('[')
+}
+
+fn normalize_os(os: &str) -> String {
+    match os {
+        "mac" => "macos".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn infer_os(template: &str) -> Option<String> {
+    let lc = template.to_lowercase();
+    if ["{{p|appdata}}", "{{p|localappdata", "{{p|userprofile", "{{p|public}}", "{{p|programdata}}"]
+        .iter()
+        .any(|t| lc.starts_with(t))
+    {
+        Some("windows".to_string())
+    } else if lc.starts_with("{{p|xdg") {
+        Some("linux".to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(yaml: &str) -> ManifestGame {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn templates(entry: &ManifestEntry) -> Vec<&str> {
+        entry.paths.iter().map(|p| p.template.as_str()).collect()
+    }
+
+    #[test]
+    fn placeholders_map_to_path_tokens() {
+        let game = game(
+            r#"
+files:
+  <winAppData>/Example/Saves: {}
+  <winLocalAppDataLow>/Studio/Example: {}
+  <winDocuments>/My Games/Example: {}
+  <xdgConfig>/example: {}
+  <root>/userdata/<storeUserId>/620980/remote: {}
+  <home>/.example/<osUserName>: {}
+installDir:
+  Example: {}
+"#,
+        );
+        let entry = to_entry("Example", &game);
+
+        // Listed in the manifest's key order
+        assert_eq!(
+            templates(&entry),
+            [
+                "{{p|home}}/.example/{{p|username}}",
+                "{{p|steam}}/userdata/{{p|uid}}/620980/remote",
+                "{{p|appdata}}/Example/Saves",
+                "{{p|userprofile\\Documents}}/My Games/Example",
+                "{{p|localappdatalow}}/Studio/Example",
+                "{{p|xdgconfighome}}/example",
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_placeholders_are_skipped() {
+        let game = game(
+            r#"
+files:
+  <winDir>/Example.ini: {}
+  <regHkcu>/Software/Example: {}
+  <xdgData>/example: {}
+"#,
+        );
+        assert_eq!(templates(&to_entry("Example", &game)), ["{{p|xdgdatahome}}/example"]);
+    }
+
+    #[test]
+    fn trailing_globs_are_trimmed() {
+        let game = game(
+            r#"
+files:
+  <base>/Saves/*.sav: {}
+  <winAppData>/Example/**/*: {}
+  <winAppData>/Example/*/Saves: {}
+  <home>/*: {}
+"#,
+        );
+        let entry = to_entry("Example", &game);
+
+        // Mid-path globs and globs right under a root can't be a single folder
+        assert_eq!(templates(&entry), ["{{p|game}}/Saves", "{{p|appdata}}/Example"]);
+    }
+
+    #[test]
+    fn game_placeholder_needs_an_install_dir() {
+        let yaml = r#"
+files:
+  <root>/steamapps/common/<game>/Saves: {}
+"#;
+        assert!(to_entry("Example", &game(yaml)).paths.is_empty());
+
+        let with_dir = game(&format!("{}installDir:\n  Example Game: {{}}\n", yaml));
+        assert_eq!(
+            templates(&to_entry("Example", &with_dir)),
+            ["{{p|steam}}/steamapps/common/Example Game/Saves"]
+        );
+    }
+
+    #[test]
+    fn when_os_is_normalized() {
+        let game = game(
+            r#"
+files:
+  <home>/Library/Application Support/Example:
+    when:
+      - os: mac
+  <home>/.local/share/example:
+    tags: [save]
+    when:
+      - os: linux
+        store: steam
+      - os: linux
+        store: steam
+  <winAppData>/Example:
+    when:
+      - store: gog
+steam:
+  id: 620980
+"#,
+        );
+        let entry = to_entry("Example", &game);
+
+        assert_eq!(entry.steam_app_id, Some(620980));
+        let os: Vec<_> = entry.paths.iter().map(|p| p.os.as_deref()).collect();
+        assert_eq!(os, [Some("linux"), Some("macos"), Some("windows")]);
+        // Repeated conditions are listed once
+        assert_eq!(entry.paths[0].store.as_deref(), Some("steam"));
+        assert_eq!(entry.paths[0].tags, ["save"]);
+        // Without an os the placeholder decides
+        assert_eq!(entry.paths[2].store.as_deref(), Some("gog"));
+    }
+}
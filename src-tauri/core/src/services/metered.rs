@@ -0,0 +1,79 @@
+//! Whether the current connection is metered, where the OS tells: through
+//! NetworkManager on Linux and the connection cost on Windows.
+
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long an answer is reused; asking spawns a process
+const CACHE_FOR: Duration = Duration::from_secs(60);
+
+static CACHE: Mutex<Option<(Instant, Option<bool>)>> = Mutex::new(None);
+
+/// `None` when it can't be told.
+pub fn is_metered() -> Option<bool> {
+    if let Ok(cache) = CACHE.lock() {
+        if let Some((at, metered)) = *cache {
+            if at.elapsed() < CACHE_FOR {
+                return metered;
+            }
+        }
+    }
+    let metered = detect();
+    if let Ok(mut cache) = CACHE.lock() {
+        *cache = Some((Instant::now(), metered));
+    }
+    metered
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+fn output(cmd: &mut Command) -> Option<String> {
+    let out = cmd.output().ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn detect() -> Option<bool> {
+    // NMMetered: 1 yes, 3 guessed yes, 2 and 4 no, 0 unknown
+    let out = output(Command::new("busctl").args([
+        "--system",
+        "get-property",
+        "org.freedesktop.NetworkManager",
+        "/org/freedesktop/NetworkManager",
+        "org.freedesktop.NetworkManager",
+        "Metered",
+    ]))?;
+    match out.strip_prefix("u ")?.trim() {
+        "1" | "3" => Some(true),
+        "2" | "4" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn detect() -> Option<bool> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let script = "[Windows.Networking.Connectivity.NetworkInformation,Windows.Networking.Connectivity,ContentType=WindowsRuntime] | Out-Null; \
+        $p = [Windows.Networking.Connectivity.NetworkInformation]::GetInternetConnectionProfile(); \
+        if ($p) { $p.GetConnectionCost().NetworkCostType }";
+    let out = output(
+        Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", script])
+            .creation_flags(CREATE_NO_WINDOW),
+    )?;
+    match out.as_str() {
+        "Fixed" | "Variable" => Some(true),
+        "Unrestricted" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn detect() -> Option<bool> {
+    None
+}
@@ -0,0 +1,13 @@
+pub mod analysis;
+pub mod analysis_runner;
+pub mod bandwidth;
+pub mod compression;
+pub mod extraction;
+pub mod launchers;
+pub mod ludusavi;
+pub mod metered;
+pub mod retention;
+pub mod steam_library;
+pub mod steam_metadata;
+pub mod version_diff;
+pub mod watcher;
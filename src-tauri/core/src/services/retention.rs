@@ -0,0 +1,164 @@
+//! Decides which cloud save versions a retention policy keeps.
+
+use chrono::{DateTime, Datelike, Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Per-game retention (`games_cache.retention_policy`). The latest and pinned
+/// versions are always kept; a version survives if any rule keeps it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Always keep this many of the most recent versions
+    pub keep_last: u32,
+    /// Keep the newest version of each day for this many days
+    pub keep_daily_days: u32,
+    /// Keep the newest version of each week for this many weeks
+    pub keep_weekly_weeks: u32,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_last: 10,
+            keep_daily_days: 7,
+            keep_weekly_weeks: 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VersionInfo {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub is_latest: bool,
+    pub is_pinned: bool,
+}
+
+/// Returns the ids the policy keeps.
+pub fn versions_to_keep(versions: &[VersionInfo], policy: &RetentionPolicy, now: DateTime<Utc>) -> HashSet<String> {
+    let mut sorted: Vec<&VersionInfo> = versions.iter().collect();
+    sorted.sort_by_key(|v| std::cmp::Reverse(v.created_at));
+
+    let mut keep: HashSet<String> = sorted
+        .iter()
+        .filter(|v| v.is_latest || v.is_pinned)
+        .map(|v| v.id.clone())
+        .collect();
+    keep.extend(sorted.iter().take(policy.keep_last as usize).map(|v| v.id.clone()));
+
+    // Newest first, so the first version seen in each bucket is the one kept
+    let daily_cutoff = now - Duration::days(policy.keep_daily_days as i64);
+    let mut days = HashSet::new();
+    let weekly_cutoff = now - Duration::weeks(policy.keep_weekly_weeks as i64);
+    let mut weeks = HashSet::new();
+
+    for v in &sorted {
+        let local = v.created_at.with_timezone(&Local);
+        if v.created_at >= daily_cutoff && days.insert(local.date_naive()) {
+            keep.insert(v.id.clone());
+        }
+        let week = local.iso_week();
+        if v.created_at >= weekly_cutoff && weeks.insert((week.year(), week.week())) {
+            keep.insert(v.id.clone());
+        }
+    }
+
+    keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Local time, so the day and week buckets don't depend on the machine's zone.
+    fn at(month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Local.with_ymd_and_hms(2026, month, day, hour, 0, 0).unwrap().with_timezone(&Utc)
+    }
+
+    fn version(id: &str, created_at: DateTime<Utc>) -> VersionInfo {
+        VersionInfo {
+            id: id.to_string(),
+            created_at,
+            is_latest: false,
+            is_pinned: false,
+        }
+    }
+
+    fn policy(keep_last: u32, keep_daily_days: u32, keep_weekly_weeks: u32) -> RetentionPolicy {
+        RetentionPolicy {
+            keep_last,
+            keep_daily_days,
+            keep_weekly_weeks,
+        }
+    }
+
+    fn ids(keep: HashSet<String>) -> Vec<String> {
+        let mut ids: Vec<String> = keep.into_iter().collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn latest_and_pinned_are_always_kept() {
+        let mut latest = version("latest", at(1, 5, 12));
+        latest.is_latest = true;
+        let mut pinned = version("pinned", at(1, 1, 12));
+        pinned.is_pinned = true;
+        let versions = [version("old", at(1, 3, 12)), latest, pinned];
+
+        assert_eq!(ids(versions_to_keep(&versions, &policy(0, 0, 0), at(3, 15, 12))), ["latest", "pinned"]);
+    }
+
+    #[test]
+    fn keep_last_keeps_the_newest() {
+        let versions = [
+            version("b", at(3, 2, 12)),
+            version("d", at(3, 4, 12)),
+            version("a", at(3, 1, 12)),
+            version("c", at(3, 3, 12)),
+        ];
+
+        assert_eq!(ids(versions_to_keep(&versions, &policy(2, 0, 0), at(3, 15, 12))), ["c", "d"]);
+    }
+
+    #[test]
+    fn one_version_per_day() {
+        let versions = [
+            version("14-morning", at(3, 14, 10)),
+            version("14-evening", at(3, 14, 18)),
+            version("13", at(3, 13, 12)),
+            version("1", at(3, 1, 12)),
+        ];
+
+        assert_eq!(ids(versions_to_keep(&versions, &policy(0, 7, 0), at(3, 15, 12))), ["13", "14-evening"]);
+    }
+
+    #[test]
+    fn one_version_per_week() {
+        // 2026-03-09 and 2026-03-02 are Mondays
+        let versions = [
+            version("mon", at(3, 9, 12)),
+            version("wed", at(3, 11, 12)),
+            version("last-week", at(3, 4, 12)),
+            version("too-old", at(2, 25, 12)),
+        ];
+
+        assert_eq!(ids(versions_to_keep(&versions, &policy(0, 0, 2), at(3, 15, 12))), ["last-week", "wed"]);
+    }
+
+    #[test]
+    fn boundaries() {
+        let now = at(3, 15, 12);
+        assert!(versions_to_keep(&[], &RetentionPolicy::default(), now).is_empty());
+
+        // The cutoff itself is still inside the window
+        let versions = [
+            version("edge", now - Duration::days(7)),
+            version("outside", now - Duration::days(7) - Duration::seconds(1)),
+        ];
+        assert_eq!(ids(versions_to_keep(&versions, &policy(0, 7, 0), now)), ["edge"]);
+        assert!(versions_to_keep(&versions, &policy(0, 0, 0), now).is_empty());
+    }
+}
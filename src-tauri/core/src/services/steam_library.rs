@@ -0,0 +1,499 @@
+//! Local Steam installation discovery.
+//!
+//! Reads `libraryfolders.vdf` and `appmanifest_*.acf` (Valve's text
+//! KeyValues format) to list installed apps without touching the network.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Redistributables and compatibility tools that show up as installed apps.
+const IGNORED_APP_IDS: &[u32] = &[228980, 1070560, 1391110, 1628350, 1493710, 2180100];
+
+/// A node of a parsed KeyValues document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Vdf {
+    Value(String),
+    Object(Vec<(String, Vdf)>),
+}
+
+impl Vdf {
+    /// Case-insensitive child lookup (Valve is inconsistent with key casing).
+    pub fn get(&self, key: &str) -> Option<&Vdf> {
+        match self {
+            Vdf::Object(children) => children
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            Vdf::Value(_) => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Vdf::Value(v) => Some(v),
+            Vdf::Object(_) => None,
+        }
+    }
+
+    pub fn entries(&self) -> &[(String, Vdf)] {
+        match self {
+            Vdf::Object(children) => children,
+            Vdf::Value(_) => &[],
+        }
+    }
+}
+
+/// Parses a KeyValues document into an object holding its top-level pairs.
+pub fn parse_vdf(input: &str) -> Result<Vdf, String> {
+    let tokens = tokenize(input)?;
+    let mut pos = 0;
+    let root = parse_object(&tokens, &mut pos, true)?;
+    Ok(root)
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Str(String),
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '{' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            '}' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '/' => {
+                // Line comment
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                } else {
+                    tokens.push(Token::Str("/".to_string()));
+                }
+            }
+            '[' => {
+                // Platform conditionals like [$WIN32] are ignored
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(other) => s.push(other),
+                            None => return Err("Unterminated escape in VDF".to_string()),
+                        },
+                        Some('"') => break,
+                        Some(other) => s.push(other),
+                        None => return Err("Unterminated string in VDF".to_string()),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            _ => {
+                let mut s = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '{' || c == '}' || c == '"' {
+                        break;
+                    }
+                    s.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Str(s));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_object(tokens: &[Token], pos: &mut usize, top_level: bool) -> Result<Vdf, String> {
+    let mut children = vec![];
+
+    loop {
+        match tokens.get(*pos) {
+            None if top_level => break,
+            None => return Err("Unexpected end of VDF".to_string()),
+            Some(Token::Close) if !top_level => {
+                *pos += 1;
+                break;
+            }
+            Some(Token::Str(key)) => {
+                *pos += 1;
+                match tokens.get(*pos) {
+                    Some(Token::Str(value)) => {
+                        *pos += 1;
+                        children.push((key.clone(), Vdf::Value(value.clone())));
+                    }
+                    Some(Token::Open) => {
+                        *pos += 1;
+                        let child = parse_object(tokens, pos, false)?;
+                        children.push((key.clone(), child));
+                    }
+                    _ => return Err(format!("Missing value for VDF key '{}'", key)),
+                }
+            }
+            Some(_) => return Err("Unexpected brace in VDF".to_string()),
+        }
+    }
+
+    Ok(Vdf::Object(children))
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SteamInstalledApp {
+    pub app_id: u32,
+    pub name: String,
+    pub install_dir: PathBuf,
+    pub library: PathBuf,
+    pub size_on_disk: Option<u64>,
+    /// Existing Steam Cloud folders (`userdata/<account>/<appid>/remote`)
+    pub cloud_save_paths: Vec<PathBuf>,
+    pub proton_prefix: Option<PathBuf>,
+}
+
+/// Library folders listed in `libraryfolders.vdf`, the Steam root included.
+pub fn library_folders(steam_root: &Path) -> Vec<PathBuf> {
+    let mut libraries = vec![steam_root.to_path_buf()];
+
+    let vdf_path = steam_root.join("steamapps").join("libraryfolders.vdf");
+    let Ok(content) = fs::read_to_string(&vdf_path) else {
+        return libraries;
+    };
+    let Ok(doc) = parse_vdf(&content) else {
+        return libraries;
+    };
+
+    let Some(folders) = doc.get("libraryfolders") else {
+        return libraries;
+    };
+
+    for (key, value) in folders.entries() {
+        if !key.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        // Newer format nests { "path" ... }, the old one maps index -> path directly
+        let path = value
+            .as_str()
+            .or_else(|| value.get("path").and_then(|p| p.as_str()));
+        if let Some(path) = path {
+            let path = PathBuf::from(path);
+            let is_known = libraries.iter().any(|l| same_dir(l, &path));
+            if !is_known && path.join("steamapps").is_dir() {
+                libraries.push(path);
+            }
+        }
+    }
+
+    libraries
+}
+
+/// Steam account folder names under `userdata/`.
+pub fn user_ids(steam_root: &Path) -> Vec<String> {
+    fs::read_dir(steam_root.join("userdata"))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| name != "0" && name.chars().all(|c| c.is_ascii_digit()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reads a single `appmanifest_<id>.acf` from a library.
+pub fn read_app_manifest(library: &Path, app_id: u32) -> Option<SteamInstalledApp> {
+    let path = library
+        .join("steamapps")
+        .join(format!("appmanifest_{}.acf", app_id));
+    parse_app_manifest(library, &path)
+}
+
+fn parse_app_manifest(library: &Path, manifest_path: &Path) -> Option<SteamInstalledApp> {
+    let content = fs::read_to_string(manifest_path).ok()?;
+    let doc = parse_vdf(&content).ok()?;
+    let state = doc.get("AppState")?;
+
+    let app_id = state.get("appid")?.as_str()?.parse::<u32>().ok()?;
+    let name = state.get("name")?.as_str()?.to_string();
+    let install_dir_name = state.get("installdir")?.as_str()?;
+
+    Some(SteamInstalledApp {
+        app_id,
+        name,
+        install_dir: library.join("steamapps").join("common").join(install_dir_name),
+        library: library.to_path_buf(),
+        size_on_disk: state
+            .get("SizeOnDisk")
+            .and_then(|v| v.as_str())
+            .and_then(|v| v.parse().ok()),
+        cloud_save_paths: vec![],
+        proton_prefix: None,
+    })
+}
+
+/// Lists installed apps across every library, with cloud save folders and Proton prefixes.
+pub fn installed_apps(steam_root: &Path) -> Vec<SteamInstalledApp> {
+    let users = user_ids(steam_root);
+    let mut apps: Vec<SteamInstalledApp> = vec![];
+
+    for library in library_folders(steam_root) {
+        let Ok(entries) = fs::read_dir(library.join("steamapps")) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !(file_name.starts_with("appmanifest_") && file_name.ends_with(".acf")) {
+                continue;
+            }
+
+            let Some(mut app) = parse_app_manifest(&library, &entry.path()) else {
+                continue;
+            };
+            if is_tool(&app) || apps.iter().any(|a| a.app_id == app.app_id) {
+                continue;
+            }
+
+            app.cloud_save_paths = users
+                .iter()
+                .map(|user| {
+                    steam_root
+                        .join("userdata")
+                        .join(user)
+                        .join(app.app_id.to_string())
+                        .join("remote")
+                })
+                .filter(|p| p.is_dir())
+                .collect();
+
+            let prefix = library
+                .join("steamapps")
+                .join("compatdata")
+                .join(app.app_id.to_string())
+                .join("pfx");
+            if prefix.is_dir() {
+                app.proton_prefix = Some(prefix);
+            }
+
+            apps.push(app);
+        }
+    }
+
+    apps.sort_by_key(|a| a.name.to_lowercase());
+    apps
+}
+
+/// Proton prefix for an app, looking in every library (compatdata lives next to the game).
+pub fn find_proton_prefix(libraries: &[PathBuf], app_id: u32) -> Option<PathBuf> {
+    libraries
+        .iter()
+        .map(|l| {
+            l.join("steamapps")
+                .join("compatdata")
+                .join(app_id.to_string())
+                .join("pfx")
+        })
+        .find(|p| p.is_dir())
+}
+
+fn is_tool(app: &SteamInstalledApp) -> bool {
+    IGNORED_APP_IDS.contains(&app.app_id)
+        || app.name.starts_with("Proton")
+        || app.name.starts_with("Steam Linux Runtime")
+        || app.name.starts_with("Steamworks Common")
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const LIBRARY_FOLDERS: &str = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"STEAM_ROOT"
+		"label"		""
+		"apps"
+		{
+			"228980"		"29212173"
+		}
+	}
+	"1"
+	{
+		"path"		"EXTRA_LIBRARY"
+		"contentid"		"4432761716237341421"
+	}
+}
+"#;
+
+    /// Before 2021 the index mapped straight to the path.
+    const LIBRARY_FOLDERS_OLD: &str = r#"
+"LibraryFolders"
+{
+	"TimeNextStatsReport"		"1612345678"
+	"ContentStatsID"		"-123456789"
+	"1"		"EXTRA_LIBRARY"
+}
+"#;
+
+    const APP_MANIFEST: &str = r#"
+"AppState"
+{
+	"appid"		"620980"
+	"Universe"		"1"
+	"name"		"The \"Quoted\" Game"
+	"StateFlags"		"4"
+	"installdir"		"Quoted Game"
+	"SizeOnDisk"		"1524871234"
+	"InstalledDepots"
+	{
+		"620981"
+		{
+			"manifest"		"5412038792283648765"
+			"size"		"1524871234"
+		}
+	}
+	"UserConfig"
+	{
+		"language"		"english"
+	}
+}
+"#;
+
+    fn library(root: &Path) -> PathBuf {
+        fs::create_dir_all(root.join("steamapps")).unwrap();
+        root.to_path_buf()
+    }
+
+    #[test]
+    fn nested_sections_and_escapes_are_parsed() {
+        let doc = parse_vdf(APP_MANIFEST).unwrap();
+        let state = doc.get("appstate").unwrap();
+
+        assert_eq!(state.get("name").and_then(Vdf::as_str), Some("The \"Quoted\" Game"));
+        let depot = state.get("InstalledDepots").and_then(|d| d.get("620981")).unwrap();
+        assert_eq!(depot.get("manifest").and_then(Vdf::as_str), Some("5412038792283648765"));
+        assert_eq!(state.get("UserConfig").unwrap().entries().len(), 1);
+        // Values have no children and objects no value
+        assert!(state.get("appid").unwrap().entries().is_empty());
+        assert_eq!(state.get("UserConfig").unwrap().as_str(), None);
+    }
+
+    #[test]
+    fn backslashes_comments_and_conditionals() {
+        let doc = parse_vdf(
+            r#"// Written by Steam
+"root"
+{
+	"path"		"D:\\SteamLibrary"
+	"unquoted"	value [$WIN32]
+	"tabbed"		"a\tb"
+}"#,
+        )
+        .unwrap();
+        let root = doc.get("root").unwrap();
+
+        assert_eq!(root.get("path").and_then(Vdf::as_str), Some("D:\\SteamLibrary"));
+        assert_eq!(root.get("unquoted").and_then(Vdf::as_str), Some("value"));
+        assert_eq!(root.get("tabbed").and_then(Vdf::as_str), Some("a\tb"));
+    }
+
+    #[test]
+    fn malformed_documents_are_rejected() {
+        assert!(parse_vdf("\"key\" \"unterminated").is_err());
+        assert!(parse_vdf("\"key\" { \"a\" \"b\"").is_err());
+        assert!(parse_vdf("\"key\"").is_err());
+        assert!(parse_vdf("}").is_err());
+    }
+
+    #[test]
+    fn library_folders_in_both_formats() {
+        let dir = TempDir::new().unwrap();
+        let root = library(&dir.path().join("steam"));
+        let extra = library(&dir.path().join("extra"));
+        let missing = dir.path().join("missing");
+
+        for (content, label) in [(LIBRARY_FOLDERS, "new"), (LIBRARY_FOLDERS_OLD, "old")] {
+            let content = content
+                .replace("STEAM_ROOT", &root.to_string_lossy())
+                .replace("EXTRA_LIBRARY", &extra.to_string_lossy());
+            fs::write(root.join("steamapps/libraryfolders.vdf"), content).unwrap();
+
+            // The root is listed once even though the new format repeats it
+            assert_eq!(library_folders(&root), [root.clone(), extra.clone()], "{} format", label);
+        }
+
+        // Libraries on drives that aren't mounted are left out
+        let content = LIBRARY_FOLDERS_OLD.replace("EXTRA_LIBRARY", &missing.to_string_lossy());
+        fs::write(root.join("steamapps/libraryfolders.vdf"), content).unwrap();
+        assert_eq!(library_folders(&root), [root]);
+    }
+
+    #[test]
+    fn app_manifests_are_read() {
+        let dir = TempDir::new().unwrap();
+        let root = library(dir.path());
+        fs::write(root.join("steamapps/appmanifest_620980.acf"), APP_MANIFEST).unwrap();
+
+        let app = read_app_manifest(&root, 620980).unwrap();
+        assert_eq!(app.app_id, 620980);
+        assert_eq!(app.name, "The \"Quoted\" Game");
+        assert_eq!(app.install_dir, root.join("steamapps/common/Quoted Game"));
+        assert_eq!(app.size_on_disk, Some(1524871234));
+        assert!(read_app_manifest(&root, 1).is_none());
+    }
+
+    #[test]
+    fn installed_apps_skip_tools_and_find_prefixes() {
+        let dir = TempDir::new().unwrap();
+        let root = library(dir.path());
+        let steamapps = root.join("steamapps");
+        fs::write(steamapps.join("appmanifest_620980.acf"), APP_MANIFEST).unwrap();
+        let tool = APP_MANIFEST
+            .replace("620980", "1493710")
+            .replace("The \\\"Quoted\\\" Game", "Proton Experimental");
+        fs::write(steamapps.join("appmanifest_1493710.acf"), tool).unwrap();
+        fs::create_dir_all(steamapps.join("compatdata/620980/pfx")).unwrap();
+        fs::create_dir_all(root.join("userdata/12345/620980/remote")).unwrap();
+
+        let apps = installed_apps(&root);
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].proton_prefix, Some(steamapps.join("compatdata/620980/pfx")));
+        assert_eq!(apps[0].cloud_save_paths, [root.join("userdata/12345/620980/remote")]);
+    }
+}
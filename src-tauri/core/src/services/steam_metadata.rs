@@ -0,0 +1,188 @@
+//! Steam store metadata (`appdetails`) and cover image downloads.
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const STEAM_APP_DETAILS_API: &str = "https://store.steampowered.com/api/appdetails";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SteamAppDetails {
+    pub app_id: u32,
+    pub name: String,
+    pub header_image: Option<String>,
+    pub capsule_image: Option<String>,
+    pub platforms: SteamPlatforms,
+    pub release_date: Option<String>,
+    pub coming_soon: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct SteamPlatforms {
+    pub windows: bool,
+    pub mac: bool,
+    pub linux: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct AppDetailsEnvelope {
+    success: bool,
+    data: Option<AppDetailsData>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AppDetailsData {
+    name: String,
+    header_image: Option<String>,
+    capsule_image: Option<String>,
+    #[serde(default)]
+    platforms: SteamPlatforms,
+    release_date: Option<AppReleaseDate>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AppReleaseDate {
+    #[serde(default)]
+    coming_soon: bool,
+    date: Option<String>,
+}
+
+pub fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent("sync-saves/0.1 (Steam lookup)")
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))
+}
+
+/// Fetches store details. Returns `Ok(None)` when Steam has no page for the app
+/// (delisted games, tools, region-locked titles).
+pub async fn fetch_app_details(
+    client: &reqwest::Client,
+    app_id: u32,
+) -> Result<Option<SteamAppDetails>, String> {
+    let resp = client
+        .get(STEAM_APP_DETAILS_API)
+        .query(&[("appids", app_id.to_string()), ("l", "english".to_string())])
+        .send()
+        .await
+        .map_err(|e| format!("Steam request failed: {e}"))?;
+
+    if !resp.status().is_success() {
+        return Err(format!("Steam returned HTTP {}", resp.status()));
+    }
+
+    let mut json: HashMap<String, AppDetailsEnvelope> = resp
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Steam response: {e}"))?;
+
+    let Some(envelope) = json.remove(&app_id.to_string()) else {
+        return Ok(None);
+    };
+    let Some(data) = envelope.data.filter(|_| envelope.success) else {
+        return Ok(None);
+    };
+
+    let (release_date, coming_soon) = data
+        .release_date
+        .map(|r| (r.date.filter(|d| !d.is_empty()), r.coming_soon))
+        .unwrap_or((None, false));
+
+    Ok(Some(SteamAppDetails {
+        app_id,
+        name: data.name,
+        header_image: data.header_image,
+        capsule_image: data.capsule_image,
+        platforms: data.platforms,
+        release_date,
+        coming_soon,
+    }))
+}
+
+/// Cover image URLs to try, best first. The portrait library capsule is missing
+/// for many older titles, so we fall back to the landscape images.
+pub fn cover_url_candidates(app_id: u32, details: Option<&SteamAppDetails>) -> Vec<String> {
+    let mut urls = vec![
+        format!("https://shared.fastly.steamstatic.com/store_item_assets/steam/apps/{}/library_600x900.jpg", app_id),
+        format!("https://shared.fastly.steamstatic.com/store_item_assets/steam/apps/{}/library_600x900_2x.jpg", app_id),
+        format!("https://cdn.cloudflare.steamstatic.com/steam/apps/{}/library_600x900.jpg", app_id),
+    ];
+    if let Some(details) = details {
+        urls.extend(details.header_image.clone());
+        urls.extend(details.capsule_image.clone());
+    }
+    urls.push(format!("https://shared.fastly.steamstatic.com/store_item_assets/steam/apps/{}/header.jpg", app_id));
+    urls.push(format!("https://cdn.cloudflare.steamstatic.com/steam/apps/{}/capsule_616x353.jpg", app_id));
+
+    let mut seen = std::collections::HashSet::new();
+    urls.retain(|u| seen.insert(u.clone()));
+    urls
+}
+
+/// Downloads the first URL that answers with an image into `dest`.
+/// Returns the URL that worked.
+pub async fn download_first_image(
+    client: &reqwest::Client,
+    urls: &[String],
+    dest: &Path,
+) -> Result<String, String> {
+    for url in urls {
+        let resp = match client.get(url).send().await {
+            Ok(resp) if resp.status().is_success() => resp,
+            _ => continue,
+        };
+        let is_image = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.starts_with("image/"))
+            .unwrap_or(false);
+        if !is_image {
+            continue;
+        }
+        let Ok(bytes) = resp.bytes().await else {
+            continue;
+        };
+        if bytes.is_empty() {
+            continue;
+        }
+
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        // Write next to the target and rename so a half-written file is never served
+        let tmp = dest.with_extension("part");
+        fs::write(&tmp, &bytes).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, dest).map_err(|e| e.to_string())?;
+        return Ok(url.clone());
+    }
+
+    Err("No cover image available".to_string())
+}
+
+/// Where a cover for `key` (a Steam app id or a game id) is cached.
+pub fn cover_cache_file(cache_dir: &Path, key: &str) -> PathBuf {
+    cache_dir.join("covers").join(format!("{}.img", key))
+}
+
+/// Reads a cached cover as a `data:` URL so the webview can show it offline.
+pub fn cover_data_url(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    let mime = if bytes.starts_with(&[0x89, b'P', b'N', b'G']) {
+        "image/png"
+    } else if bytes.starts_with(b"RIFF") {
+        "image/webp"
+    } else {
+        "image/jpeg"
+    };
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        general_purpose::STANDARD.encode(bytes)
+    ))
+}
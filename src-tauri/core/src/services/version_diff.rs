@@ -0,0 +1,276 @@
+//! Compares two save archives without extracting them to disk.
+
+use crate::services::analysis_runner::flatten;
+use crate::services::compression::{LOCATIONS_MANIFEST, SINGLE_FILE_MARKER};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{self, Cursor, Read};
+use zip::ZipArchive;
+
+/// Files above this size only get a size/checksum comparison.
+const MAX_CONTENT_DIFF_BYTES: u64 = 1024 * 1024;
+/// Line diffs are quadratic, keep them to reasonably small files.
+const MAX_TEXT_DIFF_LINES: usize = 5000;
+const MAX_REPORTED_CHANGES: usize = 500;
+
+#[derive(Debug, Clone)]
+struct ArchiveEntry {
+    size: u64,
+    crc32: u32,
+    index: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeStatus {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FileChange {
+    pub path: String,
+    pub status: ChangeStatus,
+    pub size_a: Option<u64>,
+    pub size_b: Option<u64>,
+    pub size_delta: i64,
+    pub content: Option<ContentDiff>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ContentDiff {
+    /// Changed values by flattened key
+    Json { changes: Vec<ValueChange>, truncated: bool },
+    /// Changed lines, with 1-based line numbers in each version
+    Text { lines: Vec<TextLine>, truncated: bool },
+}
+
+#[derive(Serialize, Debug)]
+pub struct ValueChange {
+    pub key: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    /// `after - before` when both are numbers
+    pub delta: Option<f64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TextLine {
+    /// `+` or `-`
+    pub op: char,
+    pub line_a: Option<usize>,
+    pub line_b: Option<usize>,
+    pub text: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ArchiveDiff {
+    pub files: Vec<FileChange>,
+    pub unchanged: usize,
+    pub total_size_a: u64,
+    pub total_size_b: u64,
+}
+
+/// Lists the files in an archive by display path. Single-file saves are named
+/// after `single_file_names[prefix]` (`""` for the root, `"<label>/"` for locations).
+fn read_entries(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    single_file_names: &HashMap<String, String>,
+) -> BTreeMap<String, ArchiveEntry> {
+    let mut entries = BTreeMap::new();
+    for index in 0..archive.len() {
+        let Ok(file) = archive.by_index(index) else { continue };
+        let name = file.name().to_string();
+        if file.is_dir() || name == LOCATIONS_MANIFEST {
+            continue;
+        }
+
+        let path = match name.strip_suffix(SINGLE_FILE_MARKER) {
+            Some(prefix) => {
+                let file_name = single_file_names.get(prefix).map(|s| s.as_str()).unwrap_or("save");
+                format!("{}{}", prefix, file_name)
+            }
+            None => name,
+        };
+        entries.insert(
+            path,
+            ArchiveEntry {
+                size: file.size(),
+                crc32: file.crc32(),
+                index,
+            },
+        );
+    }
+    entries
+}
+
+fn read_entry(archive: &mut ZipArchive<Cursor<&[u8]>>, entry: &ArchiveEntry) -> Option<Vec<u8>> {
+    if entry.size > MAX_CONTENT_DIFF_BYTES {
+        return None;
+    }
+    let mut file = archive.by_index(entry.index).ok()?;
+    let mut buf = Vec::with_capacity(entry.size as usize);
+    file.read_to_end(&mut buf).ok()?;
+    Some(buf)
+}
+
+pub fn diff_archives(
+    a: &[u8],
+    b: &[u8],
+    single_file_names: &HashMap<String, String>,
+) -> io::Result<ArchiveDiff> {
+    let mut archive_a = ZipArchive::new(Cursor::new(a))?;
+    let mut archive_b = ZipArchive::new(Cursor::new(b))?;
+    let entries_a = read_entries(&mut archive_a, single_file_names);
+    let entries_b = read_entries(&mut archive_b, single_file_names);
+
+    let paths: BTreeSet<&String> = entries_a.keys().chain(entries_b.keys()).collect();
+    let mut files = Vec::new();
+    let mut unchanged = 0;
+
+    for path in paths {
+        let (ea, eb) = (entries_a.get(path), entries_b.get(path));
+        let status = match (ea, eb) {
+            (Some(x), Some(y)) if x.crc32 == y.crc32 && x.size == y.size => {
+                unchanged += 1;
+                continue;
+            }
+            (Some(_), Some(_)) => ChangeStatus::Modified,
+            (None, Some(_)) => ChangeStatus::Added,
+            (Some(_), None) => ChangeStatus::Removed,
+            (None, None) => continue,
+        };
+
+        let content = match (ea, eb) {
+            (Some(x), Some(y)) => match (read_entry(&mut archive_a, x), read_entry(&mut archive_b, y)) {
+                (Some(before), Some(after)) => diff_content(&before, &after),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let size_a = ea.map(|e| e.size);
+        let size_b = eb.map(|e| e.size);
+        files.push(FileChange {
+            path: path.clone(),
+            status,
+            size_a,
+            size_b,
+            size_delta: size_b.unwrap_or(0) as i64 - size_a.unwrap_or(0) as i64,
+            content,
+        });
+    }
+
+    Ok(ArchiveDiff {
+        files,
+        unchanged,
+        total_size_a: entries_a.values().map(|e| e.size).sum(),
+        total_size_b: entries_b.values().map(|e| e.size).sum(),
+    })
+}
+
+fn as_text(bytes: &[u8]) -> Option<&str> {
+    if bytes.contains(&0) {
+        return None;
+    }
+    std::str::from_utf8(bytes).ok().map(|s| s.trim_start_matches('\u{feff}'))
+}
+
+/// JSON saves get a per-key diff, other text a line diff; binary files get nothing.
+fn diff_content(before: &[u8], after: &[u8]) -> Option<ContentDiff> {
+    let (text_a, text_b) = (as_text(before)?, as_text(after)?);
+
+    if let (Ok(json_a), Ok(json_b)) = (
+        serde_json::from_str::<Value>(text_a),
+        serde_json::from_str::<Value>(text_b),
+    ) {
+        if json_a.is_object() && json_b.is_object() {
+            let mut changes = diff_values(&flatten(&json_a), &flatten(&json_b));
+            let truncated = changes.len() > MAX_REPORTED_CHANGES;
+            changes.truncate(MAX_REPORTED_CHANGES);
+            return Some(ContentDiff::Json { changes, truncated });
+        }
+    }
+
+    let lines_a: Vec<&str> = text_a.lines().collect();
+    let lines_b: Vec<&str> = text_b.lines().collect();
+    if lines_a.len() > MAX_TEXT_DIFF_LINES || lines_b.len() > MAX_TEXT_DIFF_LINES {
+        return None;
+    }
+    let mut lines = diff_lines(&lines_a, &lines_b);
+    let truncated = lines.len() > MAX_REPORTED_CHANGES;
+    lines.truncate(MAX_REPORTED_CHANGES);
+    Some(ContentDiff::Text { lines, truncated })
+}
+
+/// Compares two flattened maps, keys in sorted order.
+pub fn diff_values(before: &serde_json::Map<String, Value>, after: &serde_json::Map<String, Value>) -> Vec<ValueChange> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let (a, b) = (before.get(key), after.get(key));
+            if a == b {
+                return None;
+            }
+            Some(value_change(key, a.cloned(), b.cloned()))
+        })
+        .collect()
+}
+
+pub fn value_change(key: &str, before: Option<Value>, after: Option<Value>) -> ValueChange {
+    let delta = match (before.as_ref().and_then(Value::as_f64), after.as_ref().and_then(Value::as_f64)) {
+        (Some(a), Some(b)) => Some(b - a),
+        _ => None,
+    };
+    ValueChange {
+        key: key.to_string(),
+        before,
+        after,
+        delta,
+    }
+}
+
+/// Line diff from the longest common subsequence; only changed lines are returned.
+fn diff_lines(a: &[&str], b: &[&str]) -> Vec<TextLine> {
+    let (n, m) = (a.len(), b.len());
+    // lcs[i][j] = LCS length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a[i] == b[j] {
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push(TextLine {
+                op: '-',
+                line_a: Some(i + 1),
+                line_b: None,
+                text: a[i].to_string(),
+            });
+            i += 1;
+        } else {
+            out.push(TextLine {
+                op: '+',
+                line_a: None,
+                line_b: Some(j + 1),
+                text: b[j].to_string(),
+            });
+            j += 1;
+        }
+    }
+    out
+}
@@ -0,0 +1,86 @@
+use crate::db;
+use crate::utils::file_pattern;
+use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::PathBuf;
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+/// Watches the save locations of every game with sync enabled on a background
+/// thread, calling `on_change` with the game id when its saves change.
+pub fn start_watcher<F>(db_path: PathBuf, on_change: F)
+where
+    F: Fn(&str) + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let (tx, rx) = channel();
+
+        // Create a watcher object, delivering debounced events.
+        // We use a small debounce to avoid multiple syncs for a single save operation.
+        let mut watcher =
+            RecommendedWatcher::new(tx, Config::default()).expect("Failed to create watcher");
+
+        loop {
+            // Get games to watch from DB
+            if let Ok(conn) = db::open(&db_path) {
+                // Each save location is watched on its own, all mapping back to the game id
+                let mut stmt = conn
+                    .prepare(
+                        "SELECT g.id, l.path, l.file_pattern FROM game_locations l
+                         JOIN games_cache g ON g.id = l.game_id
+                         WHERE g.sync_enabled = 1",
+                    )
+                    .unwrap();
+                let games_iter = stmt
+                    .query_map([], |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, String>(1)?,
+                            row.get::<_, Option<String>>(2)?,
+                        ))
+                    })
+                    .unwrap();
+
+                let mut watch_list = Vec::new();
+                for (id, path, pattern) in games_iter.flatten() {
+                    let p = PathBuf::from(&path);
+                    if p.exists() {
+                        let _ = watcher.watch(&p, RecursiveMode::Recursive);
+                        watch_list.push((id, p, file_pattern::parse_optional(pattern.as_deref())));
+                    }
+                }
+
+                // Wait for events
+                if let Ok(Ok(event)) = rx.recv_timeout(Duration::from_secs(1)) {
+                    if is_relevant_event(event.clone()) {
+                        // Find which game this path belongs to
+                        for (id, path, pattern) in &watch_list {
+                            // In shared folders, only this game's files count
+                            let matches = event.paths.iter().any(|p| match p.strip_prefix(path) {
+                                Ok(rel) => pattern.as_ref().map(|pat| pat.matches(rel)).unwrap_or(true),
+                                Err(_) => false,
+                            });
+                            if matches {
+                                println!(
+                                    "File change detected for game {}! Triggering sync...",
+                                    id
+                                );
+                                on_change(id);
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Periodically refresh the watch list (every 10 seconds or so)
+            // This is a simple implementation. A more robust one would
+            // use a channel to add/remove watches dynamically.
+            std::thread::sleep(Duration::from_secs(10));
+        }
+    });
+}
+
+fn is_relevant_event(event: Event) -> bool {
+    // We mainly care about data modifications and file creations
+    event.kind.is_modify() || event.kind.is_create()
+}
@@ -0,0 +1,123 @@
+use crate::sync::AuthConfig;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// A Supabase sign-in, kept in `device_config` so tools without the webview
+/// (the CLI) can reach the cloud as the same user.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CloudSession {
+    pub url: String,
+    pub key: String,
+    pub user_id: String,
+    pub access_token: String,
+    /// Unix seconds
+    pub expires_at: Option<i64>,
+    /// Only kept for the CLI's own login; the app's session is refreshed by the app alone
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+}
+
+impl CloudSession {
+    pub fn auth(&self) -> AuthConfig {
+        AuthConfig {
+            url: self.url.clone(),
+            key: self.key.clone(),
+            token: self.access_token.clone(),
+            user_id: self.user_id.clone(),
+        }
+    }
+
+    /// Treats tokens about to expire as expired, so requests don't fail halfway through.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|t| t - 60 <= chrono::Utc::now().timestamp())
+    }
+}
+
+pub const APP_SESSION_KEY: &str = "app_session";
+pub const CLI_SESSION_KEY: &str = "cli_session";
+
+pub fn load_session(conn: &Connection, key: &str) -> Result<Option<CloudSession>, String> {
+    let raw: Option<String> = conn
+        .query_row("SELECT value FROM device_config WHERE key = ?1", [key], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    Ok(raw.and_then(|s| serde_json::from_str(&s).ok()))
+}
+
+pub fn store_session(conn: &Connection, key: &str, session: Option<&CloudSession>) -> Result<(), String> {
+    match session {
+        Some(session) => {
+            let raw = serde_json::to_string(session).map_err(|e| e.to_string())?;
+            conn.execute(
+                "INSERT OR REPLACE INTO device_config (key, value) VALUES (?1, ?2)",
+                [key, &raw],
+            )
+        }
+        None => conn.execute("DELETE FROM device_config WHERE key = ?1", [key]),
+    }
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct TokenUser {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: String,
+    expires_at: Option<i64>,
+    user: TokenUser,
+}
+
+async fn request_token(url: &str, key: &str, grant_type: &str, body: serde_json::Value) -> Result<CloudSession, String> {
+    let res = reqwest::Client::new()
+        .post(format!("{}/auth/v1/token?grant_type={}", url, grant_type))
+        .header("apikey", key)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        let err_text = res.text().await.unwrap_or_default();
+        return Err(format!("Sign-in failed: {}", err_text));
+    }
+
+    let token: TokenResponse = res.json().await.map_err(|e| e.to_string())?;
+    Ok(CloudSession {
+        url: url.to_string(),
+        key: key.to_string(),
+        user_id: token.user.id,
+        access_token: token.access_token,
+        expires_at: token.expires_at,
+        refresh_token: Some(token.refresh_token),
+    })
+}
+
+pub async fn password_login(url: &str, key: &str, email: &str, password: &str) -> Result<CloudSession, String> {
+    request_token(url, key, "password", serde_json::json!({ "email": email, "password": password })).await
+}
+
+pub async fn refresh_session(session: &CloudSession) -> Result<CloudSession, String> {
+    let refresh_token = session
+        .refresh_token
+        .as_deref()
+        .ok_or_else(|| "Session can't be refreshed".to_string())?;
+    request_token(
+        &session.url,
+        &session.key,
+        "refresh_token",
+        serde_json::json!({ "refresh_token": refresh_token }),
+    )
+    .await
+}
+
+/// Credentials of the app's shared session while it is still valid.
+pub fn app_auth(conn: &Connection) -> Result<Option<AuthConfig>, String> {
+    Ok(load_session(conn, APP_SESSION_KEY)?
+        .filter(|s| !s.is_expired())
+        .map(|s| s.auth()))
+}
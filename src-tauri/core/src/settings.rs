@@ -0,0 +1,124 @@
+//! App settings kept in `device_config` under `setting_*` keys, and the pause
+//! rules built on them.
+
+use crate::services::metered;
+use chrono::{DateTime, Local};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// Every reason a sync is held back starts with this.
+pub const PAUSED: &str = "Sync is paused";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AppSettings {
+    pub launch_on_startup: bool,
+    pub desktop_notifications: bool,
+    pub auto_sync_enabled: bool,
+    /// Holds back every sync until turned off
+    pub sync_paused: bool,
+    /// RFC 3339 time a temporary pause ends
+    pub sync_paused_until: Option<String>,
+    /// KB/s, 0 for no limit
+    pub upload_limit_kbps: u32,
+    /// KB/s, 0 for no limit
+    pub download_limit_kbps: u32,
+    pub pause_on_metered: bool,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            launch_on_startup: true,
+            desktop_notifications: false,
+            auto_sync_enabled: true,
+            sync_paused: false,
+            sync_paused_until: None,
+            upload_limit_kbps: 0,
+            download_limit_kbps: 0,
+            pause_on_metered: true,
+        }
+    }
+}
+
+impl AppSettings {
+    /// When the temporary pause ends, if it hasn't yet.
+    pub fn paused_until(&self) -> Option<DateTime<Local>> {
+        self.sync_paused_until
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Local))
+            .filter(|t| *t > Local::now())
+    }
+}
+
+pub fn load(conn: &Connection) -> Result<AppSettings, String> {
+    let mut settings = AppSettings::default();
+
+    let mut stmt = conn
+        .prepare("SELECT key, value FROM device_config WHERE key LIKE 'setting_%'")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| e.to_string())?;
+
+    for (key, value) in rows.flatten() {
+        match key.as_str() {
+            "setting_launch_startup" => settings.launch_on_startup = value == "true",
+            "setting_notifications" => settings.desktop_notifications = value == "true",
+            "setting_auto_sync" => settings.auto_sync_enabled = value == "true",
+            "setting_sync_paused" => settings.sync_paused = value == "true",
+            "setting_sync_paused_until" => settings.sync_paused_until = Some(value).filter(|v| !v.is_empty()),
+            "setting_upload_limit_kbps" => settings.upload_limit_kbps = value.parse().unwrap_or(0),
+            "setting_download_limit_kbps" => settings.download_limit_kbps = value.parse().unwrap_or(0),
+            "setting_pause_on_metered" => settings.pause_on_metered = value == "true",
+            _ => {}
+        }
+    }
+
+    Ok(settings)
+}
+
+pub fn save(conn: &Connection, settings: &AppSettings) -> Result<(), String> {
+    let values = [
+        ("setting_launch_startup", settings.launch_on_startup.to_string()),
+        ("setting_notifications", settings.desktop_notifications.to_string()),
+        ("setting_auto_sync", settings.auto_sync_enabled.to_string()),
+        ("setting_sync_paused", settings.sync_paused.to_string()),
+        ("setting_sync_paused_until", settings.sync_paused_until.clone().unwrap_or_default()),
+        ("setting_upload_limit_kbps", settings.upload_limit_kbps.to_string()),
+        ("setting_download_limit_kbps", settings.download_limit_kbps.to_string()),
+        ("setting_pause_on_metered", settings.pause_on_metered.to_string()),
+    ];
+    for (key, value) in values {
+        conn.execute(
+            "INSERT OR REPLACE INTO device_config (key, value) VALUES (?1, ?2)",
+            [key, value.as_str()],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Why syncs are held back right now, for `game_id` or for every game.
+pub fn pause_reason(conn: &Connection, game_id: Option<&str>) -> Result<Option<String>, String> {
+    let settings = load(conn)?;
+    if settings.sync_paused {
+        return Ok(Some(PAUSED.to_string()));
+    }
+    if let Some(until) = settings.paused_until() {
+        return Ok(Some(format!("{} until {}", PAUSED, until.format("%H:%M"))));
+    }
+    if let Some(game_id) = game_id {
+        let game_paused: Option<i32> = conn
+            .query_row("SELECT sync_paused FROM games_cache WHERE id = ?1", [game_id], |row| row.get(0))
+            .map_err(|e| format!("Game not found: {}", e))?;
+        if game_paused.unwrap_or(0) != 0 {
+            return Ok(Some(format!("{} for this game", PAUSED)));
+        }
+    }
+    if settings.pause_on_metered && metered::is_metered() == Some(true) {
+        return Ok(Some(format!("{} on metered connections", PAUSED)));
+    }
+    Ok(None)
+}
@@ -0,0 +1,702 @@
+use crate::versions::{cache_version_meta, label_taken, VersionMeta};
+use crate::db;
+use crate::queue::{self, QueuedSync};
+use crate::services::{bandwidth, compression, extraction};
+use crate::settings;
+use crate::utils::file_pattern;
+use crate::utils::path_template::PathContext;
+use reqwest::header::{CONTENT_TYPE, AUTHORIZATION};
+use reqwest::multipart;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use sysinfo::System;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SyncResult {
+    pub success: bool,
+    pub message: String,
+    pub file_size: Option<u64>,
+    pub checksum: Option<String>,
+    pub version_id: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub cloud_game_id: Option<String>,
+    pub device_id: Option<String>,
+    pub skipped: bool,
+    /// The backend couldn't be reached; the archive waits in the queue
+    #[serde(default)]
+    pub queued: bool,
+}
+
+impl SyncResult {
+    fn queued(archive: &Archive, start_time: Instant) -> Self {
+        SyncResult {
+            success: true,
+            message: "Offline, sync queued".to_string(),
+            file_size: Some(archive.size),
+            checksum: Some(archive.checksum.clone()),
+            version_id: None,
+            duration_ms: Some(start_time.elapsed().as_millis() as u64),
+            cloud_game_id: None,
+            device_id: None,
+            skipped: false,
+            queued: true,
+        }
+    }
+}
+
+/// An archive of a game's saves, ready to upload.
+pub struct Archive {
+    pub path: PathBuf,
+    pub checksum: String,
+    pub size: u64,
+}
+
+impl Archive {
+    pub fn from_file(path: PathBuf) -> Result<Self, String> {
+        let mut file = fs::File::open(&path).map_err(|e| e.to_string())?;
+        let mut hasher = Sha256::new();
+        let mut buffer = [0; 8192];
+        loop {
+            let count = file.read(&mut buffer).map_err(|e| e.to_string())?;
+            if count == 0 {
+                break;
+            }
+            hasher.update(&buffer[..count]);
+        }
+        let size = file.metadata().map_err(|e| e.to_string())?.len();
+        Ok(Archive {
+            path,
+            checksum: hex::encode(hasher.finalize()),
+            size,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AuthConfig {
+    pub url: String,
+    pub key: String,
+    pub token: String,
+    pub user_id: String,
+}
+
+// Helper types for Supabase responses
+#[derive(Deserialize)]
+struct CloudGame {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct CloudChecksum {
+    checksum: String,
+}
+
+#[derive(Deserialize)]
+struct CloudDevice {
+    id: String,
+}
+
+/// Uploads the game's saves as a new cloud version unless they match the latest one.
+/// When the backend can't be reached, or earlier saves of the game are still
+/// waiting, the archive is queued instead (see [`queue`]).
+pub async fn run_sync(
+    db_path: &Path,
+    game_id: &str,
+    auth: &AuthConfig,
+    metadata: Option<VersionMeta>,
+) -> Result<SyncResult, String> {
+    let start_time = Instant::now();
+    let conn = db::open(db_path).map_err(|e| e.to_string())?;
+
+    if let Some(reason) = sync_blocked(&conn, game_id)? {
+        return Err(reason);
+    }
+    let metadata = metadata.map(VersionMeta::normalized).transpose()?;
+
+    let archive = archive_saves(&conn, game_id)?;
+
+    // Nothing goes up until the user retries or discards the failed uploads
+    if queue::failed_count(&conn, Some(game_id))? > 0 {
+        let mut queued = SyncResult::queued(&archive, start_time);
+        queued.message = queue::HELD_BY_FAILED.to_string();
+        queue::enqueue(db_path, &conn, game_id, &archive, metadata.as_ref())?;
+        return Ok(queued);
+    }
+
+    // Earlier saves still waiting go up first, so versions keep their order
+    if queue::pending_count(&conn, Some(game_id))? > 0 {
+        let queued = SyncResult::queued(&archive, start_time);
+        queue::enqueue(db_path, &conn, game_id, &archive, metadata.as_ref())?;
+        let mut report = queue::flush(db_path, auth, Some(game_id)).await?;
+        return match report.results.pop() {
+            Some(last) if report.remaining == 0 => last.result,
+            Some(QueuedSync { result: Err(e), .. }) if !report.offline => Err(e),
+            _ => Ok(queued),
+        };
+    }
+
+    match upload_archive(db_path, game_id, &archive, auth, metadata.as_ref(), start_time).await {
+        Err(e) if !queue::is_online(&auth.url).await => {
+            eprintln!("Backend unreachable, queueing sync of {}: {}", game_id, e);
+            let queued = SyncResult::queued(&archive, start_time);
+            queue::enqueue(db_path, &conn, game_id, &archive, metadata.as_ref())?;
+            Ok(queued)
+        }
+        result => result,
+    }
+}
+
+/// Archives the game's saves, every location of it, into a temp zip.
+fn archive_saves(conn: &Connection, game_id: &str) -> Result<Archive, String> {
+    // 1. Get Game Details
+    let (slug, local_path): (String, String) = conn
+        .query_row(
+            "SELECT slug, local_path FROM games_cache WHERE id = ?1",
+            [game_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| format!("Game not found: {}", e))?;
+
+    let locations = crate::games::load_game_locations(conn, game_id)
+        .map_err(|e| e.to_string())?;
+
+    // 2. Compress
+    let dst_path = compression::get_temp_zip_path(&slug);
+    if locations.len() > 1 || locations.iter().any(|l| l.file_pattern.is_some()) {
+        // Every location goes into the same version under its own label prefix
+        let sources: Vec<compression::ArchiveLocation> = locations
+            .iter()
+            .map(|l| compression::ArchiveLocation {
+                label: l.label.clone(),
+                path: PathBuf::from(&l.path),
+                pattern: file_pattern::parse_optional(l.file_pattern.as_deref()),
+            })
+            .collect();
+        compression::compress_locations(&sources, &dst_path)
+            .map_err(|e| format!("Compression failed: {}", e))?;
+    } else {
+        let src_path = Path::new(&local_path);
+        if !src_path.exists() {
+            return Err(format!("Local path does not exist: {}", local_path));
+        }
+        compression::compress_path(src_path, &dst_path)
+            .map_err(|e| format!("Compression failed: {}", e))?;
+    }
+
+    // 3. Calculate Checksum
+    Archive::from_file(dst_path)
+}
+
+/// Queues the game's current saves without reaching the backend, for when
+/// they can't be uploaded now (e.g. signed out); the queue sends them later.
+pub fn queue_saves(db_path: &Path, game_id: &str) -> Result<SyncResult, String> {
+    let start_time = Instant::now();
+    let conn = db::open(db_path).map_err(|e| e.to_string())?;
+    if let Some(reason) = sync_blocked(&conn, game_id)? {
+        return Err(reason);
+    }
+    let archive = archive_saves(&conn, game_id)?;
+    let mut queued = SyncResult::queued(&archive, start_time);
+    queued.message = "Sync queued until the cloud can be reached".to_string();
+    queue::enqueue(db_path, &conn, game_id, &archive, None)?;
+    Ok(queued)
+}
+
+/// Why the game's saves can't be uploaded right now, if they can't.
+pub fn sync_blocked(conn: &Connection, game_id: &str) -> Result<Option<String>, String> {
+    let sync_enabled: bool = conn
+        .query_row("SELECT sync_enabled FROM games_cache WHERE id = ?1", [game_id], |row| {
+            Ok(row.get::<_, i32>(0)? != 0)
+        })
+        .map_err(|e| format!("Game not found: {}", e))?;
+    if !sync_enabled {
+        return Ok(Some("Sync is disabled for this game".to_string()));
+    }
+    settings::pause_reason(conn, Some(game_id))
+}
+
+/// Uploads an archive of the game's saves unless it matches the latest cloud
+/// version, then removes it.
+pub(crate) async fn upload_archive(
+    db_path: &Path,
+    game_id: &str,
+    archive: &Archive,
+    auth: &AuthConfig,
+    metadata: Option<&VersionMeta>,
+    start_time: Instant,
+) -> Result<SyncResult, String> {
+    let conn = db::open(db_path).map_err(|e| e.to_string())?;
+    let (name, slug, local_path, cover_url, steam_app_id): (String, String, String, Option<String>, Option<u32>) = conn
+        .query_row(
+            "SELECT name, slug, local_path, cover_url, steam_app_id FROM games_cache WHERE id = ?1",
+            [game_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .map_err(|e| format!("Game not found: {}", e))?;
+    let locations = crate::games::load_game_locations(&conn, game_id)
+        .map_err(|e| e.to_string())?;
+    let upload_limit = settings::load(&conn)?.upload_limit_kbps;
+    let (checksum, file_size) = (archive.checksum.clone(), archive.size);
+
+    let client = reqwest::Client::new();
+    let headers = construct_headers(&auth.key, &auth.token);
+
+    // 4. Ensure Device Exists (Register Device)
+    // FIX: Get REAL persistent machine ID from system.rs Logic
+    let machine_id = crate::device::device_id(&conn)
+        .map_err(|e| format!("Failed to get valid machine ID: {}", e))?;
+        
+    let hostname = System::host_name().unwrap_or("Unknown PC".to_string());
+
+    let device_id = ensure_device(&client, auth, &machine_id, &hostname).await?;
+
+    // 5. Ensure Cloud Game Exists
+    let cloud_game_id = ensure_cloud_game(&client, auth, &name, &slug, cover_url.as_deref()).await?;
+
+    // 6. Upsert Game Path
+    upsert_game_path(&client, auth, &cloud_game_id, &device_id, &local_path, true).await?;
+
+    // 6b. Publish portable templates so other devices can resolve the paths locally
+    let ctx = PathContext::current(steam_app_id);
+    let templates: Vec<serde_json::Value> = locations
+        .iter()
+        .map(|l| {
+            serde_json::json!({
+                "label": l.label,
+                "template": l.template.clone().unwrap_or_else(|| ctx.to_template(&l.path)),
+            })
+        })
+        .collect();
+    if let Err(e) = upload_path_templates(&client, auth, &cloud_game_id, &device_id, &templates, steam_app_id).await {
+        // Older backends lack the template columns; the sync itself still works
+        eprintln!("Skipping path template upload: {}", e);
+    }
+
+    // 7. Check Latest Checksum
+    let latest_checksum = get_latest_checksum(&client, auth, &cloud_game_id).await?;
+    
+    if let Some(latest) = latest_checksum {
+        if latest == checksum {
+            let _ = fs::remove_file(&archive.path);
+            mark_synced(&conn, game_id, None)?;
+            return Ok(SyncResult {
+                success: true,
+                message: "Content unchanged, sync skipped".to_string(),
+                file_size: Some(file_size),
+                checksum: Some(checksum),
+                version_id: None,
+                duration_ms: Some(start_time.elapsed().as_millis() as u64),
+                cloud_game_id: Some(cloud_game_id),
+                device_id: Some(device_id),
+                skipped: true,
+                queued: false,
+            });
+        }
+    }
+
+    // 8. Upload File
+    let version_id = Uuid::new_v4().to_string();
+    let storage_path = format!("{}/{}/{}.zip", auth.user_id, slug, version_id);
+
+    // Re-open file for upload body
+    // reqwest multipart file from path
+    let file_content = fs::read(&archive.path).map_err(|e| format!("Failed to read zip for upload: {}", e))?;
+    
+    let part = multipart::Part::stream_with_length(
+        bandwidth::upload_body(file_content, upload_limit),
+        file_size,
+    )
+    .file_name(format!("{}.zip", version_id))
+    .mime_str("application/zip")
+    .map_err(|e| e.to_string())?;
+
+    let form = multipart::Form::new().part("", part);
+
+    let upload_res = client
+        .post(format!("{}/storage/v1/object/saves/{}", auth.url, storage_path))
+        .headers(headers.clone()) // Headers need to be cloned or reconstructed
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| format!("Upload request failed: {}", e))?;
+
+    if !upload_res.status().is_success() {
+        let err_text = upload_res.text().await.unwrap_or_default();
+        return Err(format!("Upload failed: {}", err_text));
+    }
+
+    // 9. Create Save Version Record
+    create_save_version(&client, auth, &version_id, &cloud_game_id, &device_id, &storage_path, file_size, &checksum, metadata).await?;
+    if let Some(meta) = metadata {
+        cache_version_meta(&conn, game_id, &version_id, meta, &chrono::Utc::now().to_rfc3339())
+            .map_err(|e| e.to_string())?;
+    }
+
+    // 10. Clean up
+    let _ = fs::remove_file(&archive.path);
+    mark_synced(&conn, game_id, Some(&version_id))?;
+
+    Ok(SyncResult {
+        success: true,
+        message: "Sync successful".to_string(),
+        file_size: Some(file_size),
+        checksum: Some(checksum),
+        version_id: Some(version_id),
+        duration_ms: Some(start_time.elapsed().as_millis() as u64),
+        cloud_game_id: Some(cloud_game_id),
+        device_id: Some(device_id),
+        skipped: false,
+        queued: false,
+    })
+}
+
+// --- Helper Functions ---
+
+/// Records a sync in `games_cache`; `version_id` is the version now matching the local saves, if known.
+pub fn mark_synced(conn: &Connection, game_id: &str, version_id: Option<&str>) -> Result<(), String> {
+    conn.execute(
+        "UPDATE games_cache SET last_synced_at = ?1, last_synced_id = COALESCE(?2, last_synced_id) WHERE id = ?3",
+        rusqlite::params![chrono::Utc::now().to_rfc3339(), version_id, game_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn construct_headers(key: &str, token: &str) -> reqwest::header::HeaderMap {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("apikey", key.parse().unwrap());
+    headers.insert(AUTHORIZATION, format!("Bearer {}", token).parse().unwrap());
+    headers
+}
+
+async fn ensure_device(client: &reqwest::Client, auth: &AuthConfig, machine_id: &str, device_name: &str) -> Result<String, String> {
+    // Try to find device by MACHINE ID (not name)
+    let url = format!("{}/rest/v1/devices?user_id=eq.{}&machine_id=eq.{}&select=id", auth.url, auth.user_id, machine_id);
+    let res = client.get(&url).headers(construct_headers(&auth.key, &auth.token)).send().await.map_err(|e| e.to_string())?;
+    
+    let devices: Vec<CloudDevice> = res.json().await.map_err(|e| e.to_string())?;
+    if let Some(d) = devices.first() {
+        return Ok(d.id.clone());
+    }
+
+    // Create device (UPSERT technically matches devices.ts, but here check-then-create is fine for now if no race condition)
+    // Actually, to fully match devices.ts, we should UPSERT to handle name changes.
+    // Using POST with Prefer: resolution=merge-duplicates on user_id, machine_id constraint
+    
+    let create_url = format!("{}/rest/v1/devices", auth.url);
+    let body = serde_json::json!({
+        "user_id": auth.user_id,
+        "machine_id": machine_id,
+        "name": device_name,
+        "type": "desktop",
+        "os": std::env::consts::OS
+    });
+
+    let res = client.post(&create_url)
+        .headers(construct_headers(&auth.key, &auth.token))
+        .header("Prefer", "return=representation,resolution=merge-duplicates") // Ensure this matches constraint
+        .header(CONTENT_TYPE, "application/json")
+        .json(&body)
+        .send().await.map_err(|e| e.to_string())?;
+
+    let created: Vec<CloudDevice> = res.json().await.map_err(|e| format!("Failed to parse created device: {}", e))?;
+    created.first().map(|d| d.id.clone()).ok_or("Failed to create device".to_string())
+}
+
+pub async fn find_cloud_game(client: &reqwest::Client, auth: &AuthConfig, slug: &str) -> Result<Option<String>, String> {
+    let url = format!("{}/rest/v1/games?user_id=eq.{}&slug=eq.{}&select=id", auth.url, auth.user_id, slug);
+    let res = client.get(&url).headers(construct_headers(&auth.key, &auth.token)).send().await.map_err(|e| e.to_string())?;
+
+    let games: Vec<CloudGame> = res.json().await.map_err(|e| e.to_string())?;
+    Ok(games.first().map(|g| g.id.clone()))
+}
+
+async fn ensure_cloud_game(client: &reqwest::Client, auth: &AuthConfig, name: &str, slug: &str, cover_url: Option<&str>) -> Result<String, String> {
+    if let Some(id) = find_cloud_game(client, auth, slug).await? {
+        return Ok(id);
+    }
+
+    // Create game
+    let create_url = format!("{}/rest/v1/games", auth.url);
+    let body = serde_json::json!({
+        "user_id": auth.user_id,
+        "name": name,
+        "slug": slug,
+        "cover_url": cover_url
+    });
+
+    let res = client.post(&create_url)
+        .headers(construct_headers(&auth.key, &auth.token))
+        .header("Prefer", "return=representation")
+        .header(CONTENT_TYPE, "application/json")
+        .json(&body)
+        .send().await.map_err(|e| e.to_string())?;
+
+    let created: Vec<CloudGame> = res.json().await.map_err(|e| e.to_string())?;
+    created.first().map(|g| g.id.clone()).ok_or("Failed to create cloud game".to_string())
+}
+
+async fn upsert_game_path(client: &reqwest::Client, auth: &AuthConfig, cloud_game_id: &str, device_id: &str, local_path: &str, sync_enabled: bool) -> Result<(), String> {
+    let url = format!("{}/rest/v1/game_paths", auth.url);
+    let body = serde_json::json!({
+        "game_id": cloud_game_id,
+        "device_id": device_id,
+        "local_path": local_path,
+        "sync_enabled": sync_enabled
+    });
+
+    let res = client.post(&url)
+        .headers(construct_headers(&auth.key, &auth.token))
+        .header("Prefer", "resolution=merge-duplicates")
+        .header(CONTENT_TYPE, "application/json")
+        .json(&body)
+        .send().await.map_err(|e| e.to_string())?;
+
+    if !res.status().is_success() {
+        return Err(format!("Failed to upsert game path: {}", res.status()));
+    }
+    Ok(())
+}
+
+async fn upload_path_templates(client: &reqwest::Client, auth: &AuthConfig, cloud_game_id: &str, device_id: &str, templates: &[serde_json::Value], steam_app_id: Option<u32>) -> Result<(), String> {
+    let primary_template = templates.first().and_then(|t| t["template"].as_str());
+
+    let path_url = format!("{}/rest/v1/game_paths?game_id=eq.{}&device_id=eq.{}", auth.url, cloud_game_id, device_id);
+    let res = client.patch(&path_url)
+        .headers(construct_headers(&auth.key, &auth.token))
+        .header(CONTENT_TYPE, "application/json")
+        .json(&serde_json::json!({ "path_template": primary_template }))
+        .send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(format!("Failed to update game path template: {}", res.status()));
+    }
+
+    let mut body = serde_json::json!({ "path_templates": templates });
+    if let Some(app_id) = steam_app_id {
+        body["steam_app_id"] = serde_json::json!(app_id);
+    }
+
+    let game_url = format!("{}/rest/v1/games?id=eq.{}", auth.url, cloud_game_id);
+    let res = client.patch(&game_url)
+        .headers(construct_headers(&auth.key, &auth.token))
+        .header(CONTENT_TYPE, "application/json")
+        .json(&body)
+        .send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(format!("Failed to update game templates: {}", res.status()));
+    }
+    Ok(())
+}
+
+async fn get_latest_checksum(client: &reqwest::Client, auth: &AuthConfig, cloud_game_id: &str) -> Result<Option<String>, String> {
+    let url = format!("{}/rest/v1/save_versions?game_id=eq.{}&is_latest=eq.true&select=checksum", auth.url, cloud_game_id);
+    let res = client.get(&url).headers(construct_headers(&auth.key, &auth.token)).send().await.map_err(|e| e.to_string())?;
+    
+    let versions: Vec<CloudChecksum> = res.json().await.map_err(|e| e.to_string())?;
+    Ok(versions.first().map(|v| v.checksum.clone()))
+}
+
+#[derive(Deserialize)]
+struct CloudVersionId {
+    id: String,
+}
+
+/// Ids of every save version the signed-in user can see.
+pub async fn list_cloud_version_ids(client: &reqwest::Client, auth: &AuthConfig) -> Result<HashSet<String>, String> {
+    const PAGE: usize = 1000;
+    let mut ids = HashSet::new();
+    let mut offset = 0;
+    loop {
+        let url = format!("{}/rest/v1/save_versions?select=id&order=id&limit={}&offset={}", auth.url, PAGE, offset);
+        let res = client.get(&url).headers(construct_headers(&auth.key, &auth.token)).send().await.map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            return Err(format!("Failed to list save versions: {}", res.status()));
+        }
+
+        let page: Vec<CloudVersionId> = res.json().await.map_err(|e| e.to_string())?;
+        let count = page.len();
+        ids.extend(page.into_iter().map(|v| v.id));
+        if count < PAGE {
+            return Ok(ids);
+        }
+        offset += PAGE;
+    }
+}
+
+/// Records the uploaded archive as the game's latest version. The server swaps
+/// the latest flag and inserts the row in one transaction, so concurrent syncs
+/// can't leave the game with no latest version. If the record isn't created,
+/// the uploaded object is deleted again.
+#[allow(clippy::too_many_arguments)]
+async fn create_save_version(client: &reqwest::Client, auth: &AuthConfig, id: &str, game_id: &str, device_id: &str, file_path: &str, file_size: u64, checksum: &str, meta: Option<&VersionMeta>) -> Result<(), String> {
+    let res = client.post(format!("{}/rest/v1/rpc/create_save_version", auth.url))
+        .headers(construct_headers(&auth.key, &auth.token))
+        .header(CONTENT_TYPE, "application/json")
+        .json(&serde_json::json!({
+            "p_id": id,
+            "p_game_id": game_id,
+            "p_device_id": device_id,
+            "p_file_path": file_path,
+            "p_file_size": file_size,
+            "p_checksum": checksum,
+            "p_label": meta.and_then(|m| m.label.as_deref()),
+            "p_notes": meta.and_then(|m| m.notes.as_deref()),
+            "p_is_pinned": meta.is_some_and(|m| m.is_pinned),
+        }))
+        .send().await.map_err(|e| e.to_string());
+
+    let res = match res {
+        // Older backends lack the function (migration 005)
+        Ok(res) if res.status() == reqwest::StatusCode::NOT_FOUND => {
+            insert_save_version(client, auth, id, game_id, device_id, file_path, file_size, checksum, meta).await
+        }
+        res => res,
+    };
+
+    let err = match res {
+        Ok(res) if res.status().is_success() => return Ok(()),
+        Ok(res) if res.status() == reqwest::StatusCode::CONFLICT => {
+            version_conflict(res, meta.and_then(|m| m.label.as_deref())).await
+        }
+        Ok(res) => format!("Failed to create save version: {}", res.status()),
+        Err(e) => {
+            // The server may have recorded the version before the connection
+            // dropped, so the object stays unless the version is known to exist
+            return match version_recorded(client, auth, file_path).await {
+                Ok(true) => Ok(()),
+                _ => Err(format!("Failed to create save version: {}", e)),
+            };
+        }
+    };
+    delete_object(client, auth, file_path).await;
+    Err(err)
+}
+
+/// Why the version clashed: its label, or another device's upload becoming the latest first.
+async fn version_conflict(res: reqwest::Response, label: Option<&str>) -> String {
+    let body = res.text().await.unwrap_or_default();
+    match label {
+        Some(_) if body.contains("idx_save_versions_game_label") => label_taken(label),
+        _ => "Another device uploaded this game at the same time; sync again to retry".to_string(),
+    }
+}
+
+/// Whether a version row points at the uploaded object.
+async fn version_recorded(client: &reqwest::Client, auth: &AuthConfig, file_path: &str) -> Result<bool, String> {
+    let res = client
+        .get(format!("{}/rest/v1/save_versions", auth.url))
+        .query(&[("file_path", format!("eq.{}", file_path)), ("select", "id".to_string())])
+        .headers(construct_headers(&auth.key, &auth.token))
+        .send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(format!("Failed to look up save version: {}", res.status()));
+    }
+    let rows: Vec<CloudVersionId> = res.json().await.map_err(|e| e.to_string())?;
+    Ok(!rows.is_empty())
+}
+
+/// Unsets the previous latest and inserts the version as two requests.
+#[allow(clippy::too_many_arguments)]
+async fn insert_save_version(client: &reqwest::Client, auth: &AuthConfig, id: &str, game_id: &str, device_id: &str, file_path: &str, file_size: u64, checksum: &str, meta: Option<&VersionMeta>) -> Result<reqwest::Response, String> {
+    // Unset previous latest
+    let update_url = format!("{}/rest/v1/save_versions?game_id=eq.{}&is_latest=eq.true", auth.url, game_id);
+    let res = client.patch(&update_url)
+        .headers(construct_headers(&auth.key, &auth.token))
+        .header(CONTENT_TYPE, "application/json")
+        .json(&serde_json::json!({ "is_latest": false }))
+        .send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        // Nothing was inserted; reported like a failed insert
+        return Ok(res);
+    }
+
+    // Create new
+    let create_url = format!("{}/rest/v1/save_versions", auth.url);
+    let mut body = serde_json::json!({
+        "id": id,
+        "game_id": game_id,
+        "device_id": device_id,
+        "file_path": file_path,
+        "file_size": file_size,
+        "checksum": checksum,
+        "is_latest": true
+    });
+    // Only sent when given, so backends without the label columns keep working
+    if let Some(meta) = meta {
+        body["label"] = serde_json::json!(meta.label);
+        body["notes"] = serde_json::json!(meta.notes);
+        body["is_pinned"] = serde_json::json!(meta.is_pinned);
+    }
+
+    client.post(&create_url)
+        .headers(construct_headers(&auth.key, &auth.token))
+        .header(CONTENT_TYPE, "application/json")
+        .json(&body)
+        .send().await.map_err(|e| e.to_string())
+}
+
+/// Deletes an uploaded object no version record points to.
+async fn delete_object(client: &reqwest::Client, auth: &AuthConfig, file_path: &str) {
+    let res = client
+        .delete(format!("{}/storage/v1/object/saves", auth.url))
+        .headers(construct_headers(&auth.key, &auth.token))
+        .header(CONTENT_TYPE, "application/json")
+        .json(&serde_json::json!({ "prefixes": [file_path] }))
+        .send()
+        .await;
+    match res {
+        Ok(res) if res.status().is_success() => {}
+        Ok(res) => eprintln!("Failed to delete orphaned upload {}: {}", file_path, res.status()),
+        Err(e) => eprintln!("Failed to delete orphaned upload {}: {}", file_path, e),
+    }
+}
+
+
+/// Extracts a version archive over the game's save locations.
+pub fn restore_archive(conn: &Connection, game_id: &str, bytes: &[u8]) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare("SELECT slug, local_path FROM games_cache WHERE id = ?1")
+        .map_err(|e| e.to_string())?;
+
+    let (slug, local_path): (String, String) = stmt
+        .query_row([game_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?;
+
+    let temp_zip = compression::get_temp_zip_path(&format!("{}_restore", slug));
+    fs::write(&temp_zip, bytes).map_err(|e| e.to_string())?;
+
+    let is_multi_location = extraction::read_location_labels(&temp_zip)
+        .map_err(|e| e.to_string())?
+        .is_some();
+
+    if is_multi_location {
+        let targets: Vec<(String, PathBuf)> = crate::games::load_game_locations(conn, game_id)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|l| (l.label, PathBuf::from(l.path)))
+            .collect();
+
+        let unmatched = extraction::extract_locations(&temp_zip, &targets).map_err(|e| e.to_string())?;
+        if !unmatched.is_empty() {
+            eprintln!(
+                "Restore for {} skipped locations not configured on this device: {}",
+                slug,
+                unmatched.join(", ")
+            );
+        }
+    } else {
+        let target = Path::new(&local_path);
+        extraction::extract_zip(&temp_zip, target).map_err(|e| e.to_string())?;
+    }
+
+    let _ = fs::remove_file(&temp_zip);
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! Filename patterns used to sync a single game's files out of a folder
+//! shared by many games (emulator save and memory card folders).
+//!
+//! Patterns are `;`-separated wildcards (`*` and `?`), matched without case.
+//! A pattern without `/` matches the file name, one with `/` matches the
+//! path relative to the location, e.g. `*/0100F2C0115B6000/*`.
+
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilePattern {
+    patterns: Vec<Vec<char>>,
+}
+
+impl FilePattern {
+    /// Returns `None` for an empty pattern, meaning "every file".
+    pub fn parse(raw: &str) -> Option<Self> {
+        let patterns: Vec<Vec<char>> = raw
+            .split(';')
+            .map(|p| p.trim().replace('\\', "/").to_lowercase())
+            .filter(|p| !p.is_empty())
+            .map(|p| p.chars().collect())
+            .collect();
+
+        if patterns.is_empty() {
+            None
+        } else {
+            Some(FilePattern { patterns })
+        }
+    }
+
+    /// `rel_path` is relative to the location folder.
+    pub fn matches(&self, rel_path: &Path) -> bool {
+        let rel = rel_path.to_string_lossy().replace('\\', "/").to_lowercase();
+        let file_name = rel.rsplit('/').next().unwrap_or(&rel).to_string();
+        let rel: Vec<char> = rel.chars().collect();
+        let file_name: Vec<char> = file_name.chars().collect();
+
+        self.patterns.iter().any(|p| {
+            let text = if p.contains(&'/') { &rel } else { &file_name };
+            wildcard_match(p, text)
+        })
+    }
+}
+
+/// Parses an optional stored pattern.
+pub fn parse_optional(raw: Option<&str>) -> Option<FilePattern> {
+    raw.and_then(FilePattern::parse)
+}
+
+fn wildcard_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it was tried against
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((sp, st)) = star {
+            // Let the last `*` swallow one more character and retry
+            p = sp + 1;
+            t = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
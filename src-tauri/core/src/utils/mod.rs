@@ -0,0 +1,2 @@
+pub mod file_pattern;
+pub mod path_template;
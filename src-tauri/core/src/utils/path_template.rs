@@ -0,0 +1,440 @@
+//! Portable save path templates.
+//!
+//! Paths are stored with PCGamingWiki-style `{{p|...}}` tokens so the same
+//! template resolves to the right absolute path on every device, including
+//! Windows games running through Proton on Linux.
+
+use crate::services::steam_library;
+use std::path::{Path, PathBuf};
+
+/// Folders a template token can point to on the current device.
+#[derive(Debug, Clone, Default)]
+pub struct PathContext {
+    pub home: Option<PathBuf>,
+    pub xdg_data: Option<PathBuf>,
+    pub xdg_config: Option<PathBuf>,
+    pub steam: Option<PathBuf>,
+    /// Every Steam library folder, the Steam root included.
+    pub steam_libraries: Vec<PathBuf>,
+    /// Windows user profile: the real one on Windows, or the `steamuser`
+    /// profile inside the game's Proton prefix on Linux.
+    pub windows_profile: Option<PathBuf>,
+    pub windows_appdata: Option<PathBuf>,
+    pub windows_local_appdata: Option<PathBuf>,
+    pub windows_documents: Option<PathBuf>,
+    pub windows_public: Option<PathBuf>,
+    pub program_data: Option<PathBuf>,
+    /// Steam account folder name under `userdata/` (`{{p|uid}}`).
+    pub steam_user_id: Option<String>,
+    /// Game install folder (`{{p|game}}`), when known.
+    pub game_dir: Option<PathBuf>,
+}
+
+impl PathContext {
+    /// Builds the context for this device. On non-Windows systems, Windows
+    /// tokens are mapped into `compatdata/<steam_app_id>/pfx` when that prefix exists.
+    pub fn current(steam_app_id: Option<u32>) -> Self {
+        let home = dirs::home_dir();
+        let steam = find_steam_root();
+        let steam_user_id = steam.as_deref().and_then(detect_steam_user_id);
+        let steam_libraries = steam
+            .as_deref()
+            .map(steam_library::library_folders)
+            .unwrap_or_default();
+
+        let mut ctx = PathContext {
+            xdg_data: std::env::var_os("XDG_DATA_HOME")
+                .map(PathBuf::from)
+                .or_else(|| home.as_ref().map(|h| h.join(".local").join("share"))),
+            xdg_config: std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| home.as_ref().map(|h| h.join(".config"))),
+            home,
+            steam,
+            steam_libraries,
+            steam_user_id,
+            ..Default::default()
+        };
+
+        if cfg!(windows) {
+            // XDG folders only exist on Linux-like systems
+            ctx.xdg_data = None;
+            ctx.xdg_config = None;
+            ctx.windows_profile = std::env::var_os("USERPROFILE")
+                .map(PathBuf::from)
+                .or_else(|| ctx.home.clone());
+            ctx.windows_appdata = std::env::var_os("APPDATA")
+                .map(PathBuf::from)
+                .or_else(dirs::config_dir);
+            ctx.windows_local_appdata = std::env::var_os("LOCALAPPDATA")
+                .map(PathBuf::from)
+                .or_else(dirs::data_local_dir);
+            ctx.windows_documents = dirs::document_dir();
+            ctx.windows_public = std::env::var_os("PUBLIC").map(PathBuf::from);
+            ctx.program_data = std::env::var_os("PROGRAMDATA").map(PathBuf::from);
+        }
+
+        ctx.for_app(steam_app_id)
+    }
+
+    /// Same context for a Steam app: `{{p|game}}` points at its install folder
+    /// when it is installed, and Windows tokens at its Proton prefix when
+    /// running on a non-Windows system and that prefix exists.
+    pub fn for_app(&self, steam_app_id: Option<u32>) -> Self {
+        let mut ctx = self.clone();
+        let Some(app_id) = steam_app_id else {
+            return ctx;
+        };
+
+        if ctx.game_dir.is_none() {
+            ctx.game_dir = self
+                .steam_libraries
+                .iter()
+                .find_map(|library| steam_library::read_app_manifest(library, app_id))
+                .map(|app| app.install_dir);
+        }
+
+        if !cfg!(windows) {
+            if let Some(prefix) = steam_library::find_proton_prefix(&self.steam_libraries, app_id) {
+                return ctx.with_proton_prefix(&prefix);
+            }
+        }
+        ctx
+    }
+
+    /// Maps the Windows tokens into a Wine/Proton prefix (the folder containing `drive_c`).
+    pub fn with_proton_prefix(mut self, prefix: &Path) -> Self {
+        let drive_c = prefix.join("drive_c");
+        let profile = drive_c.join("users").join("steamuser");
+
+        // Older Proton versions used "My Documents"
+        let documents = ["Documents", "My Documents"]
+            .iter()
+            .map(|d| profile.join(d))
+            .find(|p| p.exists())
+            .unwrap_or_else(|| profile.join("Documents"));
+
+        self.windows_appdata = Some(profile.join("AppData").join("Roaming"));
+        self.windows_local_appdata = Some(profile.join("AppData").join("Local"));
+        self.windows_documents = Some(documents);
+        self.windows_public = Some(drive_c.join("users").join("Public"));
+        self.program_data = Some(drive_c.join("ProgramData"));
+        self.windows_profile = Some(profile);
+        self
+    }
+
+    pub fn with_game_dir(mut self, game_dir: &Path) -> Self {
+        self.game_dir = Some(game_dir.to_path_buf());
+        self
+    }
+
+    fn token_value(&self, token: &str) -> Option<PathBuf> {
+        match token.to_lowercase().replace('/', "\\").as_str() {
+            "appdata" => self.windows_appdata.clone(),
+            "localappdata" => self.windows_local_appdata.clone(),
+            "localappdatalow" | "userprofile\\appdata\\locallow" => self
+                .windows_local_appdata
+                .as_ref()
+                .and_then(|p| p.parent())
+                .map(|p| p.join("LocalLow")),
+            "userprofile" => self.windows_profile.clone(),
+            "userprofile\\documents" => self.windows_documents.clone(),
+            "savedgames" => self.windows_profile.as_ref().map(|p| p.join("Saved Games")),
+            "programdata" | "allusersprofile" => self.program_data.clone(),
+            "public" => self.windows_public.clone(),
+            "username" => self
+                .windows_profile
+                .as_ref()
+                .or(self.home.as_ref())
+                .and_then(|p| p.file_name())
+                .map(PathBuf::from),
+            "home" => self.home.clone(),
+            "linuxhome" if cfg!(target_os = "linux") => self.home.clone(),
+            "osxhome" if cfg!(target_os = "macos") => self.home.clone(),
+            "xdgdatahome" => self.xdg_data.clone(),
+            "xdgconfighome" => self.xdg_config.clone(),
+            "steam" => self.steam.clone(),
+            "uid" => self.steam_user_id.as_ref().map(PathBuf::from),
+            "game" => self.game_dir.clone(),
+            _ => None,
+        }
+    }
+
+    /// Expands every `{{p|token}}` in `template` into an absolute path.
+    pub fn resolve(&self, template: &str) -> Result<PathBuf, String> {
+        let mut out = String::new();
+        let mut rest = template.trim();
+
+        while let Some(start) = rest.find("{{") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let end = after
+                .find("}}")
+                .ok_or_else(|| format!("Unterminated token in path template: {}", template))?;
+
+            let inner = &after[..end];
+            let token = inner
+                .split_once('|')
+                .filter(|(kind, _)| kind.eq_ignore_ascii_case("p"))
+                .map(|(_, name)| name.trim())
+                .ok_or_else(|| format!("Unsupported template: {{{{{}}}}}", inner))?;
+
+            let value = self
+                .token_value(token)
+                .ok_or_else(|| format!("Path token '{}' is not available on this device", token))?;
+            out.push_str(&value.to_string_lossy());
+
+            rest = &after[end + 2..];
+        }
+        out.push_str(rest);
+
+        Ok(PathBuf::from(normalize_separators(&out)))
+    }
+
+    /// Turns an absolute path into a template using the most specific known folder.
+    /// Paths outside every known folder are returned unchanged.
+    pub fn to_template(&self, path: &str) -> String {
+        // Windows tokens come first so they win ties with `home` on Windows
+        let candidates = [
+            ("appdata", self.windows_appdata.clone()),
+            ("localappdata", self.windows_local_appdata.clone()),
+            ("localappdatalow", self.token_value("localappdatalow")),
+            ("userprofile\\Documents", self.windows_documents.clone()),
+            ("savedgames", self.token_value("savedgames")),
+            ("programdata", self.program_data.clone()),
+            ("userprofile", self.windows_profile.clone()),
+            ("xdgdatahome", self.xdg_data.clone()),
+            ("xdgconfighome", self.xdg_config.clone()),
+            ("steam", self.steam.clone()),
+            ("home", self.home.clone()),
+        ];
+
+        let normalized = path.replace('\\', "/");
+        let mut best: Option<(&str, usize)> = None;
+
+        for (token, base) in candidates.iter() {
+            let Some(base) = base else { continue };
+            let base = base.to_string_lossy().replace('\\', "/");
+            let base = base.trim_end_matches('/');
+            if base.is_empty() || !starts_with_dir(&normalized, base) {
+                continue;
+            }
+            if best.map(|(_, len)| base.len() > len).unwrap_or(true) {
+                best = Some((token, base.len()));
+            }
+        }
+
+        match best {
+            Some((token, len)) => {
+                let rest = normalized[len..].trim_start_matches('/');
+                if rest.is_empty() {
+                    format!("{{{{p|{}}}}}", token)
+                } else {
+                    format!("{{{{p|{}}}}}/{}", token, rest)
+                }
+            }
+            None => path.to_string(),
+        }
+    }
+}
+
+/// Whether a stored path contains template tokens.
+pub fn is_template(path: &str) -> bool {
+    path.contains("{{")
+}
+
+/// Locates the Steam installation for the current OS.
+pub fn find_steam_root() -> Option<PathBuf> {
+    let mut candidates: Vec<PathBuf> = vec![];
+
+    if cfg!(windows) {
+        if let Some(pf86) = std::env::var_os("ProgramFiles(x86)") {
+            candidates.push(PathBuf::from(pf86).join("Steam"));
+        }
+        if let Some(pf) = std::env::var_os("ProgramFiles") {
+            candidates.push(PathBuf::from(pf).join("Steam"));
+        }
+        candidates.push(PathBuf::from("C:\\Program Files (x86)\\Steam"));
+    } else if let Some(home) = dirs::home_dir() {
+        if cfg!(target_os = "macos") {
+            candidates.push(home.join("Library/Application Support/Steam"));
+        } else {
+            candidates.push(home.join(".steam/steam"));
+            candidates.push(home.join(".local/share/Steam"));
+            // Flatpak install
+            candidates.push(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
+        }
+    }
+
+    candidates
+        .into_iter()
+        .find(|p| p.join("steamapps").is_dir())
+        .map(|p| p.canonicalize().unwrap_or(p))
+}
+
+/// Picks the Steam account under `userdata/` that was used most recently.
+fn detect_steam_user_id(steam_root: &Path) -> Option<String> {
+    std::fs::read_dir(steam_root.join("userdata"))
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            name != "0" && name.chars().all(|c| c.is_ascii_digit())
+        })
+        .max_by_key(|e| e.metadata().and_then(|m| m.modified()).ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+}
+
+fn starts_with_dir(path: &str, base: &str) -> bool {
+    let matches = if cfg!(windows) {
+        path.get(..base.len())
+            .map(|p| p.eq_ignore_ascii_case(base))
+            .unwrap_or(false)
+    } else {
+        path.starts_with(base)
+    };
+    matches && (path.len() == base.len() || path.as_bytes()[base.len()] == b'/')
+}
+
+fn normalize_separators(path: &str) -> String {
+    if cfg!(windows) {
+        path.replace('/', "\\")
+    } else {
+        path.replace('\\', "/")
+    }
+}
+
+// The expected paths use Unix separators
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn linux() -> PathContext {
+        PathContext {
+            home: Some(PathBuf::from("/home/user")),
+            xdg_data: Some(PathBuf::from("/home/user/.local/share")),
+            xdg_config: Some(PathBuf::from("/home/user/.config")),
+            steam: Some(PathBuf::from("/home/user/.steam/steam")),
+            steam_user_id: Some("12345".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolve_and_to_template_round_trip() {
+        let ctx = linux().with_proton_prefix(Path::new("/games/pfx"));
+        for template in [
+            "{{p|xdgconfighome}}/Example",
+            "{{p|appdata}}/Example/Saves",
+            "{{p|localappdata}}/Example",
+            "{{p|userprofile\\Documents}}/My Games/Example",
+            "{{p|steam}}/userdata/12345/620980/remote",
+            "{{p|home}}/.example",
+        ] {
+            let path = ctx.resolve(template).unwrap();
+            assert_eq!(ctx.to_template(&path.to_string_lossy()), template);
+        }
+        // Backslashes in templates become the platform's separator
+        assert_eq!(
+            ctx.resolve("{{p|appdata}}\\Example\\Saves").unwrap(),
+            PathBuf::from("/games/pfx/drive_c/users/steamuser/AppData/Roaming/Example/Saves")
+        );
+        // Paths outside every known folder are left alone
+        assert_eq!(ctx.to_template("/srv/saves"), "/srv/saves");
+        assert_eq!(ctx.to_template("/home/user"), "{{p|home}}");
+    }
+
+    #[test]
+    fn windows_tokens_map_into_the_proton_prefix() {
+        let ctx = linux().with_proton_prefix(Path::new("/games/pfx"));
+        let profile = Path::new("/games/pfx/drive_c/users/steamuser");
+
+        assert_eq!(ctx.resolve("{{p|appdata}}").unwrap(), profile.join("AppData/Roaming"));
+        assert_eq!(ctx.resolve("{{p|localappdata}}").unwrap(), profile.join("AppData/Local"));
+        assert_eq!(ctx.resolve("{{p|localappdatalow}}").unwrap(), profile.join("AppData/LocalLow"));
+        assert_eq!(ctx.resolve("{{p|savedgames}}").unwrap(), profile.join("Saved Games"));
+        assert_eq!(ctx.resolve("{{p|userprofile/appdata/locallow}}").unwrap(), profile.join("AppData/LocalLow"));
+        assert_eq!(ctx.resolve("{{p|programdata}}").unwrap(), Path::new("/games/pfx/drive_c/ProgramData"));
+        assert_eq!(ctx.resolve("{{p|public}}").unwrap(), Path::new("/games/pfx/drive_c/users/Public"));
+        assert_eq!(ctx.resolve("{{P|Username}}").unwrap(), Path::new("steamuser"));
+        // Linux tokens are untouched
+        assert_eq!(ctx.resolve("{{p|xdgdatahome}}").unwrap(), Path::new("/home/user/.local/share"));
+        assert_eq!(ctx.resolve("{{p|uid}}").unwrap(), Path::new("12345"));
+    }
+
+    #[test]
+    fn older_prefixes_use_my_documents() {
+        let dir = TempDir::new().unwrap();
+        let profile = dir.path().join("drive_c/users/steamuser");
+
+        // Neither exists yet: the current name
+        let ctx = linux().with_proton_prefix(dir.path());
+        assert_eq!(ctx.windows_documents, Some(profile.join("Documents")));
+
+        std::fs::create_dir_all(profile.join("My Documents")).unwrap();
+        let ctx = linux().with_proton_prefix(dir.path());
+        assert_eq!(ctx.windows_documents, Some(profile.join("My Documents")));
+
+        std::fs::create_dir_all(profile.join("Documents")).unwrap();
+        let ctx = linux().with_proton_prefix(dir.path());
+        assert_eq!(ctx.windows_documents, Some(profile.join("Documents")));
+    }
+
+    #[test]
+    fn malformed_and_unknown_tokens_fail() {
+        let ctx = linux();
+
+        let err = ctx.resolve("{{p|home/Saves").unwrap_err();
+        assert!(err.contains("Unterminated"), "{}", err);
+        let err = ctx.resolve("{{p|nosuchfolder}}/Saves").unwrap_err();
+        assert!(err.contains("not available"), "{}", err);
+        let err = ctx.resolve("{{Path|home}}").unwrap_err();
+        assert!(err.contains("Unsupported"), "{}", err);
+        // Known tokens without a value here, e.g. Windows folders without a prefix
+        assert!(ctx.resolve("{{p|appdata}}/Example").is_err());
+        assert!(ctx.resolve("{{p|game}}/Saves").is_err());
+        // Plain paths need no tokens
+        assert_eq!(ctx.resolve("/srv/saves").unwrap(), Path::new("/srv/saves"));
+    }
+
+    #[test]
+    fn prefixes_only_match_whole_folders() {
+        let ctx = linux();
+
+        assert_eq!(ctx.to_template("/home/user2/saves"), "/home/user2/saves");
+        assert_eq!(ctx.to_template("/home/user/saves"), "{{p|home}}/saves");
+        assert!(!starts_with_dir("/home/user2", "/home/user"));
+        assert!(starts_with_dir("/home/user", "/home/user"));
+        assert!(starts_with_dir("/home/user/saves", "/home/user"));
+        // The most specific folder wins
+        assert_eq!(ctx.to_template("/home/user/.local/share/game"), "{{p|xdgdatahome}}/game");
+        assert_eq!(ctx.to_template("/home/user/.local/shared/game"), "{{p|home}}/.local/shared/game");
+    }
+
+    #[test]
+    fn steam_apps_get_their_install_folder() {
+        let dir = TempDir::new().unwrap();
+        let library = dir.path().join("library");
+        std::fs::create_dir_all(library.join("steamapps/compatdata/620980/pfx")).unwrap();
+        std::fs::write(
+            library.join("steamapps/appmanifest_620980.acf"),
+            "\"AppState\" { \"appid\" \"620980\" \"name\" \"Example\" \"installdir\" \"Example Game\" }",
+        )
+        .unwrap();
+        let ctx = PathContext {
+            steam_libraries: vec![library.clone()],
+            ..linux()
+        };
+
+        let app = ctx.for_app(Some(620980));
+        assert_eq!(
+            app.resolve("{{p|game}}/Saves").unwrap(),
+            library.join("steamapps/common/Example Game/Saves")
+        );
+        let prefix = library.join("steamapps/compatdata/620980/pfx");
+        assert_eq!(app.windows_appdata, Some(prefix.join("drive_c/users/steamuser/AppData/Roaming")));
+        // Other apps keep the plain context
+        assert_eq!(ctx.for_app(Some(1)).windows_appdata, None);
+    }
+}
@@ -0,0 +1,614 @@
+use crate::db;
+use crate::settings;
+use crate::games::{delete_analyses, load_game_locations};
+use crate::sync::{construct_headers, find_cloud_game, mark_synced, restore_archive, AuthConfig};
+use crate::services::bandwidth;
+use crate::services::retention::{self, RetentionPolicy, VersionInfo};
+use crate::services::version_diff::{self, ArchiveDiff, ValueChange};
+use chrono::{DateTime, Utc};
+use reqwest::header::CONTENT_TYPE;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Storage and PostgREST deletes are sent in batches of this many versions.
+const DELETE_BATCH: usize = 100;
+const MAX_LABEL_LEN: usize = 64;
+
+#[derive(Serialize, Debug)]
+pub struct VersionDiff {
+    pub version_a: String,
+    pub version_b: String,
+    #[serde(flatten)]
+    pub archive: ArchiveDiff,
+    /// Changes in `version_analysis`, progress fields first; `None` unless both versions were analyzed
+    pub analysis: Option<Vec<ValueChange>>,
+}
+
+struct StoredAnalysis {
+    data: Map<String, Value>,
+    completion_percentage: Option<f64>,
+    play_time_seconds: Option<i64>,
+}
+
+fn load_analysis(conn: &Connection, version_id: &str) -> Result<Option<StoredAnalysis>, String> {
+    conn.query_row(
+        "SELECT analysis_data, completion_percentage, play_time_seconds FROM version_analysis WHERE version_id = ?1",
+        [version_id],
+        |row| {
+            let raw: String = row.get(0)?;
+            Ok(StoredAnalysis {
+                data: serde_json::from_str(&raw).unwrap_or_default(),
+                completion_percentage: row.get(1)?,
+                play_time_seconds: row.get(2)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+fn diff_analysis(a: &StoredAnalysis, b: &StoredAnalysis) -> Vec<ValueChange> {
+    let mut changes = Vec::new();
+    if a.completion_percentage != b.completion_percentage {
+        changes.push(version_diff::value_change(
+            "completion_percentage",
+            a.completion_percentage.map(Value::from),
+            b.completion_percentage.map(Value::from),
+        ));
+    }
+    if a.play_time_seconds != b.play_time_seconds {
+        changes.push(version_diff::value_change(
+            "play_time_seconds",
+            a.play_time_seconds.map(Value::from),
+            b.play_time_seconds.map(Value::from),
+        ));
+    }
+    changes.extend(version_diff::diff_values(&a.data, &b.data));
+    changes
+}
+
+/// File names to show for single-file saves, keyed by archive prefix.
+fn single_file_names(conn: &Connection, game_id: &str) -> Result<HashMap<String, String>, String> {
+    let file_name = |path: &str| {
+        Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+    };
+
+    let mut names = HashMap::new();
+    let local_path: String = conn
+        .query_row("SELECT local_path FROM games_cache WHERE id = ?1", [game_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if let Some(name) = file_name(&local_path) {
+        names.insert(String::new(), name);
+    }
+    for location in load_game_locations(conn, game_id).map_err(|e| e.to_string())? {
+        if let Some(name) = file_name(&location.path) {
+            names.insert(format!("{}/", location.label), name);
+        }
+    }
+    Ok(names)
+}
+
+/// Compares two versions of a game's save (zip bytes, older one first): files
+/// added, removed or modified with size deltas, per-key JSON and line diffs for
+/// text saves, and what changed in their analysis data.
+pub fn diff(
+    conn: &Connection,
+    game_id: &str,
+    version_a: String,
+    version_b: String,
+    bytes_a: &[u8],
+    bytes_b: &[u8],
+) -> Result<VersionDiff, String> {
+    let names = single_file_names(conn, game_id)?;
+    let archive = version_diff::diff_archives(bytes_a, bytes_b, &names).map_err(|e| e.to_string())?;
+
+    let analysis = match (load_analysis(conn, &version_a)?, load_analysis(conn, &version_b)?) {
+        (Some(a), Some(b)) => Some(diff_analysis(&a, &b)),
+        _ => None,
+    };
+
+    Ok(VersionDiff {
+        version_a,
+        version_b,
+        archive,
+        analysis,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CloudVersion {
+    pub id: String,
+    pub file_path: String,
+    pub file_size: u64,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub is_latest: bool,
+    #[serde(default)]
+    pub is_pinned: bool,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PrunedVersion {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub file_size: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PruneReport {
+    pub game_id: String,
+    pub dry_run: bool,
+    pub policy: RetentionPolicy,
+    pub kept: usize,
+    /// Versions removed, or that would be removed in a dry run
+    pub pruned: Vec<PrunedVersion>,
+    pub reclaimed_bytes: u64,
+}
+
+/// Every version of the cloud game, newest first, fetched a page at a time
+/// since PostgREST caps each response.
+pub async fn list_cloud_versions(
+    client: &reqwest::Client,
+    auth: &AuthConfig,
+    cloud_game_id: &str,
+) -> Result<Vec<CloudVersion>, String> {
+    const PAGE: usize = 1000;
+    let mut versions = Vec::new();
+    loop {
+        let url = format!(
+            "{}/rest/v1/save_versions?game_id=eq.{}&select=id,file_path,file_size,created_at,is_latest,is_pinned,label&order=created_at.desc,id.desc&limit={}&offset={}",
+            auth.url, cloud_game_id, PAGE, versions.len()
+        );
+        let res = client.get(&url).headers(construct_headers(&auth.key, &auth.token)).send().await.map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            let err_text = res.text().await.unwrap_or_default();
+            return Err(format!("Failed to list save versions: {}", err_text));
+        }
+
+        let page: Vec<CloudVersion> = res.json().await.map_err(|e| e.to_string())?;
+        let count = page.len();
+        versions.extend(page);
+        if count < PAGE {
+            return Ok(versions);
+        }
+    }
+}
+
+/// Deletes the versions' storage objects, then the rows of those whose object is gone.
+async fn delete_cloud_versions(
+    client: &reqwest::Client,
+    auth: &AuthConfig,
+    versions: &[&CloudVersion],
+) -> Result<Vec<String>, String> {
+    let mut deleted = Vec::new();
+    for batch in versions.chunks(DELETE_BATCH) {
+        let prefixes: Vec<&str> = batch.iter().map(|v| v.file_path.as_str()).collect();
+        let res = client
+            .delete(format!("{}/storage/v1/object/saves", auth.url))
+            .headers(construct_headers(&auth.key, &auth.token))
+            .header(CONTENT_TYPE, "application/json")
+            .json(&serde_json::json!({ "prefixes": prefixes }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            let err_text = res.text().await.unwrap_or_default();
+            return Err(format!("Failed to delete version files: {}", err_text));
+        }
+
+        let ids: Vec<&str> = batch.iter().map(|v| v.id.as_str()).collect();
+        let res = client
+            .delete(format!("{}/rest/v1/save_versions?id=in.({})", auth.url, ids.join(",")))
+            .headers(construct_headers(&auth.key, &auth.token))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            let err_text = res.text().await.unwrap_or_default();
+            return Err(format!("Failed to delete save versions: {}", err_text));
+        }
+        deleted.extend(ids.into_iter().map(String::from));
+    }
+    Ok(deleted)
+}
+
+pub fn load_retention_policy(conn: &Connection, game_id: &str) -> Result<Option<RetentionPolicy>, String> {
+    let raw: Option<String> = conn
+        .query_row("SELECT retention_policy FROM games_cache WHERE id = ?1", [game_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    Ok(raw.and_then(|s| serde_json::from_str(&s).ok()))
+}
+
+/// Sets the game's retention policy; `None` goes back to the default.
+pub fn store_retention_policy(conn: &Connection, game_id: &str, policy: Option<&RetentionPolicy>) -> Result<(), String> {
+    let raw = policy
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE games_cache SET retention_policy = ?1 WHERE id = ?2",
+        rusqlite::params![raw, game_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Deletes the game's cloud versions (storage objects and rows) that its
+/// retention policy doesn't keep. `policy` overrides the stored one; with
+/// `dry_run` nothing is deleted and the report lists what would be.
+pub async fn prune(
+    db_path: &Path,
+    game_id: &str,
+    auth: &AuthConfig,
+    policy: Option<RetentionPolicy>,
+    dry_run: bool,
+) -> Result<PruneReport, String> {
+    let (slug, stored_policy, local_pins) = {
+        let conn = db::open(db_path).map_err(|e| e.to_string())?;
+        let slug: String = conn
+            .query_row("SELECT slug FROM games_cache WHERE id = ?1", [game_id], |row| row.get(0))
+            .map_err(|e| format!("Game not found: {}", e))?;
+        (slug, load_retention_policy(&conn, game_id)?, load_local_pins(&conn, game_id)?)
+    };
+    let policy = policy.or(stored_policy).unwrap_or_default();
+
+    let client = reqwest::Client::new();
+    let versions = match find_cloud_game(&client, auth, &slug).await? {
+        Some(cloud_game_id) => list_cloud_versions(&client, auth, &cloud_game_id).await?,
+        None => vec![],
+    };
+
+    let infos: Vec<VersionInfo> = versions
+        .iter()
+        .map(|v| VersionInfo {
+            id: v.id.clone(),
+            created_at: v.created_at,
+            is_latest: v.is_latest,
+            // A pin made while the cloud update failed still protects the version
+            is_pinned: v.is_pinned || local_pins.contains(&v.id),
+        })
+        .collect();
+    let keep = retention::versions_to_keep(&infos, &policy, Utc::now());
+    let doomed: Vec<&CloudVersion> = versions.iter().filter(|v| !keep.contains(&v.id)).collect();
+
+    let pruned_ids = if dry_run || doomed.is_empty() {
+        doomed.iter().map(|v| v.id.clone()).collect()
+    } else {
+        let deleted = delete_cloud_versions(&client, auth, &doomed).await?;
+        let conn = db::open(db_path).map_err(|e| e.to_string())?;
+        delete_analyses(&conn, &deleted).map_err(|e| e.to_string())?;
+        forget_version_meta(&conn, &deleted).map_err(|e| e.to_string())?;
+        eprintln!("Pruned {} versions of {}", deleted.len(), slug);
+        deleted
+    };
+
+    let pruned: Vec<PrunedVersion> = doomed
+        .into_iter()
+        .filter(|v| pruned_ids.contains(&v.id))
+        .map(|v| PrunedVersion {
+            id: v.id.clone(),
+            created_at: v.created_at,
+            file_size: v.file_size,
+        })
+        .collect();
+
+    Ok(PruneReport {
+        game_id: game_id.to_string(),
+        dry_run,
+        policy,
+        kept: keep.len(),
+        reclaimed_bytes: pruned.iter().map(|v| v.file_size).sum(),
+        pruned,
+    })
+}
+
+/// Label, notes and pin of a cloud version. Pinned versions are never pruned.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct VersionMeta {
+    pub label: Option<String>,
+    pub notes: Option<String>,
+    pub is_pinned: bool,
+}
+
+impl VersionMeta {
+    /// Trims the label and notes, dropping empty ones.
+    pub fn normalized(self) -> Result<Self, String> {
+        let clean = |s: Option<String>| s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        let label = clean(self.label);
+        if label.as_ref().is_some_and(|l| l.chars().count() > MAX_LABEL_LEN) {
+            return Err(format!("Labels are limited to {} characters", MAX_LABEL_LEN));
+        }
+        Ok(Self {
+            label,
+            notes: clean(self.notes),
+            is_pinned: self.is_pinned,
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.label.is_none() && self.notes.is_none() && !self.is_pinned
+    }
+}
+
+pub fn label_taken(label: Option<&str>) -> String {
+    format!(
+        "Label '{}' is already used by another version of this game",
+        label.unwrap_or_default()
+    )
+}
+
+/// Stores a version's metadata in `version_labels`; versions left without any are removed.
+pub fn cache_version_meta(
+    conn: &Connection,
+    game_id: &str,
+    version_id: &str,
+    meta: &VersionMeta,
+    created_at: &str,
+) -> rusqlite::Result<()> {
+    if meta.is_empty() {
+        conn.execute("DELETE FROM version_labels WHERE version_id = ?1", [version_id])?;
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT INTO version_labels (version_id, game_id, label, notes, is_pinned, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(version_id) DO UPDATE SET
+            label = excluded.label,
+            notes = excluded.notes,
+            is_pinned = excluded.is_pinned,
+            created_at = excluded.created_at",
+        rusqlite::params![version_id, game_id, meta.label, meta.notes, meta.is_pinned, created_at],
+    )?;
+    Ok(())
+}
+
+fn forget_version_meta(conn: &Connection, version_ids: &[String]) -> rusqlite::Result<()> {
+    for chunk in version_ids.chunks(500) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        conn.execute(
+            &format!("DELETE FROM version_labels WHERE version_id IN ({})", placeholders),
+            rusqlite::params_from_iter(chunk),
+        )?;
+    }
+    Ok(())
+}
+
+fn load_local_pins(conn: &Connection, game_id: &str) -> Result<HashSet<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT version_id FROM version_labels WHERE game_id = ?1 AND is_pinned = 1")
+        .map_err(|e| e.to_string())?;
+    let pins = stmt
+        .query_map([game_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(pins)
+}
+
+#[derive(Deserialize)]
+struct CloudVersionMeta {
+    id: String,
+    created_at: String,
+    #[serde(flatten)]
+    meta: VersionMeta,
+}
+
+/// Replaces the game's cached metadata with the cloud's.
+async fn refresh_version_meta(db_path: &Path, game_id: &str, auth: &AuthConfig) -> Result<(), String> {
+    let slug: String = {
+        let conn = db::open(db_path).map_err(|e| e.to_string())?;
+        conn.query_row("SELECT slug FROM games_cache WHERE id = ?1", [game_id], |row| row.get(0))
+            .map_err(|e| format!("Game not found: {}", e))?
+    };
+
+    let client = reqwest::Client::new();
+    let versions: Vec<CloudVersionMeta> = match find_cloud_game(&client, auth, &slug).await? {
+        Some(cloud_game_id) => {
+            let url = format!(
+                "{}/rest/v1/save_versions?game_id=eq.{}&or=(label.not.is.null,notes.not.is.null,is_pinned.is.true)&select=id,created_at,label,notes,is_pinned",
+                auth.url, cloud_game_id
+            );
+            let res = client.get(&url).headers(construct_headers(&auth.key, &auth.token)).send().await.map_err(|e| e.to_string())?;
+            if !res.status().is_success() {
+                let err_text = res.text().await.unwrap_or_default();
+                return Err(format!("Failed to list version labels: {}", err_text));
+            }
+            res.json().await.map_err(|e| e.to_string())?
+        }
+        None => vec![],
+    };
+
+    let mut conn = db::open(db_path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM version_labels WHERE game_id = ?1", [game_id])
+        .map_err(|e| e.to_string())?;
+    for v in versions {
+        cache_version_meta(&tx, game_id, &v.id, &v.meta, &v.created_at).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+#[derive(Serialize, Debug)]
+pub struct LabeledVersion {
+    pub version_id: String,
+    #[serde(flatten)]
+    pub meta: VersionMeta,
+    pub created_at: Option<String>,
+    /// From the version's analysis, when it has one
+    pub completion_percentage: Option<f64>,
+    pub play_time_seconds: Option<i64>,
+}
+
+/// The game's labeled, annotated or pinned versions, newest first. With `auth`
+/// the local cache is refreshed from the cloud first; offline it is used as is.
+pub async fn list_labeled(
+    db_path: &Path,
+    game_id: &str,
+    auth: Option<&AuthConfig>,
+) -> Result<Vec<LabeledVersion>, String> {
+    if let Some(auth) = auth {
+        if let Err(e) = refresh_version_meta(db_path, game_id, auth).await {
+            eprintln!("Using cached version labels: {}", e);
+        }
+    }
+
+    let conn = db::open(db_path).map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT l.version_id, l.label, l.notes, l.is_pinned, l.created_at, a.completion_percentage, a.play_time_seconds
+             FROM version_labels l
+             LEFT JOIN version_analysis a ON a.version_id = l.version_id
+             WHERE l.game_id = ?1
+             ORDER BY l.created_at DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let versions = stmt
+        .query_map([game_id], |row| {
+            Ok(LabeledVersion {
+                version_id: row.get(0)?,
+                meta: VersionMeta {
+                    label: row.get(1)?,
+                    notes: row.get(2)?,
+                    is_pinned: row.get(3)?,
+                },
+                created_at: row.get(4)?,
+                completion_percentage: row.get(5)?,
+                play_time_seconds: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(versions)
+}
+
+#[derive(Deserialize)]
+struct UpdatedVersion {
+    created_at: String,
+}
+
+/// Sets a version's label, notes and pin in the cloud and the local cache.
+/// Labels are unique per game, ignoring case.
+pub async fn set_metadata(
+    db_path: &Path,
+    game_id: &str,
+    version_id: &str,
+    auth: &AuthConfig,
+    metadata: VersionMeta,
+) -> Result<VersionMeta, String> {
+    let meta = metadata.normalized()?;
+
+    let res = reqwest::Client::new()
+        .patch(format!("{}/rest/v1/save_versions?id=eq.{}&select=created_at", auth.url, version_id))
+        .headers(construct_headers(&auth.key, &auth.token))
+        .header(CONTENT_TYPE, "application/json")
+        .header("Prefer", "return=representation")
+        .json(&meta)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if res.status() == reqwest::StatusCode::CONFLICT {
+        return Err(label_taken(meta.label.as_deref()));
+    }
+    if !res.status().is_success() {
+        let err_text = res.text().await.unwrap_or_default();
+        return Err(format!("Failed to update version: {}", err_text));
+    }
+    let updated: Vec<UpdatedVersion> = res.json().await.map_err(|e| e.to_string())?;
+    let Some(updated) = updated.first() else {
+        return Err(format!("Version not found: {}", version_id));
+    };
+
+    let conn = db::open(db_path).map_err(|e| e.to_string())?;
+    cache_version_meta(&conn, game_id, version_id, &meta, &updated.created_at).map_err(|e| e.to_string())?;
+    Ok(meta)
+}
+
+#[derive(Serialize, Debug)]
+pub struct RestoredVersion {
+    pub version_id: String,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub file_size: u64,
+}
+
+/// Downloads and restores `version` (an id or label), or the latest version when `None`.
+pub async fn restore_cloud_version(
+    db_path: &Path,
+    game_id: &str,
+    auth: &AuthConfig,
+    version: Option<&str>,
+) -> Result<RestoredVersion, String> {
+    let slug: String = {
+        let conn = db::open(db_path).map_err(|e| e.to_string())?;
+        conn.query_row("SELECT slug FROM games_cache WHERE id = ?1", [game_id], |row| row.get(0))
+            .map_err(|e| format!("Game not found: {}", e))?
+    };
+
+    let client = reqwest::Client::new();
+    let cloud_game_id = find_cloud_game(&client, auth, &slug)
+        .await?
+        .ok_or_else(|| "This game has no versions in the cloud".to_string())?;
+    let versions = list_cloud_versions(&client, auth, &cloud_game_id).await?;
+
+    let target = match version.map(str::trim) {
+        Some(wanted) => versions
+            .iter()
+            .find(|v| v.id == wanted)
+            .or_else(|| {
+                versions.iter().find(|v| {
+                    v.label
+                        .as_deref()
+                        .is_some_and(|l| l.to_lowercase() == wanted.to_lowercase())
+                })
+            })
+            .ok_or_else(|| format!("No version with id or label '{}'", wanted))?,
+        // Newest first, in case no row is flagged as latest
+        None => versions
+            .iter()
+            .find(|v| v.is_latest)
+            .or(versions.first())
+            .ok_or_else(|| "This game has no versions in the cloud".to_string())?,
+    };
+
+    let res = client
+        .get(format!("{}/storage/v1/object/saves/{}", auth.url, target.file_path))
+        .headers(construct_headers(&auth.key, &auth.token))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        let err_text = res.text().await.unwrap_or_default();
+        return Err(format!("Download failed: {}", err_text));
+    }
+    let download_limit = {
+        let conn = db::open(db_path).map_err(|e| e.to_string())?;
+        settings::load(&conn)?.download_limit_kbps
+    };
+    let bytes = bandwidth::download(res, download_limit).await?;
+
+    let (path, id, version_id) = (db_path.to_path_buf(), game_id.to_string(), target.id.clone());
+    tokio::task::spawn_blocking(move || {
+        let conn = db::open(&path).map_err(|e| e.to_string())?;
+        restore_archive(&conn, &id, &bytes)?;
+        mark_synced(&conn, &id, Some(&version_id))
+    })
+    .await
+    .map_err(|e| format!("Restore failed: {}", e))??;
+
+    eprintln!("Restored version {} of {}", target.id, slug);
+    Ok(RestoredVersion {
+        version_id: target.id.clone(),
+        label: target.label.clone(),
+        created_at: target.created_at,
+        file_size: target.file_size,
+    })
+}
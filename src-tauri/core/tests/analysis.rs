@@ -0,0 +1,103 @@
+//! Analyzer scripts run in their sandbox and can't reach past it.
+#![cfg(unix)]
+
+mod common;
+
+use common::{read, write};
+use serde_json::json;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+use sync_saves_core::services::analysis_runner::run_analyzer;
+use tempfile::TempDir;
+
+struct Setup {
+    dir: TempDir,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let setup = Setup { dir: TempDir::new().unwrap() };
+        write(&setup.sandbox().join("saves/user1.dat"), "save");
+        setup
+    }
+
+    fn sandbox(&self) -> PathBuf {
+        self.dir.path().join("sandbox")
+    }
+
+    /// Runs a shell script as the analyzer of `saves/user1.dat`.
+    fn run(&self, script: &str, timeout: Duration) -> Result<serde_json::Value, String> {
+        let path = self.dir.path().join("analyzer.sh");
+        write(&path, script);
+        let sandbox = self.sandbox();
+        run_analyzer(path.to_str().unwrap(), &sandbox.join("saves/user1.dat"), &sandbox, timeout)
+    }
+}
+
+/// Whether the process runs; a zombie left for init to reap doesn't count.
+fn alive(pid: &str) -> bool {
+    let out = Command::new("ps").args(["-o", "stat=", "-p", pid]).output().unwrap();
+    let stat = String::from_utf8_lossy(&out.stdout);
+    !stat.trim().is_empty() && !stat.trim_start().starts_with('Z')
+}
+
+#[test]
+fn legacy_output_inside_the_sandbox_is_read() {
+    let setup = Setup::new();
+    let value = setup
+        .run(
+            "echo '{\"geo\": 120}' > \"$1.json\"\necho \"Decoded JSON written to: $1.json\"\n",
+            Duration::from_secs(10),
+        )
+        .unwrap();
+
+    assert_eq!(value, json!({ "geo": 120 }));
+    assert!(!setup.sandbox().join("saves/user1.dat.json").exists());
+}
+
+#[test]
+fn legacy_output_outside_the_sandbox_is_left_alone() {
+    let setup = Setup::new();
+    let outside = setup.dir.path().join("important.json");
+    write(&outside, "{\"keep\": true}");
+
+    let script = format!("echo \"Decoded JSON written to: {}\"\n", outside.display());
+    let err = setup.run(&script, Duration::from_secs(10)).unwrap_err();
+    assert!(err.contains("outside the sandbox"), "{}", err);
+    // Reached through a relative path too
+    let err = setup
+        .run("echo 'Decoded JSON written to: ../important.json'\n", Duration::from_secs(10))
+        .unwrap_err();
+    assert!(err.contains("outside the sandbox"), "{}", err);
+
+    assert_eq!(read(&outside), "{\"keep\": true}");
+}
+
+#[test]
+fn timeout_kills_what_the_script_started() {
+    let setup = Setup::new();
+    let pid_file = setup.dir.path().join("child.pid");
+    let script = format!("sleep 30 &\necho $! > '{}'\nsleep 30\n", pid_file.display());
+
+    let err = setup.run(&script, Duration::from_secs(1)).unwrap_err();
+    assert!(err.contains("timed out"), "{}", err);
+
+    let pid = read(&pid_file).trim().to_string();
+    // The kill is delivered asynchronously
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(!alive(&pid), "background process {} survived", pid);
+}
+
+#[test]
+fn background_processes_dont_outlive_the_script() {
+    let setup = Setup::new();
+    let pid_file = setup.dir.path().join("child.pid");
+    let script = format!("sleep 30 &\necho $! > '{}'\necho '{{\"done\": true}}'\n", pid_file.display());
+
+    let value = setup.run(&script, Duration::from_secs(10)).unwrap();
+    assert_eq!(value, json!({ "done": true }));
+    let pid = read(&pid_file).trim().to_string();
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(!alive(&pid), "background process {} survived", pid);
+}
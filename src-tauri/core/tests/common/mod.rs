@@ -0,0 +1,484 @@
+//! In-memory stand-in for the Supabase endpoints the sync engine talks to:
+//! PostgREST tables under `/rest/v1/` and the `saves` bucket under
+//! `/storage/v1/object/`, plus the Postgres functions under `/rest/v1/rpc/`.
+//! Only the filters and headers the engine uses are understood
+//! (`col=eq.value`, `order`, `limit`/`offset`, `Prefer`).
+
+#![allow(dead_code)]
+
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use sync_saves_core::db;
+use sync_saves_core::games::{self, LocalGame};
+use sync_saves_core::settings::{self, AppSettings};
+use sync_saves_core::sync::AuthConfig;
+use tempfile::TempDir;
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+pub const USER_ID: &str = "00000000-0000-0000-0000-000000000001";
+const BUCKET_PREFIX: &str = "/storage/v1/object/saves";
+
+/// Columns that make a row unique, as in the Supabase schema.
+fn unique_keys(table: &str) -> &'static [&'static str] {
+    match table {
+        "devices" => &["user_id", "machine_id"],
+        "games" => &["user_id", "slug"],
+        "game_paths" => &["game_id", "device_id"],
+        _ => &[],
+    }
+}
+
+#[derive(Default)]
+pub struct State {
+    pub tables: HashMap<String, Vec<Value>>,
+    pub objects: BTreeMap<String, Vec<u8>>,
+    /// Every request as "METHOD /path", in order
+    pub log: Vec<String>,
+    failures: Vec<Failure>,
+    stale_reads: HashMap<String, usize>,
+    clock: i64,
+}
+
+struct Failure {
+    method: String,
+    path: String,
+    status: u16,
+    body: Value,
+    remaining: Option<usize>,
+}
+
+impl State {
+    pub fn rows(&self, table: &str) -> &[Value] {
+        self.tables.get(table).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn count(&self, method: &str, path: &str) -> usize {
+        let prefix = format!("{} {}", method, path);
+        self.log.iter().filter(|r| r.starts_with(&prefix)).count()
+    }
+
+    fn take_failure(&mut self, method: &str, path: &str) -> Option<ResponseTemplate> {
+        let i = self
+            .failures
+            .iter()
+            .position(|f| f.method == method && path.starts_with(&f.path) && f.remaining != Some(0))?;
+        let failure = &mut self.failures[i];
+        if let Some(n) = failure.remaining.as_mut() {
+            *n -= 1;
+        }
+        Some(ResponseTemplate::new(failure.status).set_body_json(failure.body.clone()))
+    }
+
+    fn next_timestamp(&mut self) -> String {
+        // Strictly increasing, so `order=created_at.desc` is deterministic
+        self.clock += 1;
+        (chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap() + chrono::Duration::seconds(self.clock))
+            .to_rfc3339()
+    }
+}
+
+/// A running mock with its state, shared with the handler.
+pub struct MockSupabase {
+    pub server: MockServer,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockSupabase {
+    pub async fn start() -> Self {
+        let server = MockServer::start().await;
+        let state = Arc::new(Mutex::new(State::default()));
+        Mock::given(any())
+            .respond_with(Handler(state.clone()))
+            .mount(&server)
+            .await;
+        Self { server, state }
+    }
+
+    pub fn auth(&self) -> AuthConfig {
+        AuthConfig {
+            url: self.server.uri(),
+            key: "anon-key".to_string(),
+            token: "access-token".to_string(),
+            user_id: USER_ID.to_string(),
+        }
+    }
+
+    pub fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    /// Answers `method` requests whose path starts with `path` with `status`,
+    /// `times` times or for good when `None`.
+    pub fn fail(&self, method: &str, path: &str, status: u16, times: Option<usize>) {
+        self.state().failures.push(Failure {
+            method: method.to_string(),
+            path: path.to_string(),
+            status,
+            body: json!({ "message": format!("mock {} error", status) }),
+            remaining: times,
+        });
+    }
+
+    /// Makes the next `times` reads of `table` come back empty, as if another
+    /// client inserted its rows right after we looked.
+    pub fn stale_reads(&self, table: &str, times: usize) {
+        self.state().stale_reads.insert(table.to_string(), times);
+    }
+
+    pub fn insert(&self, table: &str, row: Value) -> Value {
+        let mut state = self.state();
+        let created_at = state.next_timestamp();
+        let row = with_defaults(table, row, created_at);
+        state.tables.entry(table.to_string()).or_default().push(row.clone());
+        row
+    }
+}
+
+struct Handler(Arc<Mutex<State>>);
+
+impl Respond for Handler {
+    fn respond(&self, req: &Request) -> ResponseTemplate {
+        let mut state = self.0.lock().unwrap();
+        let method = req.method.to_string();
+        let path = req.url.path().to_string();
+        state.log.push(format!("{} {}", method, path));
+
+        if req.headers.get("apikey").is_none() || req.headers.get("authorization").is_none() {
+            return ResponseTemplate::new(401).set_body_json(json!({ "message": "missing credentials" }));
+        }
+        if let Some(failure) = state.take_failure(&method, &path) {
+            return failure;
+        }
+
+        if let Some(function) = path.strip_prefix("/rest/v1/rpc/") {
+            let function = function.to_string();
+            rpc(&mut state, &function, req)
+        } else if let Some(table) = path.strip_prefix("/rest/v1/") {
+            let table = table.to_string();
+            rest(&mut state, &method, &table, req)
+        } else if let Some(object) = path.strip_prefix(BUCKET_PREFIX) {
+            let object = object.trim_start_matches('/').to_string();
+            storage(&mut state, &method, &object, req)
+        } else {
+            ResponseTemplate::new(404)
+        }
+    }
+}
+
+fn query(req: &Request) -> Vec<(String, String)> {
+    req.url.query_pairs().map(|(k, v)| (k.into_owned(), v.into_owned())).collect()
+}
+
+fn matches_filters(row: &Value, filters: &[(String, String)]) -> bool {
+    filters.iter().all(|(col, cond)| match cond.strip_prefix("eq.") {
+        Some(expected) => match &row[col] {
+            Value::String(s) => s == expected,
+            Value::Null => false,
+            other => serde_json::from_str::<Value>(expected).is_ok_and(|v| &v == other),
+        },
+        None => true,
+    })
+}
+
+fn with_defaults(table: &str, row: Value, created_at: String) -> Value {
+    let mut row = row.as_object().cloned().unwrap_or_default();
+    row.entry("id").or_insert_with(|| json!(uuid::Uuid::new_v4().to_string()));
+    row.entry("created_at").or_insert(json!(created_at));
+    if table == "save_versions" {
+        row.entry("is_pinned").or_insert(json!(false));
+        row.entry("label").or_insert(Value::Null);
+        row.entry("notes").or_insert(Value::Null);
+    }
+    Value::Object(row)
+}
+
+fn same_key(table: &str, a: &Value, b: &Value) -> bool {
+    let keys = unique_keys(table);
+    if !keys.is_empty() && keys.iter().all(|k| a[*k] == b[*k]) {
+        return true;
+    }
+    // One latest version per game, and unique labels per game ignoring case
+    let both_latest = a["is_latest"] == true && b["is_latest"] == true;
+    table == "save_versions"
+        && a["game_id"] == b["game_id"]
+        && (both_latest
+            || matches!((a["label"].as_str(), b["label"].as_str()), (Some(x), Some(y)) if x.to_lowercase() == y.to_lowercase()))
+}
+
+/// The 409 Postgres answers with when `row` breaks a unique constraint against `existing`.
+fn duplicate(table: &str, existing: &Value, row: &Value) -> ResponseTemplate {
+    let both_latest = existing["is_latest"] == true && row["is_latest"] == true;
+    let name = match table {
+        "save_versions" if both_latest => "idx_save_versions_one_latest".to_string(),
+        "save_versions" => "idx_save_versions_game_label".to_string(),
+        _ => format!("{}_{}_key", table, unique_keys(table).join("_")),
+    };
+    ResponseTemplate::new(409).set_body_json(json!({
+        "code": "23505",
+        "message": format!("duplicate key value violates unique constraint \"{}\"", name),
+    }))
+}
+
+/// The functions from the migrations, each applied in one step like the
+/// transaction it runs in.
+fn rpc(state: &mut State, function: &str, req: &Request) -> ResponseTemplate {
+    let args: Map<String, Value> = match serde_json::from_slice(&req.body) {
+        Ok(args) => args,
+        Err(_) => return ResponseTemplate::new(400),
+    };
+    let arg = |name: &str| args.get(name).cloned().unwrap_or(Value::Null);
+
+    match function {
+        "create_save_version" => {
+            let created_at = state.next_timestamp();
+            let mut row = with_defaults(
+                "save_versions",
+                json!({
+                    "id": arg("p_id"),
+                    "game_id": arg("p_game_id"),
+                    "device_id": arg("p_device_id"),
+                    "file_path": arg("p_file_path"),
+                    "file_size": arg("p_file_size"),
+                    "checksum": arg("p_checksum"),
+                    "label": arg("p_label"),
+                    "notes": arg("p_notes"),
+                    "is_pinned": arg("p_is_pinned").as_bool().unwrap_or(false),
+                }),
+                created_at,
+            );
+            let rows = state.tables.entry("save_versions".to_string()).or_default();
+            if let Some(existing) = rows.iter().find(|r| same_key("save_versions", r, &row)) {
+                return duplicate("save_versions", existing, &row);
+            }
+            let mut parent = Value::Null;
+            for r in rows.iter_mut().filter(|r| r["game_id"] == row["game_id"] && r["is_latest"] == true) {
+                r["is_latest"] = json!(false);
+                parent = r["id"].clone();
+            }
+            row["is_latest"] = json!(true);
+            row["parent_version_id"] = parent;
+            rows.push(row.clone());
+            ResponseTemplate::new(200).set_body_json(row)
+        }
+        _ => ResponseTemplate::new(404).set_body_json(json!({ "code": "PGRST202", "message": "function not found" })),
+    }
+}
+
+fn rest(state: &mut State, method: &str, table: &str, req: &Request) -> ResponseTemplate {
+    let params = query(req);
+    let filters: Vec<(String, String)> = params
+        .iter()
+        .filter(|(k, _)| !matches!(k.as_str(), "select" | "order" | "limit" | "offset"))
+        .cloned()
+        .collect();
+    let prefer = req
+        .headers
+        .get("prefer")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    match method {
+        "GET" => {
+            if let Some(n) = state.stale_reads.get_mut(table).filter(|n| **n > 0) {
+                *n -= 1;
+                return ResponseTemplate::new(200).set_body_json(json!([]));
+            }
+            let mut rows: Vec<Value> = state
+                .rows(table)
+                .iter()
+                .filter(|r| matches_filters(r, &filters))
+                .cloned()
+                .collect();
+            let param = |name: &str| params.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+            if let Some(order) = param("order") {
+                // `col.dir` terms, the first one deciding first
+                for term in order.split(',').rev() {
+                    let (col, desc) = match term.split_once('.') {
+                        Some((col, dir)) => (col.to_string(), dir == "desc"),
+                        None => (term.to_string(), false),
+                    };
+                    rows.sort_by(|a, b| {
+                        let ord = a[&col].to_string().cmp(&b[&col].to_string());
+                        if desc { ord.reverse() } else { ord }
+                    });
+                }
+            }
+            let offset = param("offset").and_then(|v| v.parse().ok()).unwrap_or(0);
+            let limit = param("limit").and_then(|v| v.parse().ok()).unwrap_or(usize::MAX);
+            let rows: Vec<Value> = rows.into_iter().skip(offset).take(limit).collect();
+            ResponseTemplate::new(200).set_body_json(rows)
+        }
+        "POST" => {
+            let body: Value = match serde_json::from_slice(&req.body) {
+                Ok(body) => body,
+                Err(_) => return ResponseTemplate::new(400),
+            };
+            let created_at = state.next_timestamp();
+            let row = with_defaults(table, body, created_at);
+            let rows = state.tables.entry(table.to_string()).or_default();
+            let stored = match rows.iter_mut().find(|r| same_key(table, r, &row)) {
+                Some(existing) if prefer.contains("resolution=merge-duplicates") => {
+                    // The existing row keeps its id, like an upsert on the unique columns
+                    let mut merged: Map<String, Value> = existing.as_object().cloned().unwrap_or_default();
+                    for (k, v) in row.as_object().unwrap() {
+                        if k != "id" && k != "created_at" {
+                            merged.insert(k.clone(), v.clone());
+                        }
+                    }
+                    *existing = Value::Object(merged);
+                    existing.clone()
+                }
+                Some(existing) => return duplicate(table, existing, &row),
+                None => {
+                    rows.push(row.clone());
+                    row
+                }
+            };
+            if prefer.contains("return=representation") {
+                ResponseTemplate::new(201).set_body_json(json!([stored]))
+            } else {
+                ResponseTemplate::new(201)
+            }
+        }
+        "PATCH" => {
+            let body: Map<String, Value> = match serde_json::from_slice(&req.body) {
+                Ok(body) => body,
+                Err(_) => return ResponseTemplate::new(400),
+            };
+            for row in state.tables.entry(table.to_string()).or_default() {
+                if matches_filters(row, &filters) {
+                    for (k, v) in &body {
+                        row[k] = v.clone();
+                    }
+                }
+            }
+            ResponseTemplate::new(204)
+        }
+        "DELETE" => {
+            let mut deleted = Vec::new();
+            let id_in = filters
+                .iter()
+                .find(|(k, _)| k == "id")
+                .and_then(|(_, v)| v.strip_prefix("in.("))
+                .map(|v| v.trim_end_matches(')').split(',').map(str::to_string).collect::<Vec<_>>());
+            let rows = state.tables.entry(table.to_string()).or_default();
+            rows.retain(|r| {
+                let hit = match &id_in {
+                    Some(ids) => r["id"].as_str().is_some_and(|id| ids.iter().any(|x| x == id)),
+                    None => matches_filters(r, &filters),
+                };
+                if hit {
+                    deleted.push(r.clone());
+                }
+                !hit
+            });
+            ResponseTemplate::new(200).set_body_json(deleted)
+        }
+        _ => ResponseTemplate::new(405),
+    }
+}
+
+fn storage(state: &mut State, method: &str, object: &str, req: &Request) -> ResponseTemplate {
+    match method {
+        "POST" if !object.is_empty() => {
+            if state.objects.contains_key(object) {
+                return ResponseTemplate::new(400).set_body_json(json!({ "error": "Duplicate", "statusCode": "409" }));
+            }
+            let Some(bytes) = multipart_file(req) else {
+                return ResponseTemplate::new(400).set_body_json(json!({ "error": "No file" }));
+            };
+            state.objects.insert(object.to_string(), bytes);
+            ResponseTemplate::new(200).set_body_json(json!({ "Key": format!("saves/{}", object) }))
+        }
+        "GET" => match state.objects.get(object) {
+            Some(bytes) => ResponseTemplate::new(200).set_body_bytes(bytes.clone()),
+            None => ResponseTemplate::new(400).set_body_json(json!({ "error": "not_found", "statusCode": "404" })),
+        },
+        "DELETE" if object.is_empty() => {
+            let body: Value = serde_json::from_slice(&req.body).unwrap_or_default();
+            let mut removed = Vec::new();
+            for prefix in body["prefixes"].as_array().into_iter().flatten().filter_map(Value::as_str) {
+                if state.objects.remove(prefix).is_some() {
+                    removed.push(json!({ "name": prefix }));
+                }
+            }
+            ResponseTemplate::new(200).set_body_json(removed)
+        }
+        _ => ResponseTemplate::new(405),
+    }
+}
+
+/// Content of the first part of a multipart/form-data body.
+fn multipart_file(req: &Request) -> Option<Vec<u8>> {
+    let content_type = req.headers.get("content-type")?.to_str().ok()?;
+    let boundary = content_type.split("boundary=").nth(1)?.trim_matches('"');
+    let body = &req.body;
+    let start = find(body, b"\r\n\r\n")? + 4;
+    let end = find(&body[start..], format!("\r\n--{}", boundary).as_bytes())? + start;
+    Some(body[start..end].to_vec())
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// A local database and save folder for one test.
+pub struct LocalSetup {
+    pub dir: TempDir,
+    pub db_path: PathBuf,
+}
+
+impl LocalSetup {
+    pub fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("sync_saves.db");
+        db::init(&db_path).unwrap();
+        let setup = Self { dir, db_path };
+        // Keep the host's network out of the results
+        setup.update_settings(|s| s.pause_on_metered = false);
+        setup
+    }
+
+    pub fn update_settings(&self, f: impl FnOnce(&mut AppSettings)) {
+        let conn = self.conn();
+        let mut settings = settings::load(&conn).unwrap();
+        f(&mut settings);
+        settings::save(&conn, &settings).unwrap();
+    }
+
+    pub fn conn(&self) -> rusqlite::Connection {
+        db::open(&self.db_path).unwrap()
+    }
+
+    /// Adds a game whose saves live in a fresh folder, seeded with `files`.
+    /// Names should be unique per test, since archives are staged by slug.
+    pub fn add_game(&self, name: &str, files: &[(&str, &str)]) -> LocalGame {
+        let saves = self.dir.path().join(name.replace(' ', "_"));
+        for (file, content) in files {
+            write(&saves.join(file), content);
+        }
+        games::create_game(
+            &mut self.conn(),
+            name.to_string(),
+            saves.to_string_lossy().to_string(),
+            "pc".to_string(),
+            None,
+            Vec::new(),
+            None,
+        )
+        .unwrap()
+    }
+}
+
+pub fn write(path: &Path, content: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+pub fn read(path: &Path) -> String {
+    std::fs::read_to_string(path).unwrap()
+}
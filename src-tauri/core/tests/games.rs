@@ -0,0 +1,57 @@
+//! Save locations given as path templates.
+
+mod common;
+
+use common::LocalSetup;
+use sync_saves_core::games::{self, NewGameLocation, UpdateGameParams};
+use sync_saves_core::utils::path_template::PathContext;
+
+/// Tokens `to_template` never produces, so they only survive when kept as given.
+const PRIMARY: &str = "{{p|home}}/Saves/{{p|username}}";
+const EXTRA: &str = "{{p|home}}/Configs/{{p|username}}";
+
+fn templates(local: &LocalSetup, game_id: &str) -> Vec<Option<String>> {
+    games::load_game_locations(&local.conn(), game_id)
+        .unwrap()
+        .into_iter()
+        .map(|l| l.template)
+        .collect()
+}
+
+#[test]
+fn templates_are_stored_as_given() {
+    let local = LocalSetup::new();
+    let game = games::create_game(
+        &mut local.conn(),
+        "Templated".to_string(),
+        PRIMARY.to_string(),
+        "pc".to_string(),
+        None,
+        vec![NewGameLocation {
+            label: "Config".to_string(),
+            path: EXTRA.to_string(),
+            file_pattern: None,
+        }],
+        None,
+    )
+    .unwrap();
+
+    let resolved = PathContext::current(None).resolve(PRIMARY).unwrap();
+    assert_eq!(game.local_path, resolved.to_string_lossy());
+    assert_eq!(templates(&local, &game.id), [Some(PRIMARY.to_string()), Some(EXTRA.to_string())]);
+
+    // Updates that leave the path alone keep the template
+    let rename = UpdateGameParams {
+        name: Some("Templated Renamed".to_string()),
+        ..Default::default()
+    };
+    games::update_game(&local.conn(), &game.id, rename).unwrap();
+    assert_eq!(templates(&local, &game.id)[0].as_deref(), Some(PRIMARY));
+
+    let moved = UpdateGameParams {
+        local_path: Some("{{p|home}}/Moved/{{p|username}}".to_string()),
+        ..Default::default()
+    };
+    games::update_game(&local.conn(), &game.id, moved).unwrap();
+    assert_eq!(templates(&local, &game.id)[0].as_deref(), Some("{{p|home}}/Moved/{{p|username}}"));
+}
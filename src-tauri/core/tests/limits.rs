@@ -0,0 +1,118 @@
+//! Sync pauses and bandwidth limits against a mock Supabase.
+
+mod common;
+
+use common::{LocalSetup, MockSupabase};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use sync_saves_core::games::{self, UpdateGameParams};
+use sync_saves_core::settings::PAUSED;
+use sync_saves_core::sync::run_sync;
+use sync_saves_core::versions::restore_cloud_version;
+
+const STORAGE: &str = "/storage/v1/object/saves";
+
+/// Bytes that don't compress, so the archive is about as large.
+fn noise(len: usize) -> Vec<u8> {
+    let mut x: u64 = 0x9E37_79B9_7F4A_7C15;
+    (0..len)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x as u8
+        })
+        .collect()
+}
+
+/// Adds a game with one `len` byte save, returning its id and folder.
+fn add_large_game(local: &LocalSetup, name: &str, len: usize) -> (String, PathBuf) {
+    let game = local.add_game(name, &[]);
+    let saves = PathBuf::from(&game.local_path);
+    std::fs::create_dir_all(&saves).unwrap();
+    std::fs::write(saves.join("save.bin"), noise(len)).unwrap();
+    (game.id, saves)
+}
+
+#[tokio::test]
+async fn global_pause_blocks_sync() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let game = local.add_game("Paused Everywhere", &[("save.dat", "data")]);
+
+    local.update_settings(|s| s.sync_paused = true);
+    let err = run_sync(&local.db_path, &game.id, &mock.auth(), None).await.unwrap_err();
+    assert!(err.starts_with(PAUSED), "{}", err);
+    assert!(mock.state().log.is_empty());
+
+    local.update_settings(|s| s.sync_paused = false);
+    run_sync(&local.db_path, &game.id, &mock.auth(), None).await.unwrap();
+    assert_eq!(mock.state().count("POST", STORAGE), 1);
+}
+
+#[tokio::test]
+async fn pause_until_expires() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let game = local.add_game("Paused For A While", &[("save.dat", "data")]);
+
+    let later = chrono::Local::now() + chrono::Duration::hours(1);
+    local.update_settings(|s| s.sync_paused_until = Some(later.to_rfc3339()));
+    let err = run_sync(&local.db_path, &game.id, &mock.auth(), None).await.unwrap_err();
+    assert!(err.starts_with(PAUSED), "{}", err);
+
+    let earlier = chrono::Local::now() - chrono::Duration::minutes(1);
+    local.update_settings(|s| s.sync_paused_until = Some(earlier.to_rfc3339()));
+    run_sync(&local.db_path, &game.id, &mock.auth(), None).await.unwrap();
+}
+
+#[tokio::test]
+async fn game_pause_blocks_only_that_game() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let paused = local.add_game("Paused Game", &[("save.dat", "a")]);
+    let other = local.add_game("Other Game", &[("save.dat", "b")]);
+
+    let updates = UpdateGameParams {
+        sync_paused: Some(true),
+        ..Default::default()
+    };
+    assert!(games::update_game(&local.conn(), &paused.id, updates).unwrap().sync_paused);
+
+    let err = run_sync(&local.db_path, &paused.id, &mock.auth(), None).await.unwrap_err();
+    assert!(err.starts_with(PAUSED), "{}", err);
+    run_sync(&local.db_path, &other.id, &mock.auth(), None).await.unwrap();
+    assert_eq!(mock.state().count("POST", STORAGE), 1);
+}
+
+#[tokio::test]
+async fn upload_limit_paces_sync() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let (game_id, _) = add_large_game(&local, "Slow Upload", 256 * 1024);
+
+    local.update_settings(|s| s.upload_limit_kbps = 128);
+    let started = Instant::now();
+    run_sync(&local.db_path, &game_id, &mock.auth(), None).await.unwrap();
+
+    assert!(started.elapsed() >= Duration::from_millis(1900), "{:?}", started.elapsed());
+    assert!(mock.state().objects.values().next().unwrap().len() >= 256 * 1024);
+}
+
+#[tokio::test]
+async fn download_limit_paces_restore() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let (game_id, saves) = add_large_game(&local, "Slow Download", 256 * 1024);
+    run_sync(&local.db_path, &game_id, &mock.auth(), None).await.unwrap();
+    std::fs::remove_file(saves.join("save.bin")).unwrap();
+
+    local.update_settings(|s| s.download_limit_kbps = 128);
+    let started = Instant::now();
+    restore_cloud_version(&local.db_path, &game_id, &mock.auth(), None)
+        .await
+        .unwrap();
+
+    assert!(started.elapsed() >= Duration::from_millis(1900), "{:?}", started.elapsed());
+    assert_eq!(std::fs::read(saves.join("save.bin")).unwrap(), noise(256 * 1024));
+}
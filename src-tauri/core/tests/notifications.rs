@@ -0,0 +1,82 @@
+//! Rate limiting and grouping of sync notifications.
+
+use std::time::{Duration, Instant};
+use sync_saves_core::notifications::{
+    SyncEvent, Throttle, CONFLICT_INTERVAL, FAILURE_INTERVAL, FAILURE_THRESHOLD, UPLOAD_INTERVAL,
+};
+
+fn body(throttle: &mut Throttle, game_id: &str, event: SyncEvent, now: Instant) -> Option<String> {
+    throttle.notice(game_id, "Hollow Knight", &event, now).map(|n| n.body)
+}
+
+#[test]
+fn uploads_are_grouped_per_game() {
+    let mut throttle = Throttle::default();
+    let start = Instant::now();
+
+    assert_eq!(
+        body(&mut throttle, "a", SyncEvent::Uploaded, start).as_deref(),
+        Some("Saves uploaded to the cloud")
+    );
+    assert_eq!(body(&mut throttle, "a", SyncEvent::Uploaded, start + Duration::from_secs(10)), None);
+    assert_eq!(body(&mut throttle, "a", SyncEvent::Uploaded, start + Duration::from_secs(20)), None);
+    // Another game isn't held back
+    assert!(body(&mut throttle, "b", SyncEvent::Uploaded, start + Duration::from_secs(30)).is_some());
+
+    assert_eq!(
+        body(&mut throttle, "a", SyncEvent::Uploaded, start + UPLOAD_INTERVAL).as_deref(),
+        Some("3 save versions uploaded to the cloud")
+    );
+}
+
+#[test]
+fn only_repeated_failures_notify() {
+    let mut throttle = Throttle::default();
+    let start = Instant::now();
+    let failed = || SyncEvent::Failed("Upload failed: 500".to_string());
+
+    for i in 1..FAILURE_THRESHOLD {
+        assert_eq!(body(&mut throttle, "a", failed(), start + Duration::from_secs(i as u64)), None);
+    }
+    let notice = body(&mut throttle, "a", failed(), start + Duration::from_secs(10)).unwrap();
+    assert!(notice.contains("3 times in a row"), "{}", notice);
+    assert!(notice.ends_with("Upload failed: 500"), "{}", notice);
+
+    assert_eq!(body(&mut throttle, "a", failed(), start + Duration::from_secs(60)), None);
+    assert!(body(&mut throttle, "a", failed(), start + Duration::from_secs(10) + FAILURE_INTERVAL).is_some());
+}
+
+#[test]
+fn success_resets_failures() {
+    let mut throttle = Throttle::default();
+    let now = Instant::now();
+    let failed = || SyncEvent::Failed("offline".to_string());
+
+    for _ in 1..FAILURE_THRESHOLD {
+        body(&mut throttle, "a", failed(), now);
+    }
+    body(&mut throttle, "a", SyncEvent::Uploaded, now);
+    for _ in 1..FAILURE_THRESHOLD {
+        assert_eq!(body(&mut throttle, "a", failed(), now), None);
+    }
+    assert!(body(&mut throttle, "a", failed(), now).is_some());
+}
+
+#[test]
+fn restores_always_notify_and_conflicts_are_limited() {
+    let mut throttle = Throttle::default();
+    let start = Instant::now();
+    let restored = |label: Option<&str>| SyncEvent::Restored {
+        label: label.map(str::to_string),
+    };
+
+    assert_eq!(
+        body(&mut throttle, "a", restored(Some("Before boss")), start).as_deref(),
+        Some("Restored \"Before boss\" from the cloud")
+    );
+    assert!(body(&mut throttle, "a", restored(None), start).is_some());
+
+    assert!(body(&mut throttle, "a", SyncEvent::Conflict, start).is_some());
+    assert_eq!(body(&mut throttle, "a", SyncEvent::Conflict, start + Duration::from_secs(60)), None);
+    assert!(body(&mut throttle, "a", SyncEvent::Conflict, start + CONFLICT_INTERVAL).is_some());
+}
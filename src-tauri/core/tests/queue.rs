@@ -0,0 +1,274 @@
+//! Offline syncs queued and flushed against a mock Supabase.
+
+mod common;
+
+use common::{read, write, LocalSetup, MockSupabase};
+use std::path::PathBuf;
+use sync_saves_core::games::{self, UpdateGameParams};
+use sync_saves_core::queue::{self, MAX_RETRIES};
+use sync_saves_core::sync::{queue_saves, run_sync, AuthConfig};
+use sync_saves_core::versions::{restore_cloud_version, VersionMeta};
+
+/// The mock's credentials, pointed at a port nothing listens on.
+fn offline(mock: &MockSupabase) -> AuthConfig {
+    AuthConfig {
+        url: "http://127.0.0.1:1".to_string(),
+        ..mock.auth()
+    }
+}
+
+fn pending(local: &LocalSetup, game_id: Option<&str>) -> u32 {
+    queue::pending_count(&local.conn(), game_id).unwrap()
+}
+
+fn queued_archives(local: &LocalSetup) -> usize {
+    std::fs::read_dir(queue::archive_dir(&local.db_path))
+        .map(|d| d.count())
+        .unwrap_or(0)
+}
+
+#[tokio::test]
+async fn offline_syncs_are_queued_and_flushed_in_order() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let game = local.add_game("Offline Order", &[("save.dat", "first")]);
+    let save = PathBuf::from(&game.local_path).join("save.dat");
+
+    let first = run_sync(&local.db_path, &game.id, &offline(&mock), None).await.unwrap();
+    assert!(first.queued && first.version_id.is_none(), "{}", first.message);
+    write(&save, "second");
+    let meta = VersionMeta {
+        label: Some("Before boss".to_string()),
+        ..Default::default()
+    };
+    let second = run_sync(&local.db_path, &game.id, &offline(&mock), Some(meta)).await.unwrap();
+    assert!(second.queued);
+    assert_eq!(pending(&local, Some(&game.id)), 2);
+    assert_eq!(queued_archives(&local), 2);
+    assert!(mock.state().log.is_empty());
+
+    let report = queue::flush(&local.db_path, &mock.auth(), None).await.unwrap();
+    assert!(!report.offline);
+    assert_eq!(report.remaining, 0);
+    let checksums: Vec<_> = report
+        .results
+        .iter()
+        .map(|q| q.result.as_ref().unwrap().checksum.clone())
+        .collect();
+    assert_eq!(checksums, [first.checksum, second.checksum]);
+    assert_eq!(queued_archives(&local), 0);
+
+    {
+        let state = mock.state();
+        let versions = state.rows("save_versions");
+        assert_eq!(versions.len(), 2);
+        assert!(versions[0]["label"].is_null());
+        assert_eq!(versions[1]["label"], "Before boss");
+        assert_eq!(versions[1]["is_latest"], true);
+    }
+
+    // The latest version holds the last queued saves
+    write(&save, "local edit");
+    restore_cloud_version(&local.db_path, &game.id, &mock.auth(), None).await.unwrap();
+    assert_eq!(read(&save), "second");
+}
+
+#[tokio::test]
+async fn sync_waits_behind_queued_saves() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let game = local.add_game("Behind Queue", &[("save.dat", "offline")]);
+    let save = PathBuf::from(&game.local_path).join("save.dat");
+
+    let queued = run_sync(&local.db_path, &game.id, &offline(&mock), None).await.unwrap();
+    write(&save, "online");
+    let result = run_sync(&local.db_path, &game.id, &mock.auth(), None).await.unwrap();
+
+    assert!(!result.queued && !result.skipped, "{}", result.message);
+    assert_ne!(result.checksum, queued.checksum);
+    assert_eq!(pending(&local, None), 0);
+    let state = mock.state();
+    let versions = state.rows("save_versions");
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0]["checksum"], queued.checksum.unwrap());
+    assert_eq!(versions[1]["checksum"], result.checksum.unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn overlapping_flushes_send_each_entry_once() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let game = local.add_game("Flushed Twice", &[("save.dat", "first")]);
+    run_sync(&local.db_path, &game.id, &offline(&mock), None).await.unwrap();
+    write(&PathBuf::from(&game.local_path).join("save.dat"), "second");
+    run_sync(&local.db_path, &game.id, &offline(&mock), None).await.unwrap();
+
+    let auth = mock.auth();
+    let (a, b) = tokio::join!(
+        queue::flush(&local.db_path, &auth, None),
+        queue::flush(&local.db_path, &auth, None)
+    );
+    let sent = a.unwrap().results.len() + b.unwrap().results.len();
+
+    // A flush that finds the first entry taken leaves the game to the other one
+    let versions = mock.state().rows("save_versions").len();
+    assert_eq!(sent, versions);
+    assert!(versions <= 2);
+    queue::flush(&local.db_path, &auth, None).await.unwrap();
+    assert_eq!(mock.state().rows("save_versions").len(), 2);
+    assert_eq!(pending(&local, None), 0);
+}
+
+#[tokio::test]
+async fn flush_while_offline_keeps_the_queue() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let game = local.add_game("Still Offline", &[("save.dat", "data")]);
+    run_sync(&local.db_path, &game.id, &offline(&mock), None).await.unwrap();
+
+    let report = queue::flush(&local.db_path, &offline(&mock), None).await.unwrap();
+
+    assert!(report.offline);
+    assert!(report.results.is_empty());
+    assert_eq!(report.remaining, 1);
+    assert_eq!(queued_archives(&local), 1);
+}
+
+#[tokio::test]
+async fn failed_entry_holds_back_only_its_game() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let blocked = local.add_game("Rejected Game", &[("save.dat", "a")]);
+    let other = local.add_game("Accepted Game", &[("save.dat", "b")]);
+    run_sync(&local.db_path, &blocked.id, &offline(&mock), None).await.unwrap();
+    write(&PathBuf::from(&blocked.local_path).join("save.dat"), "a2");
+    run_sync(&local.db_path, &blocked.id, &offline(&mock), None).await.unwrap();
+    run_sync(&local.db_path, &other.id, &offline(&mock), None).await.unwrap();
+
+    // The backend answers, but refuses the first game's uploads
+    mock.fail("GET", "/rest/v1/games", 500, Some(1));
+    let report = queue::flush(&local.db_path, &mock.auth(), None).await.unwrap();
+
+    let results: Vec<(&str, bool)> = report
+        .results
+        .iter()
+        .map(|q| (q.game_id.as_str(), q.result.is_ok()))
+        .collect();
+    assert_eq!(results, [(blocked.id.as_str(), false), (other.id.as_str(), true)]);
+    assert_eq!(pending(&local, Some(&blocked.id)), 2);
+    assert_eq!(pending(&local, Some(&other.id)), 0);
+
+    let report = queue::flush(&local.db_path, &mock.auth(), None).await.unwrap();
+    assert_eq!(report.results.len(), 2);
+    assert_eq!(report.remaining, 0);
+}
+
+#[tokio::test]
+async fn entries_are_set_aside_after_max_retries() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let game = local.add_game("Set Aside", &[("save.dat", "data")]);
+    let save = PathBuf::from(&game.local_path).join("save.dat");
+    run_sync(&local.db_path, &game.id, &offline(&mock), None).await.unwrap();
+
+    mock.fail("POST", "/storage/v1/object/saves", 500, Some(MAX_RETRIES as usize));
+    for i in 1..=MAX_RETRIES {
+        let report = queue::flush(&local.db_path, &mock.auth(), None).await.unwrap();
+        assert!(report.results[0].result.is_err());
+        assert_eq!(report.given_up, u32::from(i == MAX_RETRIES));
+    }
+    assert_eq!(pending(&local, None), 0);
+    assert_eq!(queue::failed_count(&local.conn(), None).unwrap(), 1);
+    assert_eq!(queued_archives(&local), 1);
+
+    // Later saves of the game wait behind it instead of overtaking it
+    write(&save, "newer");
+    let held = run_sync(&local.db_path, &game.id, &mock.auth(), None).await.unwrap();
+    assert!(held.queued);
+    assert_eq!(held.message, queue::HELD_BY_FAILED);
+    assert!(queue::flush(&local.db_path, &mock.auth(), None).await.unwrap().results.is_empty());
+    assert!(mock.state().rows("save_versions").is_empty());
+
+    assert_eq!(queue::retry_failed(&local.conn(), Some(&game.id)).unwrap(), 1);
+    let report = queue::flush(&local.db_path, &mock.auth(), None).await.unwrap();
+    assert_eq!(report.results.len(), 2);
+    assert_eq!(report.remaining, 0);
+    assert_eq!(queued_archives(&local), 0);
+    let state = mock.state();
+    let versions = state.rows("save_versions");
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[1]["checksum"], held.checksum.unwrap());
+}
+
+#[tokio::test]
+async fn discarded_entries_release_their_game() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let game = local.add_game("Discarded", &[("save.dat", "data")]);
+    run_sync(&local.db_path, &game.id, &offline(&mock), None).await.unwrap();
+    mock.fail("POST", "/storage/v1/object/saves", 500, Some(MAX_RETRIES as usize));
+    for _ in 0..MAX_RETRIES {
+        queue::flush(&local.db_path, &mock.auth(), None).await.unwrap();
+    }
+
+    assert_eq!(queue::discard_failed(&local.conn(), None).unwrap(), 1);
+    assert_eq!(queue::failed_count(&local.conn(), None).unwrap(), 0);
+    assert_eq!(queued_archives(&local), 0);
+
+    write(&PathBuf::from(&game.local_path).join("save.dat"), "newer");
+    let result = run_sync(&local.db_path, &game.id, &mock.auth(), None).await.unwrap();
+    assert!(!result.queued, "{}", result.message);
+    assert_eq!(mock.state().rows("save_versions").len(), 1);
+}
+
+#[tokio::test]
+async fn paused_games_stay_queued() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let game = local.add_game("Queued Then Paused", &[("save.dat", "data")]);
+    run_sync(&local.db_path, &game.id, &offline(&mock), None).await.unwrap();
+
+    let pause = |paused| UpdateGameParams {
+        sync_paused: Some(paused),
+        ..Default::default()
+    };
+    games::update_game(&local.conn(), &game.id, pause(true)).unwrap();
+    let report = queue::flush(&local.db_path, &mock.auth(), None).await.unwrap();
+    assert!(report.results.is_empty());
+    assert_eq!(report.remaining, 1);
+
+    games::update_game(&local.conn(), &game.id, pause(false)).unwrap();
+    let report = queue::flush(&local.db_path, &mock.auth(), None).await.unwrap();
+    assert_eq!(report.results.len(), 1);
+    assert_eq!(mock.state().rows("save_versions").len(), 1);
+}
+
+#[tokio::test]
+async fn saves_queued_while_signed_out_go_up_later() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let game = local.add_game("Signed Out", &[("save.dat", "played")]);
+
+    let queued = queue_saves(&local.db_path, &game.id).unwrap();
+    assert!(queued.queued);
+    assert_eq!(pending(&local, Some(&game.id)), 1);
+    assert!(mock.state().log.is_empty());
+
+    let report = queue::flush(&local.db_path, &mock.auth(), None).await.unwrap();
+    assert_eq!(report.remaining, 0);
+    {
+        let state = mock.state();
+        let versions = state.rows("save_versions");
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0]["checksum"], queued.checksum.unwrap().as_str());
+    }
+
+    // Paused games keep nothing back for later
+    let pause = UpdateGameParams {
+        sync_paused: Some(true),
+        ..Default::default()
+    };
+    games::update_game(&local.conn(), &game.id, pause).unwrap();
+    assert!(queue_saves(&local.db_path, &game.id).is_err());
+    assert_eq!(pending(&local, Some(&game.id)), 0);
+}
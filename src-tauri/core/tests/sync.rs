@@ -0,0 +1,372 @@
+//! Full sync and restore scenarios against a mock Supabase.
+
+mod common;
+
+use common::{read, write, LocalSetup, MockSupabase, USER_ID};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Duration;
+use sync_saves_core::device;
+use sync_saves_core::games;
+use sync_saves_core::sync::run_sync;
+use sync_saves_core::versions::{list_cloud_versions, restore_cloud_version, VersionMeta};
+
+fn last_synced_id(local: &LocalSetup, game_id: &str) -> Option<String> {
+    games::get_local_game(&local.conn(), game_id).unwrap().last_synced_id
+}
+
+#[tokio::test]
+async fn sync_modify_sync_restore() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let game = local.add_game("Round Trip", &[("slot1.sav", "chapter 1"), ("config/settings.ini", "volume=3")]);
+    let saves = PathBuf::from(&game.local_path);
+
+    let first = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap();
+    assert!(first.success && !first.skipped);
+    let first_id = first.version_id.clone().unwrap();
+
+    write(&saves.join("slot1.sav"), "chapter 2");
+    write(&saves.join("slot2.sav"), "new game+");
+    let second = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap();
+    assert!(!second.skipped);
+    let second_id = second.version_id.clone().unwrap();
+    assert_ne!(first.checksum, second.checksum);
+    assert_eq!(first.device_id, second.device_id);
+    assert_eq!(last_synced_id(&local, &game.id), Some(second_id.clone()));
+
+    {
+        let state = mock.state();
+        assert_eq!(state.rows("games").len(), 1);
+        assert_eq!(state.rows("devices").len(), 1);
+        assert_eq!(state.rows("game_paths").len(), 1);
+        assert_eq!(state.objects.len(), 2);
+        let versions = state.rows("save_versions");
+        assert_eq!(versions.len(), 2);
+        let latest: Vec<&str> = versions
+            .iter()
+            .filter(|v| v["is_latest"] == true)
+            .filter_map(|v| v["id"].as_str())
+            .collect();
+        assert_eq!(latest, [second_id.as_str()]);
+        assert!(versions.iter().all(|v| v["file_path"]
+            .as_str()
+            .unwrap()
+            .starts_with(&format!("{}/round-trip/", USER_ID))));
+    }
+
+    write(&saves.join("slot1.sav"), "corrupted");
+    let restored = restore_cloud_version(&local.db_path, &game.id, &auth, Some(&first_id))
+        .await
+        .unwrap();
+    assert_eq!(restored.version_id, first_id);
+    assert_eq!(read(&saves.join("slot1.sav")), "chapter 1");
+    assert_eq!(read(&saves.join("config/settings.ini")), "volume=3");
+    assert_eq!(last_synced_id(&local, &game.id), Some(first_id));
+
+    let restored = restore_cloud_version(&local.db_path, &game.id, &auth, None).await.unwrap();
+    assert_eq!(restored.version_id, second_id);
+    assert_eq!(read(&saves.join("slot1.sav")), "chapter 2");
+    assert_eq!(read(&saves.join("slot2.sav")), "new game+");
+}
+
+#[tokio::test]
+async fn unchanged_saves_are_skipped() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let game = local.add_game("Unchanged", &[("save.dat", "same bytes")]);
+
+    let first = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap();
+    // Archive entries carry timestamps with 2 second resolution; a later
+    // archive of the same files must still hash the same
+    tokio::time::sleep(Duration::from_millis(2100)).await;
+    let second = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap();
+
+    assert!(!first.skipped);
+    assert!(second.skipped, "{}", second.message);
+    assert_eq!(second.version_id, None);
+    assert_eq!(first.checksum, second.checksum);
+    assert_eq!(last_synced_id(&local, &game.id), first.version_id);
+
+    let state = mock.state();
+    assert_eq!(state.objects.len(), 1);
+    assert_eq!(state.rows("save_versions").len(), 1);
+    assert_eq!(state.count("POST", "/storage/v1/object/saves"), 1);
+}
+
+#[tokio::test]
+async fn upload_error_creates_no_version() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let game = local.add_game("Upload Error", &[("save.dat", "progress")]);
+    mock.fail("POST", "/storage/v1/object/saves", 500, Some(1));
+
+    let err = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap_err();
+    assert!(err.starts_with("Upload failed"), "{}", err);
+    assert!(mock.state().rows("save_versions").is_empty());
+    assert_eq!(last_synced_id(&local, &game.id), None);
+
+    // The next attempt goes through
+    let retry = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap();
+    assert!(!retry.skipped);
+    assert_eq!(mock.state().rows("save_versions").len(), 1);
+}
+
+#[tokio::test]
+async fn rest_error_stops_before_upload() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let game = local.add_game("Rest Error", &[("save.dat", "progress")]);
+    mock.fail("GET", "/rest/v1/games", 503, None);
+
+    assert!(run_sync(&local.db_path, &game.id, &auth, None).await.is_err());
+    let state = mock.state();
+    assert!(state.rows("games").is_empty());
+    assert!(state.objects.is_empty());
+    assert!(state.rows("save_versions").is_empty());
+}
+
+#[tokio::test]
+async fn version_record_error_fails_sync() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let game = local.add_game("Record Error", &[("save.dat", "progress")]);
+    let first = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap();
+    write(&PathBuf::from(&game.local_path).join("save.dat"), "more progress");
+    mock.fail("POST", "/rest/v1/rpc/create_save_version", 500, None);
+
+    let err = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap_err();
+    assert!(err.contains("Failed to create save version"), "{}", err);
+    assert_eq!(last_synced_id(&local, &game.id), first.version_id);
+
+    // The upload is removed again and the previous version stays the latest
+    let state = mock.state();
+    assert_eq!(state.objects.len(), 1);
+    let versions = state.rows("save_versions");
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0]["is_latest"], true);
+}
+
+#[tokio::test]
+async fn backend_without_rpc_still_records_versions() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let game = local.add_game("Old Backend", &[("save.dat", "first")]);
+    mock.fail("POST", "/rest/v1/rpc/create_save_version", 404, None);
+
+    run_sync(&local.db_path, &game.id, &auth, None).await.unwrap();
+    write(&PathBuf::from(&game.local_path).join("save.dat"), "second");
+    let second = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap();
+
+    let state = mock.state();
+    assert_eq!(state.count("POST", "/rest/v1/save_versions"), 2);
+    let latest: Vec<&Value> = state.rows("save_versions").iter().filter(|v| v["is_latest"] == true).collect();
+    assert_eq!(latest.len(), 1);
+    assert_eq!(latest[0]["id"].as_str(), second.version_id.as_deref());
+}
+
+#[tokio::test]
+async fn duplicate_label_is_rejected() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let game = local.add_game("Label Clash", &[("save.dat", "before boss")]);
+    let meta = |label: &str| VersionMeta {
+        label: Some(label.to_string()),
+        ..Default::default()
+    };
+
+    run_sync(&local.db_path, &game.id, &auth, Some(meta("Before Boss"))).await.unwrap();
+    write(&PathBuf::from(&game.local_path).join("save.dat"), "after boss");
+    let err = run_sync(&local.db_path, &game.id, &auth, Some(meta("before boss")))
+        .await
+        .unwrap_err();
+
+    assert!(err.contains("already used"), "{}", err);
+    let state = mock.state();
+    assert_eq!(state.rows("save_versions").len(), 1);
+    assert_eq!(state.objects.len(), 1);
+}
+
+#[tokio::test]
+async fn concurrent_upload_is_not_a_label_clash() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let game = local.add_game("Latest Clash", &[("save.dat", "progress")]);
+    mock.fail("POST", "/rest/v1/rpc/create_save_version", 409, Some(1));
+    let meta = VersionMeta {
+        label: Some("Chapter 2".to_string()),
+        ..Default::default()
+    };
+
+    let err = run_sync(&local.db_path, &game.id, &auth, Some(meta)).await.unwrap_err();
+    assert!(err.contains("at the same time"), "{}", err);
+    assert!(mock.state().objects.is_empty());
+}
+
+#[tokio::test]
+async fn failed_latest_reset_creates_no_version() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let game = local.add_game("Reset Error", &[("save.dat", "first")]);
+    mock.fail("POST", "/rest/v1/rpc/create_save_version", 404, None);
+    let first = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap();
+    write(&PathBuf::from(&game.local_path).join("save.dat"), "second");
+    mock.fail("PATCH", "/rest/v1/save_versions", 500, Some(1));
+
+    let err = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap_err();
+    assert!(err.contains("Failed to create save version"), "{}", err);
+
+    let state = mock.state();
+    assert_eq!(state.count("POST", "/rest/v1/save_versions"), 1);
+    assert_eq!(state.objects.len(), 1);
+    let versions = state.rows("save_versions");
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0]["id"].as_str(), first.version_id.as_deref());
+    assert_eq!(versions[0]["is_latest"], true);
+}
+
+#[tokio::test]
+async fn device_registered_during_sync_is_reused() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let game = local.add_game("Device Race", &[("save.dat", "progress")]);
+
+    // Another sync registered this machine between our lookup and insert
+    let machine_id = device::device_id(&local.conn()).unwrap();
+    let existing = mock.insert(
+        "devices",
+        serde_json::json!({ "user_id": USER_ID, "machine_id": machine_id, "name": "Other process" }),
+    );
+    mock.stale_reads("devices", 1);
+
+    let result = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap();
+    assert_eq!(result.device_id.as_deref(), existing["id"].as_str());
+    assert_eq!(mock.state().rows("devices").len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn concurrent_syncs_share_one_device() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let a = local.add_game("Parallel A", &[("a.sav", "a")]);
+    let b = local.add_game("Parallel B", &[("b.sav", "b")]);
+
+    let (ra, rb) = tokio::join!(
+        run_sync(&local.db_path, &a.id, &auth, None),
+        run_sync(&local.db_path, &b.id, &auth, None)
+    );
+    let (ra, rb) = (ra.unwrap(), rb.unwrap());
+
+    assert_eq!(ra.device_id, rb.device_id);
+    let state = mock.state();
+    assert_eq!(state.rows("devices").len(), 1);
+    assert_eq!(state.rows("games").len(), 2);
+    assert_eq!(state.rows("save_versions").len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn concurrent_devices_leave_one_latest_version() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let desktop = LocalSetup::new();
+    let laptop = LocalSetup::new();
+    let a = desktop.add_game("Shared Game", &[("save.dat", "desktop")]);
+    let b = laptop.add_game("Shared Game", &[("save.dat", "laptop")]);
+    // Both devices sync once so the second round races only on the version
+    run_sync(&desktop.db_path, &a.id, &auth, None).await.unwrap();
+    run_sync(&laptop.db_path, &b.id, &auth, None).await.unwrap();
+    write(&PathBuf::from(&a.local_path).join("save.dat"), "desktop 2");
+    write(&PathBuf::from(&b.local_path).join("save.dat"), "laptop 2");
+
+    let (ra, rb) = tokio::join!(
+        run_sync(&desktop.db_path, &a.id, &auth, None),
+        run_sync(&laptop.db_path, &b.id, &auth, None)
+    );
+    let (ra, rb) = (ra.unwrap(), rb.unwrap());
+
+    let state = mock.state();
+    assert_eq!(state.rows("games").len(), 1);
+    let versions = state.rows("save_versions");
+    assert_eq!(versions.len(), 4);
+    let latest: Vec<&Value> = versions.iter().filter(|v| v["is_latest"] == true).collect();
+    assert_eq!(latest.len(), 1);
+    let id = latest[0]["id"].as_str();
+    assert!(id == ra.version_id.as_deref() || id == rb.version_id.as_deref());
+    // Each version points at the one it replaced
+    for pair in versions.windows(2) {
+        assert_eq!(pair[1]["parent_version_id"], pair[0]["id"]);
+    }
+}
+
+#[tokio::test]
+async fn versions_past_one_page_are_listed() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let game = local.add_game("Long History", &[("save.dat", "latest")]);
+    // Inserted first, so older than the version the sync creates
+    for _ in 0..1200 {
+        mock.insert(
+            "save_versions",
+            serde_json::json!({ "file_path": "old.zip", "file_size": 1, "is_latest": false }),
+        );
+    }
+    let result = run_sync(&local.db_path, &game.id, &auth, None).await.unwrap();
+    let cloud_game_id = result.cloud_game_id.unwrap();
+    for row in mock.state().tables.get_mut("save_versions").unwrap() {
+        row["game_id"] = serde_json::json!(cloud_game_id);
+    }
+
+    let versions = list_cloud_versions(&reqwest::Client::new(), &auth, &cloud_game_id).await.unwrap();
+    assert_eq!(versions.len(), 1201);
+    assert_eq!(versions[0].id, result.version_id.unwrap());
+    assert_eq!(mock.state().count("GET", "/rest/v1/save_versions"), 2 + 1);
+}
+
+#[tokio::test]
+async fn restore_errors_leave_saves_alone() {
+    let mock = MockSupabase::start().await;
+    let auth = mock.auth();
+    let local = LocalSetup::new();
+    let game = local.add_game("Restore Error", &[("save.dat", "synced")]);
+    let save = PathBuf::from(&game.local_path).join("save.dat");
+    run_sync(&local.db_path, &game.id, &auth, None).await.unwrap();
+    write(&save, "local only");
+
+    let err = restore_cloud_version(&local.db_path, &game.id, &auth, Some("no such label"))
+        .await
+        .unwrap_err();
+    assert!(err.starts_with("No version with id or label"), "{}", err);
+
+    mock.fail("GET", "/storage/v1/object/saves", 500, Some(1));
+    let err = restore_cloud_version(&local.db_path, &game.id, &auth, None).await.unwrap_err();
+    assert!(err.starts_with("Download failed"), "{}", err);
+
+    mock.fail("GET", "/rest/v1/save_versions", 401, Some(1));
+    assert!(restore_cloud_version(&local.db_path, &game.id, &auth, None).await.is_err());
+
+    assert_eq!(read(&save), "local only");
+}
+
+#[tokio::test]
+async fn restore_without_cloud_game_fails() {
+    let mock = MockSupabase::start().await;
+    let local = LocalSetup::new();
+    let game = local.add_game("Never Synced", &[("save.dat", "local")]);
+
+    let err = restore_cloud_version(&local.db_path, &game.id, &mock.auth(), None)
+        .await
+        .unwrap_err();
+    assert_eq!(err, "This game has no versions in the cloud");
+}
@@ -0,0 +1,260 @@
+use crate::db;
+use base64::{engine::general_purpose, Engine as _};
+use rusqlite::Connection;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use sync_saves_core::games::load_game_locations;
+use sync_saves_core::services::analysis::{self, DecoderInfo};
+use sync_saves_core::services::analysis_runner::{self, AnalysisConfig, AnalysisOutput, DEFAULT_TIMEOUT_SECS};
+use sync_saves_core::services::extraction;
+use sync_saves_core::utils::file_pattern;
+use tauri::{command, AppHandle};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+struct AnalysisSetup {
+    analyzer: String,
+    config: AnalysisConfig,
+    local_path: String,
+    last_synced_id: Option<String>,
+}
+
+fn load_setup(conn: &Connection, game_id: &str) -> Result<AnalysisSetup, String> {
+    let (script, config_json, local_path, last_synced_id): (Option<String>, Option<String>, String, Option<String>) = conn
+        .query_row(
+            "SELECT custom_script_path, analysis_config, local_path, last_synced_id FROM games_cache WHERE id = ?1",
+            [game_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let analyzer = script
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| "No analysis script configured for this game".to_string())?;
+
+    let config: AnalysisConfig = config_json
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    if config.target_path.trim().is_empty() {
+        return Err("No target save file configured for analysis".to_string());
+    }
+
+    Ok(AnalysisSetup {
+        analyzer,
+        config,
+        local_path,
+        last_synced_id,
+    })
+}
+
+/// A throwaway folder under the app cache, removed when dropped.
+struct Sandbox(PathBuf);
+
+impl Sandbox {
+    fn create(app: &AppHandle) -> Result<Self, String> {
+        let dir = db::app_paths(app)?
+            .analysis_dir()
+            .join(Uuid::new_v4().to_string());
+        fs::create_dir_all(dir.join("save")).map_err(|e| e.to_string())?;
+        Ok(Sandbox(dir))
+    }
+
+    fn save_dir(&self) -> PathBuf {
+        self.0.join("save")
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Where a location's files go inside the sandbox; single-file saves keep their name
+/// so `target_path` can find them.
+fn sandbox_target(base: &Path, location_path: &str) -> PathBuf {
+    let path = Path::new(location_path);
+    match path.file_name() {
+        Some(name) if !path.is_dir() => base.join(name),
+        _ => base.to_path_buf(),
+    }
+}
+
+fn extract_version(conn: &Connection, game_id: &str, setup: &AnalysisSetup, zip: &Path, save_dir: &Path) -> Result<(), String> {
+    let is_multi_location = extraction::read_location_labels(zip)
+        .map_err(|e| e.to_string())?
+        .is_some();
+
+    if is_multi_location {
+        let targets: Vec<(String, PathBuf)> = load_game_locations(conn, game_id)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|l| {
+                let base = save_dir.join(&l.label);
+                (l.label, sandbox_target(&base, &l.path))
+            })
+            .collect();
+        extraction::extract_locations(zip, &targets).map_err(|e| e.to_string())?;
+    } else {
+        extraction::extract_zip(zip, &sandbox_target(save_dir, &setup.local_path)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn copy_local_save(conn: &Connection, game_id: &str, save_dir: &Path) -> Result<(), String> {
+    let locations = load_game_locations(conn, game_id).map_err(|e| e.to_string())?;
+    let multi = locations.len() > 1;
+
+    for location in locations {
+        let src = PathBuf::from(&location.path);
+        let base = if multi { save_dir.join(&location.label) } else { save_dir.to_path_buf() };
+
+        if src.is_file() {
+            let dest = sandbox_target(&base, &location.path);
+            fs::create_dir_all(&base).map_err(|e| e.to_string())?;
+            fs::copy(&src, &dest).map_err(|e| e.to_string())?;
+            continue;
+        }
+
+        let pattern = file_pattern::parse_optional(location.file_pattern.as_deref());
+        for entry in WalkDir::new(&src).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(rel) = entry.path().strip_prefix(&src) else { continue };
+            if !pattern.as_ref().map(|p| p.matches(rel)).unwrap_or(true) {
+                continue;
+            }
+            let dest = base.join(rel);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::copy(entry.path(), &dest).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+fn analyze(setup: &AnalysisSetup, sandbox: &Sandbox) -> Result<AnalysisOutput, String> {
+    let save_dir = sandbox.save_dir();
+    let target = analysis_runner::find_target_file(&save_dir, &setup.config.target_path)
+        .ok_or_else(|| format!("Save file '{}' not found", setup.config.target_path))?;
+
+    let timeout = Duration::from_secs(setup.config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let started = Instant::now();
+    let raw = analysis_runner::run_analyzer(&setup.analyzer, &target, &sandbox.0, timeout)?;
+
+    let rel_target = target.strip_prefix(&save_dir).unwrap_or(&target).to_path_buf();
+    analysis_runner::build_output(raw, &setup.config, &setup.analyzer, &rel_target, started.elapsed())
+}
+
+fn update_game_stats(conn: &Connection, game_id: &str, output: &AnalysisOutput) -> Result<(), String> {
+    conn.execute(
+        "UPDATE games_cache SET
+            completion_percentage = COALESCE(?1, completion_percentage),
+            play_time_seconds = COALESCE(?2, play_time_seconds),
+            last_analyzed_at = ?3
+         WHERE id = ?4",
+        rusqlite::params![
+            output.completion_percentage,
+            output.play_time_seconds,
+            chrono::Utc::now().to_rfc3339(),
+            game_id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Analyzes a downloaded save version (base64 zip, as for `restore_game`) with
+/// the game's analyzer and stores the tracked values in `version_analysis`.
+/// Game stats are only updated when this is the game's latest synced version.
+#[command]
+pub async fn analyze_version(
+    app: AppHandle,
+    game_id: String,
+    version_id: String,
+    base64_data: String,
+) -> Result<AnalysisOutput, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+        let setup = load_setup(&conn, &game_id)?;
+        let sandbox = Sandbox::create(&app)?;
+
+        let bytes = general_purpose::STANDARD
+            .decode(base64_data)
+            .map_err(|e| e.to_string())?;
+        let zip = sandbox.0.join("version.zip");
+        fs::write(&zip, bytes).map_err(|e| e.to_string())?;
+        extract_version(&conn, &game_id, &setup, &zip, &sandbox.save_dir())?;
+        let _ = fs::remove_file(&zip);
+
+        let output = analyze(&setup, &sandbox)?;
+        println!(
+            "Analyzed version {} of {} in {}ms ({} values)",
+            version_id,
+            game_id,
+            output.duration_ms,
+            output.data.len()
+        );
+
+        let data = serde_json::to_string(&output.data).map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT OR REPLACE INTO version_analysis (version_id, analysis_data, completion_percentage, play_time_seconds, analyzer, game_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                &version_id,
+                &data,
+                output.completion_percentage,
+                output.play_time_seconds,
+                &output.analyzer,
+                &game_id
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+        if setup.last_synced_id.as_deref() == Some(version_id.as_str()) {
+            update_game_stats(&conn, &game_id, &output)?;
+        }
+
+        Ok(output)
+    })
+    .await
+    .map_err(|e| format!("Analysis failed: {}", e))?
+}
+
+/// Analyzes the save files currently on disk and updates the game's stats.
+#[command]
+pub async fn analyze_local_save(app: AppHandle, game_id: String) -> Result<AnalysisOutput, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+        let setup = load_setup(&conn, &game_id)?;
+        let sandbox = Sandbox::create(&app)?;
+
+        // The analyzer works on a copy so a misbehaving script can't touch the real save
+        copy_local_save(&conn, &game_id, &sandbox.save_dir())?;
+
+        let output = analyze(&setup, &sandbox)?;
+        update_game_stats(&conn, &game_id, &output)?;
+        Ok(output)
+    })
+    .await
+    .map_err(|e| format!("Analysis failed: {}", e))?
+}
+
+/// Built-in decoders that can be used as `builtin:<id>` analyzers.
+#[command]
+pub fn list_save_decoders() -> Vec<DecoderInfo> {
+    analysis::list_decoders()
+}
+
+/// Decodes a save file with a built-in decoder (`auto` by default), so the
+/// analysis settings can show which fields are available.
+#[command]
+pub async fn decode_save_file(path: String, decoder: Option<String>) -> Result<serde_json::Value, String> {
+    let decoder = decoder.unwrap_or_else(|| "auto".to_string());
+    tauri::async_runtime::spawn_blocking(move || analysis::decode_file(&decoder, Path::new(&path)))
+        .await
+        .map_err(|e| format!("Decoding failed: {}", e))?
+}
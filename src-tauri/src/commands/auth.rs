@@ -1,6 +1,14 @@
 use crate::db;
+use sync_saves_core::session::{self, store_session, CloudSession, APP_SESSION_KEY};
+use sync_saves_core::sync::AuthConfig;
 use tauri::{command, AppHandle};
 
+/// The app's shared session, for work the backend starts without the frontend.
+pub(crate) fn app_auth(app: &AppHandle) -> Result<Option<AuthConfig>, String> {
+    let conn = db::get_connection(app).map_err(|e| e.to_string())?;
+    session::app_auth(&conn)
+}
+
 #[command]
 pub fn set_current_user(app: AppHandle, user_id: String) -> Result<(), String> {
     // Store user_id in device_config or session state
@@ -33,3 +41,14 @@ pub fn get_current_user(app: AppHandle) -> Result<Option<String>, String> {
 
     Ok(None)
 }
+
+/// Shares the app's current sign-in with the CLI; `None` on sign-out.
+#[command]
+pub fn share_cloud_session(app: AppHandle, session: Option<CloudSession>) -> Result<(), String> {
+    let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+    let session = session.map(|s| CloudSession {
+        refresh_token: None,
+        ..s
+    });
+    store_session(&conn, APP_SESSION_KEY, session.as_ref())
+}
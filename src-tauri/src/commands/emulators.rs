@@ -0,0 +1,223 @@
+use crate::db;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use sync_saves_core::games::{get_local_game, insert_location, LocalGame, PRIMARY_LOCATION_LABEL};
+use sync_saves_core::utils::file_pattern::{self, FilePattern};
+use sync_saves_core::utils::path_template::PathContext;
+use tauri::{command, AppHandle};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+/// Save, memory card and save state folders of the supported emulators.
+const EMULATOR_PROFILES_JSON: &str = include_str!("../../resources/emulator_profiles.json");
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EmulatorProfile {
+    pub id: String,
+    pub name: String,
+    pub pattern_hint: String,
+    /// The first directory is labeled "saves" and becomes the game's primary location
+    pub directories: Vec<EmulatorDirectory>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EmulatorDirectory {
+    pub label: String,
+    pub templates: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EmulatorProfileStatus {
+    pub id: String,
+    pub name: String,
+    pub pattern_hint: String,
+    pub installed: bool,
+    pub directories: Vec<EmulatorDirectoryStatus>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EmulatorDirectoryStatus {
+    pub label: String,
+    pub template: Option<String>,
+    pub path: Option<String>,
+    pub exists: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EmulatorSaveFile {
+    /// Path relative to the emulator folder, with forward slashes
+    pub rel_path: String,
+    pub size: u64,
+    pub modified_ms: Option<i64>,
+}
+
+pub(crate) fn emulator_profiles() -> Vec<EmulatorProfile> {
+    serde_json::from_str(EMULATOR_PROFILES_JSON).unwrap_or_default()
+}
+
+fn find_profile(profile_id: &str) -> Result<EmulatorProfile, String> {
+    emulator_profiles()
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| format!("Unknown emulator: {}", profile_id))
+}
+
+/// Picks the template for this device: the first one whose folder exists,
+/// otherwise the first one that resolves here.
+fn locate_directory(dir: &EmulatorDirectory, ctx: &PathContext) -> EmulatorDirectoryStatus {
+    let resolved: Vec<(String, String, bool)> = dir
+        .templates
+        .iter()
+        .filter_map(|t| {
+            let path = ctx.resolve(t).ok()?;
+            Some((t.clone(), path.to_string_lossy().to_string(), path.is_dir()))
+        })
+        .collect();
+
+    let best = resolved
+        .iter()
+        .find(|(_, _, exists)| *exists)
+        .or_else(|| resolved.first());
+
+    EmulatorDirectoryStatus {
+        label: dir.label.clone(),
+        template: best.map(|(t, _, _)| t.clone()),
+        path: best.map(|(_, p, _)| p.clone()),
+        exists: best.map(|(_, _, e)| *e).unwrap_or(false),
+    }
+}
+
+/// Lists the emulator profiles and where their folders are on this device.
+#[command]
+pub fn get_emulator_profiles() -> Vec<EmulatorProfileStatus> {
+    let ctx = PathContext::current(None);
+
+    emulator_profiles()
+        .into_iter()
+        .map(|profile| {
+            let directories: Vec<EmulatorDirectoryStatus> = profile
+                .directories
+                .iter()
+                .map(|d| locate_directory(d, &ctx))
+                .collect();
+
+            EmulatorProfileStatus {
+                installed: directories.first().map(|d| d.exists).unwrap_or(false),
+                id: profile.id,
+                name: profile.name,
+                pattern_hint: profile.pattern_hint,
+                directories,
+            }
+        })
+        .collect()
+}
+
+/// Lists files in one of an emulator's folders, optionally filtered by a
+/// pattern, so the user can check what a pattern would sync.
+#[command]
+pub fn list_emulator_save_files(
+    profile_id: String,
+    label: String,
+    file_pattern: Option<String>,
+) -> Result<Vec<EmulatorSaveFile>, String> {
+    let profile = find_profile(&profile_id)?;
+    let dir = profile
+        .directories
+        .iter()
+        .find(|d| d.label == label)
+        .ok_or_else(|| format!("{} has no '{}' folder", profile.name, label))?;
+
+    let status = locate_directory(dir, &PathContext::current(None));
+    let Some(root) = status.path.filter(|_| status.exists) else {
+        return Ok(vec![]);
+    };
+
+    let pattern = file_pattern::parse_optional(file_pattern.as_deref());
+    Ok(list_files(Path::new(&root), pattern.as_ref()))
+}
+
+fn list_files(root: &Path, pattern: Option<&FilePattern>) -> Vec<EmulatorSaveFile> {
+    let mut files: Vec<EmulatorSaveFile> = WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let rel = e.path().strip_prefix(root).ok()?;
+            if !pattern.map(|p| p.matches(rel)).unwrap_or(true) {
+                return None;
+            }
+            let md = e.metadata().ok()?;
+            Some(EmulatorSaveFile {
+                rel_path: rel.to_string_lossy().replace('\\', "/"),
+                size: md.len(),
+                modified_ms: md
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as i64),
+            })
+        })
+        .collect();
+
+    files.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    files
+}
+
+/// Adds a game whose saves live in an emulator's shared folders. Every
+/// profile folder found on this device becomes a location limited to
+/// `file_pattern`, so restores land back in the right emulator folder.
+#[command]
+pub fn add_emulator_game(
+    app: AppHandle,
+    profile_id: String,
+    name: String,
+    file_pattern: String,
+    cover_url: Option<String>,
+) -> Result<LocalGame, String> {
+    let profile = find_profile(&profile_id)?;
+    if FilePattern::parse(&file_pattern).is_none() {
+        return Err("A file pattern is required to pick this game's files".to_string());
+    }
+
+    let ctx = PathContext::current(None);
+    let directories: Vec<EmulatorDirectoryStatus> = profile
+        .directories
+        .iter()
+        .map(|d| locate_directory(d, &ctx))
+        .collect();
+
+    let primary = directories
+        .first()
+        .filter(|d| d.label == PRIMARY_LOCATION_LABEL && d.template.is_some())
+        .ok_or_else(|| format!("{} save folder not found on this device", profile.name))?;
+    let primary_path = primary.path.clone().unwrap_or_default();
+
+    let mut conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+    let id = Uuid::new_v4().to_string();
+    let slug = name.to_lowercase().replace(" ", "-");
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO games_cache (id, name, slug, platform, local_path, sync_enabled, cover_url, status, emulator_profile)
+         VALUES (?1, ?2, ?3, 'emulator', ?4, 1, ?5, 'idle', ?6)",
+        rusqlite::params![&id, &name, &slug, &primary_path, &cover_url, &profile.id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut sort_order = 0;
+    for dir in &directories {
+        // Folders this emulator build doesn't have (e.g. no save states) are skipped
+        let Some(template) = &dir.template else { continue };
+        if sort_order > 0 && !dir.exists {
+            continue;
+        }
+        insert_location(&tx, &id, &dir.label, template, sort_order, &ctx, Some(&file_pattern))?;
+        sort_order += 1;
+    }
+
+    let game = get_local_game(&tx, &id)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(game)
+}
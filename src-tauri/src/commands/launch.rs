@@ -0,0 +1,170 @@
+use crate::commands::auth::app_auth;
+use crate::db;
+use crate::{notifications, queue, tray};
+use serde::Serialize;
+use sync_saves_core::games;
+use sync_saves_core::launch::{self, LaunchTarget, PullAction, PullReport, RunningGame};
+use sync_saves_core::notifications::SyncEvent;
+use sync_saves_core::sync::{self, AuthConfig, SyncResult};
+use tauri::{command, AppHandle, Emitter};
+
+const LAUNCH_LINK_PREFIX: &str = "sync-saves://launch/";
+
+#[derive(Serialize, Debug)]
+pub struct LaunchReport {
+    pub pull: Option<PullReport>,
+    /// Why the cloud couldn't be checked; the game starts with the local saves anyway
+    pub pull_error: Option<String>,
+    pub target: String,
+    /// Whether the saves are uploaded when the game exits
+    pub tracked: bool,
+}
+
+/// Pulls the cloud's latest save when it is ahead; returns the pull, why the
+/// cloud couldn't be checked and the session used. Without `auth` the app's
+/// shared session is used.
+pub(crate) async fn pull_before_launch(
+    app: &AppHandle,
+    game_id: &str,
+    auth: Option<AuthConfig>,
+) -> Result<(Option<PullReport>, Option<String>, Option<AuthConfig>), String> {
+    let db_path = db::get_db_path(app);
+    let auth = match auth {
+        Some(auth) => Some(auth),
+        None => app_auth(app)?,
+    };
+
+    let (pull, pull_error) = match &auth {
+        Some(auth) => match launch::pull_latest(&db_path, game_id, auth).await {
+            Ok(report) => (Some(report), None),
+            Err(e) => (None, Some(e)),
+        },
+        None => (None, Some("Not signed in".to_string())),
+    };
+    if let Some(e) = &pull_error {
+        eprintln!("Launching {} without checking the cloud: {}", game_id, e);
+    }
+    match pull.as_ref().map(|p| p.action) {
+        Some(PullAction::Pulled) => notifications::sync_event(app, game_id, SyncEvent::Restored { label: None }),
+        Some(PullAction::KeptLocal) => notifications::sync_event(app, game_id, SyncEvent::Conflict),
+        _ => {}
+    }
+    tray::refresh(app);
+    Ok((pull, pull_error, auth))
+}
+
+/// Pulls the cloud's latest save when it is ahead, starts the game (`command`,
+/// else its executable, else its Steam app) and uploads its saves once it
+/// exits. Without `auth` the app's shared session is used.
+pub(crate) async fn launch_game(
+    app: &AppHandle,
+    game_id: &str,
+    auth: Option<AuthConfig>,
+    command: Option<Vec<String>>,
+) -> Result<(LaunchReport, RunningGame, Option<AuthConfig>), String> {
+    let (pull, pull_error, auth) = pull_before_launch(app, game_id, auth).await?;
+
+    let game = {
+        let conn = db::get_connection(app).map_err(|e| e.to_string())?;
+        games::get_local_game(&conn, game_id)?
+    };
+    let target = LaunchTarget::for_game(&game, command)?;
+    let running = launch::start(&target)?;
+
+    let report = LaunchReport {
+        pull,
+        pull_error,
+        target: target.describe(),
+        tracked: running.is_tracked(),
+    };
+    Ok((report, running, auth))
+}
+
+/// Uploads the game's saves after it exits and emits `game-exited`.
+pub(crate) async fn push_on_exit(
+    app: &AppHandle,
+    game_id: &str,
+    launch_auth: Option<AuthConfig>,
+) -> Result<SyncResult, String> {
+    let db_path = db::get_db_path(app);
+    // The app's session may have been refreshed while the game was running
+    let auth = app_auth(app).ok().flatten().or(launch_auth);
+    let result = match auth {
+        Some(auth) => tray::track(app, game_id, sync::run_sync(&db_path, game_id, &auth, None)).await,
+        None => Err("Not signed in".to_string()),
+    };
+    // The play session's saves wait in the queue rather than being lost
+    let result = match result {
+        Err(e) => {
+            eprintln!("Upload after {} exited failed: {}", game_id, e);
+            match sync::queue_saves(&db_path, game_id) {
+                Ok(queued) => {
+                    queue::changed(app);
+                    Ok(queued)
+                }
+                Err(_) => Err(e),
+            }
+        }
+        ok => ok,
+    };
+    notifications::sync_result(app, game_id, &result);
+
+    let _ = app.emit(
+        "game-exited",
+        serde_json::json!({
+            "game_id": game_id,
+            "result": result.as_ref().ok(),
+            "error": result.as_ref().err(),
+        }),
+    );
+    result
+}
+
+fn watch_in_background(app: AppHandle, game_id: String, running: RunningGame, auth: Option<AuthConfig>) {
+    if !running.is_tracked() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        let _ = tauri::async_runtime::spawn_blocking(move || running.wait()).await;
+        let _ = push_on_exit(&app, &game_id, auth).await;
+    });
+}
+
+#[command]
+pub async fn prepare_launch(
+    app: AppHandle,
+    game_id: String,
+    auth: Option<AuthConfig>,
+) -> Result<LaunchReport, String> {
+    let (report, running, auth) = launch_game(&app, &game_id, auth, None).await?;
+    watch_in_background(app, game_id, running, auth);
+    Ok(report)
+}
+
+/// The game id of a `sync-saves://launch/{game_id}` link.
+pub(crate) fn launch_link_game_id(url: &str) -> Option<String> {
+    let id = url
+        .trim_matches(|c| c == '"' || c == '\'')
+        .trim()
+        .strip_prefix(LAUNCH_LINK_PREFIX)?
+        .split(['?', '#'])
+        .next()?
+        .trim_end_matches('/');
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+/// Launches the game of a deep link; failures go to the UI as `launch-failed`.
+pub(crate) fn launch_from_link(app: AppHandle, game_id: String) {
+    tauri::async_runtime::spawn(async move {
+        match launch_game(&app, &game_id, None, None).await {
+            Ok((report, running, auth)) => {
+                println!("Launched {} ({})", game_id, report.target);
+                watch_in_background(app, game_id, running, auth);
+            }
+            Err(e) => {
+                eprintln!("Launch of {} failed: {}", game_id, e);
+                let _ = app.emit("launch-failed", serde_json::json!({ "game_id": game_id, "error": e }));
+            }
+        }
+    });
+}
@@ -0,0 +1,97 @@
+use crate::commands::manifest::manifest_candidates;
+use crate::commands::scan::{bundled_candidates, load_existing_games, scan_candidates, ScanCandidate, ScanSuggestion};
+use crate::db;
+use std::collections::HashMap;
+use sync_saves_core::services::launchers::{self, LauncherGame};
+use tauri::{command, AppHandle};
+
+/// Lists games installed through Heroic, Lutris, GOG Galaxy and the Epic launcher.
+#[command]
+pub async fn list_launcher_games(launchers: Option<Vec<String>>) -> Result<Vec<LauncherGame>, String> {
+    let launchers = launchers.unwrap_or_default();
+    validate_launchers(&launchers)?;
+
+    tauri::async_runtime::spawn_blocking(move || launchers::installed_games(&launchers))
+        .await
+        .map_err(|e| format!("Launcher scan failed: {}", e))
+}
+
+/// Finds save folders for launcher games, using the launcher's install folder
+/// and Wine prefix to resolve known save locations. Results can be passed to
+/// `add_game` as-is, with `platform` already set to "gog", "epic", ...
+#[command]
+pub async fn scan_launcher_games(
+    app: AppHandle,
+    launchers: Option<Vec<String>>,
+    include_manifest: Option<bool>,
+) -> Result<Vec<ScanSuggestion>, String> {
+    let launchers = launchers.unwrap_or_default();
+    validate_launchers(&launchers)?;
+
+    let mut known = bundled_candidates();
+    if include_manifest.unwrap_or(true) {
+        let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+        known.extend(manifest_candidates(&conn)?);
+    }
+
+    let (known_names, known_paths) = load_existing_games(&app)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let games = launchers::installed_games(&launchers);
+        println!("Launcher scan: {} installed games", games.len());
+        let candidates = to_candidates(games, &known);
+        scan_candidates(candidates, &known_names, &known_paths)
+    })
+    .await
+    .map_err(|e| format!("Launcher scan failed: {}", e))
+}
+
+fn validate_launchers(launchers: &[String]) -> Result<(), String> {
+    match launchers.iter().find(|l| !launchers::LAUNCHERS.contains(&l.as_str())) {
+        Some(unknown) => Err(format!("Unknown launcher: {}", unknown)),
+        None => Ok(()),
+    }
+}
+
+/// Pairs each launcher game with the save templates known for its title.
+fn to_candidates(games: Vec<LauncherGame>, known: &[ScanCandidate]) -> Vec<ScanCandidate> {
+    let mut by_title: HashMap<String, &ScanCandidate> = HashMap::new();
+    for candidate in known {
+        by_title.entry(title_key(&candidate.name)).or_insert(candidate);
+    }
+
+    games
+        .into_iter()
+        .map(|game| {
+            let matched = by_title.get(&title_key(&game.title));
+            let mut templates: Vec<String> = game
+                .save_paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect();
+            if let Some(matched) = matched {
+                templates.extend(matched.templates.iter().cloned());
+            }
+
+            ScanCandidate {
+                name: matched.map(|m| m.name.clone()).unwrap_or(game.title),
+                templates,
+                platform: Some(game.platform),
+                // Only used for Steam/Proton lookups, which don't apply here
+                steam_app_id: None,
+                cover_url: None,
+                install_dir: game.install_path.map(|p| p.to_string_lossy().to_string()),
+                wine_prefix: game.wine_prefix.map(|p| p.to_string_lossy().to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Launchers decorate titles differently ("Hades™" vs "HADES", "Baldur's Gate 3" vs "Baldurs Gate 3").
+fn title_key(title: &str) -> String {
+    title
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
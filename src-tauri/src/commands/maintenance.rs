@@ -0,0 +1,12 @@
+use crate::db;
+use sync_saves_core::maintenance::{self, GcReport};
+use sync_saves_core::sync::AuthConfig;
+use tauri::{command, AppHandle};
+
+/// Cleans up local data: analyses of deleted games and of versions no longer
+/// in the cloud (when signed in), plus stale temp archives and sandboxes.
+#[command]
+pub async fn collect_garbage(app: AppHandle, auth: Option<AuthConfig>) -> Result<GcReport, String> {
+    let paths = db::app_paths(&app)?;
+    maintenance::collect_garbage(&paths, auth.as_ref()).await
+}
@@ -0,0 +1,271 @@
+use crate::commands::pcgw::{applies_to_current_os, expand_path_tokens, PcgwSaveLocations, PcgwSavePath};
+use crate::commands::scan::ScanCandidate;
+use crate::db;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::path::PathBuf;
+use sync_saves_core::services::ludusavi::{self, ManifestEntry};
+use sync_saves_core::utils::path_template::PathContext;
+use tauri::{command, AppHandle};
+
+#[derive(Serialize, Debug)]
+pub struct ManifestImportResult {
+    pub games: usize,
+    pub duration_ms: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ManifestStatus {
+    pub games: u64,
+    pub imported_at: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ManifestSearchResult {
+    pub title: String,
+    pub steam_app_id: Option<u32>,
+}
+
+/// Imports a Ludusavi `manifest.yaml`, replacing any previously imported data.
+#[command]
+pub async fn import_ludusavi_manifest(app: AppHandle, path: String) -> Result<ManifestImportResult, String> {
+    let start_time = std::time::Instant::now();
+
+    // The manifest is tens of MB of YAML, parse it off the async runtime
+    let games = tauri::async_runtime::spawn_blocking(move || -> Result<usize, String> {
+        let manifest = ludusavi::load_manifest(&PathBuf::from(&path))?;
+        let mut conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+        tx.execute("DELETE FROM manifest_games", []).map_err(|e| e.to_string())?;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT OR REPLACE INTO manifest_games (name, name_lower, steam_app_id, data)
+                     VALUES (?1, ?2, ?3, ?4)",
+                )
+                .map_err(|e| e.to_string())?;
+
+            for (name, game) in &manifest {
+                let entry = ludusavi::to_entry(name, game);
+                if entry.paths.is_empty() && entry.registry_keys.is_empty() {
+                    continue;
+                }
+                let data = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+                stmt.execute(rusqlite::params![name, name.to_lowercase(), entry.steam_app_id, data])
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+
+        tx.execute(
+            "INSERT OR REPLACE INTO device_config (key, value) VALUES ('manifest_imported_at', ?1)",
+            [chrono::Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| e.to_string())?;
+
+        // Entries without paths are skipped, so count what was stored
+        let stored: usize = tx
+            .query_row("SELECT COUNT(*) FROM manifest_games", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(stored)
+    })
+    .await
+    .map_err(|e| format!("Manifest import failed: {}", e))??;
+
+    Ok(ManifestImportResult {
+        games,
+        duration_ms: start_time.elapsed().as_millis() as u64,
+    })
+}
+
+#[command]
+pub fn manifest_status(app: AppHandle) -> Result<ManifestStatus, String> {
+    let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+
+    let games: u64 = conn
+        .query_row("SELECT COUNT(*) FROM manifest_games", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    let imported_at: Option<String> = conn
+        .query_row(
+            "SELECT value FROM device_config WHERE key = 'manifest_imported_at'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    Ok(ManifestStatus { games, imported_at })
+}
+
+#[command]
+pub fn manifest_search_games(
+    app: AppHandle,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<ManifestSearchResult>, String> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Ok(vec![]);
+    }
+    let limit = limit.unwrap_or(8).clamp(1, 50);
+
+    let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+    let pattern = format!("%{}%", query.replace('%', "\\%").replace('_', "\\_"));
+
+    // Exact and prefix matches first, then the shortest names
+    let mut stmt = conn
+        .prepare(
+            "SELECT name, steam_app_id FROM manifest_games
+             WHERE name_lower LIKE ?1 ESCAPE '\\'
+             ORDER BY (name_lower = ?2) DESC, (name_lower LIKE ?2 || '%') DESC, length(name)
+             LIMIT ?3",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![pattern, query, limit], |row| {
+            Ok(ManifestSearchResult {
+                title: row.get(0)?,
+                steam_app_id: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn manifest_get_save_locations(app: AppHandle, title: String) -> Result<PcgwSaveLocations, String> {
+    let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+
+    let data: Option<String> = conn
+        .query_row(
+            "SELECT data FROM manifest_games WHERE name = ?1 OR name_lower = ?2 LIMIT 1",
+            [title.trim(), &title.trim().to_lowercase()],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let data = data.ok_or_else(|| format!("No manifest entry for '{}'", title.trim()))?;
+    let entry: ManifestEntry = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    Ok(entry_to_locations(entry))
+}
+
+#[command]
+pub fn manifest_find_by_steam_app_id(
+    app: AppHandle,
+    steam_app_id: u32,
+) -> Result<Option<PcgwSaveLocations>, String> {
+    let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+
+    let data: Option<String> = conn
+        .query_row(
+            "SELECT data FROM manifest_games WHERE steam_app_id = ?1 LIMIT 1",
+            [steam_app_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    match data {
+        Some(data) => {
+            let entry: ManifestEntry = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+            Ok(Some(entry_to_locations(entry)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Shapes a manifest entry like a PCGW lookup so the UI can treat both the same.
+pub(crate) fn entry_to_locations(entry: ManifestEntry) -> PcgwSaveLocations {
+    let ctx = PathContext::current(entry.steam_app_id);
+
+    let mut paths: Vec<PcgwSavePath> = entry
+        .paths
+        .into_iter()
+        .map(|p| {
+            let platform = p.os.clone().unwrap_or_else(|| "other".to_string());
+            let os = match (p.os.as_deref(), p.store.as_deref()) {
+                (Some(os), Some(store)) => format!("{} ({})", os_label(os), store),
+                (Some(os), None) => os_label(os).to_string(),
+                (None, Some(store)) => format!("Any ({})", store),
+                (None, None) => "Any".to_string(),
+            };
+            let expanded = expand_path_tokens(&p.template, &ctx);
+            // Entries for any OS apply wherever they resolve
+            let current_os = match p.os {
+                Some(_) => applies_to_current_os(&platform, expanded.is_some(), &ctx),
+                None => expanded.is_some(),
+            };
+
+            PcgwSavePath {
+                os,
+                platform,
+                raw: p.template,
+                expanded,
+                current_os,
+            }
+        })
+        .collect();
+
+    paths.sort_by_key(|p| std::cmp::Reverse(p.current_os));
+
+    PcgwSaveLocations {
+        title: entry.name,
+        paths,
+        cover_url: None,
+        steam_app_id: entry.steam_app_id,
+        registry_keys: entry.registry_keys,
+    }
+}
+
+fn os_label(os: &str) -> &str {
+    match os {
+        "windows" => "Windows",
+        "linux" => "Linux",
+        "macos" => "OS X",
+        other => other,
+    }
+}
+
+/// Every imported game as a scan candidate.
+pub(crate) fn manifest_candidates(conn: &Connection) -> Result<Vec<ScanCandidate>, String> {
+    let mut stmt = conn
+        .prepare("SELECT data FROM manifest_games")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?;
+
+    let mut candidates = vec![];
+    for data in rows.flatten() {
+        let Ok(entry) = serde_json::from_str::<ManifestEntry>(&data) else {
+            continue;
+        };
+        // Install-dir paths only resolve for Steam games found in a local library
+        let templates: Vec<String> = entry
+            .paths
+            .into_iter()
+            .map(|p| p.template)
+            .filter(|t| entry.steam_app_id.is_some() || !t.contains("{{p|game}}"))
+            .collect();
+        if templates.is_empty() {
+            continue;
+        }
+        candidates.push(ScanCandidate {
+            name: entry.name,
+            templates,
+            platform: None,
+            steam_app_id: entry.steam_app_id,
+            cover_url: None,
+            install_dir: None,
+            wine_prefix: None,
+        });
+    }
+
+    Ok(candidates)
+}
@@ -1,6 +1,14 @@
+pub mod analysis;
 pub mod auth;
+pub mod emulators;
 pub mod games;
+pub mod launch;
+pub mod launchers;
+pub mod maintenance;
+pub mod manifest;
 pub mod pcgw;
+pub mod scan;
 pub mod sync;
 pub mod system;
 pub mod steam;
+pub mod versions;
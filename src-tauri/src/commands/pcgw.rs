@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
+use sync_saves_core::utils::path_template::PathContext;
 
 const PCGW_API: &str = "https://www.pcgamingwiki.com/w/api.php";
 
@@ -12,8 +13,12 @@ pub struct PcgwSearchResult {
 #[derive(Debug, Clone, Serialize)]
 pub struct PcgwSavePath {
     pub os: String,
+    /// Normalized platform: "windows", "linux", "macos", "steam_play" or "other"
+    pub platform: String,
     pub raw: String,
     pub expanded: Option<String>,
+    /// Whether this entry applies to the device we're running on
+    pub current_os: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -21,6 +26,9 @@ pub struct PcgwSaveLocations {
     pub title: String,
     pub paths: Vec<PcgwSavePath>,
     pub cover_url: Option<String>,
+    pub steam_app_id: Option<u32>,
+    /// Registry keys holding save data (not synced, listed for reference)
+    pub registry_keys: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -209,41 +217,59 @@ pub async fn pcgw_get_save_locations(title: String) -> Result<PcgwSaveLocations,
         })
         .unwrap_or_default();
 
+    let steam_app_id = parse_steam_app_id(&wikitext);
+    let paths = parse_save_paths(&wikitext, &PathContext::current(steam_app_id));
+    let registry_keys = parse_registry_keys(&wikitext);
+
+    Ok(PcgwSaveLocations {
+        title: page.title, // Use resolved title
+        paths,
+        cover_url,
+        steam_app_id,
+        registry_keys,
+    })
+}
+
+pub(crate) fn parse_save_paths(wikitext: &str, ctx: &PathContext) -> Vec<PcgwSavePath> {
     let mut paths = vec![];
 
-    for template in extract_templates_named(&wikitext, "Game data/saves") {
+    for template in extract_templates_named(wikitext, "Game data/saves") {
         let params = split_template_params(&template);
         if params.len() < 2 {
             continue;
         }
 
         let os = params[0].trim().to_string();
-        let raw_path = params[1].trim().to_string();
+        let platform = normalize_platform(&os);
 
-        // Minimal: focus on Windows-ish entries (including variations)
-        let os_lc = os.to_lowercase();
-        if !(os_lc.contains("windows") || os_lc == "win") {
-            continue;
-        }
+        // A single entry may list several paths separated by <br>
+        for raw_path in split_path_list(&params[1]) {
+            // Registry keys can't be synced as files
+            if is_registry_key(&raw_path) {
+                continue;
+            }
 
-        if raw_path.is_empty() {
-            continue;
-        }
+            let expanded = expand_path_tokens(&raw_path, ctx);
+            let current_os = applies_to_current_os(platform, expanded.is_some(), ctx);
 
-        let expanded = expand_windows_path_tokens(&raw_path);
-        paths.push(PcgwSavePath {
-            os,
-            raw: raw_path,
-            expanded,
-        });
+            paths.push(PcgwSavePath {
+                os: os.clone(),
+                platform: platform.to_string(),
+                raw: raw_path,
+                expanded,
+                current_os,
+            });
+        }
     }
 
-    // De-dupe by expanded/raw
+    // Entries for this device first, then de-dupe by expanded/raw
     paths.sort_by(|a, b| {
-        a.expanded
-            .as_deref()
-            .unwrap_or(&a.raw)
-            .cmp(b.expanded.as_deref().unwrap_or(&b.raw))
+        b.current_os.cmp(&a.current_os).then_with(|| {
+            a.expanded
+                .as_deref()
+                .unwrap_or(&a.raw)
+                .cmp(b.expanded.as_deref().unwrap_or(&b.raw))
+        })
     });
     paths.dedup_by(|a, b| {
         a.os == b.os
@@ -251,10 +277,102 @@ pub async fn pcgw_get_save_locations(title: String) -> Result<PcgwSaveLocations,
             && a.expanded.as_deref().unwrap_or("") == b.expanded.as_deref().unwrap_or("")
     });
 
-    Ok(PcgwSaveLocations {
-        title: page.title, // Use resolved title
-        paths,
-        cover_url,
+    paths
+}
+
+/// Registry keys listed as save locations, spelled like the Ludusavi manifest's
+/// (`HKEY_CURRENT_USER/Software/...`).
+pub(crate) fn parse_registry_keys(wikitext: &str) -> Vec<String> {
+    let mut keys = vec![];
+    for template in extract_templates_named(wikitext, "Game data/saves") {
+        let params = split_template_params(&template);
+        if params.len() < 2 {
+            continue;
+        }
+        for raw_path in split_path_list(&params[1]) {
+            if !is_registry_key(&raw_path) {
+                continue;
+            }
+            let key = raw_path
+                .replace("{{p|hkcu}}", "HKEY_CURRENT_USER")
+                .replace("{{p|hklm}}", "HKEY_LOCAL_MACHINE")
+                .replace("{{p|wow64}}", "WOW6432Node")
+                .replace('\\', "/");
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    }
+    keys
+}
+
+fn is_registry_key(raw_path: &str) -> bool {
+    let lc = raw_path.to_lowercase();
+    lc.contains("{{p|hkcu}}") || lc.contains("{{p|hklm}}") || lc.contains("{{p|wow64}}")
+}
+
+/// Windows entries also apply on Linux when they resolve into a Proton prefix.
+pub(crate) fn applies_to_current_os(platform: &str, resolved: bool, ctx: &PathContext) -> bool {
+    match platform {
+        "windows" => cfg!(windows) || (resolved && ctx.windows_profile.is_some()),
+        "linux" | "steam_play" => cfg!(target_os = "linux"),
+        "macos" => cfg!(target_os = "macos"),
+        _ => false,
+    }
+}
+
+fn normalize_platform(os: &str) -> &'static str {
+    let os_lc = os.trim().to_lowercase();
+    if os_lc.contains("steam play") || os_lc.contains("proton") {
+        "steam_play"
+    } else if os_lc.contains("windows") || os_lc == "win" || os_lc == "microsoft store" {
+        "windows"
+    } else if os_lc.contains("linux") {
+        "linux"
+    } else if os_lc.contains("os x") || os_lc.contains("macos") || os_lc.contains("mac os") {
+        "macos"
+    } else {
+        "other"
+    }
+}
+
+/// Splits a wikitext path parameter into individual paths, dropping `<ref>` notes.
+fn split_path_list(raw: &str) -> Vec<String> {
+    let mut text = raw.to_string();
+
+    // Strip <ref>...</ref> and self-closing <ref ... />
+    while let Some(start) = text.find("<ref") {
+        let rest = &text[start..];
+        let end = if let Some(close) = rest.find("</ref>") {
+            start + close + "</ref>".len()
+        } else if let Some(close) = rest.find("/>") {
+            start + close + 2
+        } else {
+            text.len()
+        };
+        text.replace_range(start..end, "");
+    }
+
+    text.replace("<br/>", "<br>")
+        .replace("<br />", "<br>")
+        .split("<br>")
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Reads the first `steam appid` from the game's infobox.
+fn parse_steam_app_id(wikitext: &str) -> Option<u32> {
+    wikitext.lines().find_map(|line| {
+        let line = line.trim().trim_start_matches('|').trim();
+        let (key, value) = line.split_once('=')?;
+        if key.trim() != "steam appid" {
+            return None;
+        }
+        value
+            .split(',')
+            .next()
+            .and_then(|v| v.trim().parse::<u32>().ok())
     })
 }
 
@@ -344,45 +462,151 @@ fn split_template_params(template_inner: &str) -> Vec<String> {
     out
 }
 
-fn expand_windows_path_tokens(raw: &str) -> Option<String> {
-    // Expand a small subset of common PCGW tokens.
-    // If no expansion happened, return None.
-    let mut s = raw.to_string();
-
-    let roaming = std::env::var("APPDATA").ok();
-    let local = std::env::var("LOCALAPPDATA").ok();
-    let userprofile = std::env::var("USERPROFILE").ok();
-
-    let mut changed = false;
-
-    let replacements: Vec<(&str, Option<String>)> = vec![
-        ("{{p|appdata}}", roaming.clone()),
-        ("{{p|localappdata}}", local.clone()),
-        ("{{p|userprofile}}", userprofile.clone()),
-        (
-            "{{p|savedgames}}",
-            userprofile.as_ref().map(|u| format!("{}\\Saved Games", u)),
-        ),
-    ];
-
-    for (token, value) in replacements {
-        if let Some(v) = value {
-            if s.contains(token) {
-                s = s.replace(token, &v);
-                changed = true;
-            }
+pub(crate) fn expand_path_tokens(raw: &str, ctx: &PathContext) -> Option<String> {
+    // If a token isn't available on this device, there's no usable expansion.
+    ctx.resolve(raw)
+        .ok()
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    const WIKITEXT: &str = r#"{{Infobox game
+|steam appid  = 620980, 1018800
+}}
+===Save game data location===
+{{Game data|
+{{Game data/saves|Windows|{{p|appdata}}\Example\Saves<br />{{p|userprofile\Documents}}\My Games\Example<ref>{{Refcheck|user=Someone|date=2021-03-02}}</ref>}}
+{{Game data/saves|Microsoft Store|{{p|localappdata}}\Packages\Example_8wekyb3d8bbwe\LocalState<ref name="store"/>}}
+{{Game data/saves|Windows|{{p|hkcu}}\Software\Example\Saves}}
+{{Game data/saves|Windows|{{p|unknowntoken}}\Example}}
+{{Game data/saves|Steam Play (Linux)|{{p|steam}}\steamapps\compatdata\620980\pfx\}}
+{{Game data/saves|Linux|{{p|xdgdatahome}}/Example/}}
+{{Game data/saves|OS X|{{p|osxhome}}/Library/Application Support/Example/}}
+{{Game data/saves|Linux|{{p|xdgdatahome}}/Example/}}
+{{Game data/saves|DOS|{{p|game}}\SAVES}}
+}}"#;
+
+    /// A Linux device running the game through Proton.
+    fn ctx() -> PathContext {
+        let profile = PathBuf::from("/pfx/drive_c/users/steamuser");
+        PathContext {
+            home: Some(PathBuf::from("/home/user")),
+            xdg_data: Some(PathBuf::from("/home/user/.local/share")),
+            steam: Some(PathBuf::from("/home/user/.steam/steam")),
+            windows_appdata: Some(profile.join("AppData/Roaming")),
+            windows_local_appdata: Some(profile.join("AppData/Local")),
+            windows_documents: Some(profile.join("Documents")),
+            windows_profile: Some(profile),
+            ..Default::default()
         }
     }
 
-    // Normalize slashes a bit (PCGW often uses /)
-    if s.contains('/') {
-        s = s.replace('/', "\\");
-        changed = true;
+    fn find<'a>(paths: &'a [PcgwSavePath], raw: &str) -> &'a PcgwSavePath {
+        paths
+            .iter()
+            .find(|p| p.raw.starts_with(raw))
+            .unwrap_or_else(|| panic!("no path starting with {}", raw))
     }
 
-    if changed {
-        Some(s)
-    } else {
-        None
+    fn expanded(path: &PcgwSavePath) -> Option<&Path> {
+        path.expanded.as_deref().map(Path::new)
+    }
+
+    #[test]
+    fn nested_templates_and_refs_are_skipped() {
+        let paths = parse_save_paths(WIKITEXT, &ctx());
+
+        let documents = find(&paths, "{{p|userprofile\\Documents}}");
+        assert_eq!(documents.raw, "{{p|userprofile\\Documents}}\\My Games\\Example");
+        let store = find(&paths, "{{p|localappdata}}");
+        assert_eq!(store.raw, "{{p|localappdata}}\\Packages\\Example_8wekyb3d8bbwe\\LocalState");
+        assert!(paths.iter().all(|p| !p.raw.contains("<ref") && !p.raw.contains("Refcheck")));
+    }
+
+    #[test]
+    fn every_os_row_is_listed_with_its_platform() {
+        let ctx = ctx();
+        let paths = parse_save_paths(WIKITEXT, &ctx);
+
+        let appdata = find(&paths, "{{p|appdata}}");
+        assert_eq!(appdata.os, "Windows");
+        assert_eq!(appdata.platform, "windows");
+        let saves = ctx.windows_appdata.unwrap().join("Example/Saves");
+        assert_eq!(expanded(appdata), Some(saves.as_path()));
+        assert_eq!(find(&paths, "{{p|localappdata}}").platform, "windows");
+        assert_eq!(find(&paths, "{{p|steam}}").platform, "steam_play");
+        assert_eq!(find(&paths, "{{p|osxhome}}").platform, "macos");
+        assert_eq!(find(&paths, "{{p|game}}").platform, "other");
+
+        let linux = find(&paths, "{{p|xdgdatahome}}");
+        assert_eq!(linux.platform, "linux");
+        assert_eq!(expanded(linux), Some(Path::new("/home/user/.local/share/Example")));
+        // The repeated Linux row is listed once
+        assert_eq!(paths.iter().filter(|p| p.raw.starts_with("{{p|xdgdatahome}}")).count(), 1);
+        // Registry keys can't be synced
+        assert!(paths.iter().all(|p| !p.raw.contains("hkcu")));
+        assert_eq!(paths.len(), 8);
+    }
+
+    #[test]
+    fn entries_for_this_device_come_first() {
+        let paths = parse_save_paths(WIKITEXT, &ctx());
+
+        let first_other = paths.iter().position(|p| !p.current_os).unwrap_or(paths.len());
+        assert!(paths[first_other..].iter().all(|p| !p.current_os));
+        if cfg!(target_os = "linux") {
+            // Windows paths resolve into the Proton prefix
+            assert!(find(&paths, "{{p|appdata}}").current_os);
+            assert!(find(&paths, "{{p|xdgdatahome}}").current_os);
+            assert!(!find(&paths, "{{p|osxhome}}").current_os);
+        }
+    }
+
+    #[test]
+    fn unknown_tokens_are_kept_unexpanded() {
+        let paths = parse_save_paths(WIKITEXT, &ctx());
+
+        let unknown = find(&paths, "{{p|unknowntoken}}");
+        assert_eq!(unknown.expanded, None);
+        assert_eq!(unknown.current_os, cfg!(windows));
+        // Known tokens without a value on this device don't expand either
+        let game = find(&paths, "{{p|game}}");
+        assert_eq!(game.expanded, None);
+        assert!(!game.current_os);
+    }
+
+    #[test]
+    fn registry_rows_are_listed_as_keys() {
+        let wikitext = "{{Game data/saves|Windows|{{p|hkcu}}\\Software\\Example\\Saves<br />{{p|hklm}}\\Software\\{{p|wow64}}\\Example}}\n{{Game data/saves|Windows|{{p|hkcu}}\\Software\\Example\\Saves}}";
+        assert_eq!(
+            parse_registry_keys(wikitext),
+            [
+                "HKEY_CURRENT_USER/Software/Example/Saves",
+                "HKEY_LOCAL_MACHINE/Software/WOW6432Node/Example",
+            ]
+        );
+        assert_eq!(parse_registry_keys(WIKITEXT), ["HKEY_CURRENT_USER/Software/Example/Saves"]);
+    }
+
+    #[test]
+    fn windows_rows_need_a_prefix_off_windows() {
+        let without_prefix = PathContext {
+            windows_profile: None,
+            ..ctx()
+        };
+        assert!(applies_to_current_os("windows", true, &ctx()));
+        assert_eq!(applies_to_current_os("windows", true, &without_prefix), cfg!(windows));
+        assert_eq!(applies_to_current_os("windows", false, &ctx()), cfg!(windows));
+        assert!(!applies_to_current_os("other", true, &ctx()));
+    }
+
+    #[test]
+    fn steam_app_id_is_the_first_listed() {
+        assert_eq!(parse_steam_app_id(WIKITEXT), Some(620980));
+        assert_eq!(parse_steam_app_id("|steam appid = \n"), None);
     }
 }
@@ -0,0 +1,230 @@
+use crate::commands::manifest::manifest_candidates;
+use crate::commands::pcgw::pcgw_get_save_locations;
+use crate::commands::steam::installed_candidates;
+use crate::db;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use sync_saves_core::games::{collect_location_stats, LocationSaveStats};
+use sync_saves_core::utils::path_template::PathContext;
+use tauri::{command, AppHandle};
+
+/// Small list of popular games shipped with the app so a scan works offline.
+const KNOWN_GAMES_JSON: &str = include_str!("../../resources/known_games.json");
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ScanCandidate {
+    pub name: String,
+    pub templates: Vec<String>,
+    #[serde(default)]
+    pub platform: Option<String>,
+    #[serde(default)]
+    pub steam_app_id: Option<u32>,
+    #[serde(default)]
+    pub cover_url: Option<String>,
+    /// Install folder for `{{p|game}}` (launcher imports)
+    #[serde(default)]
+    pub install_dir: Option<String>,
+    /// Wine prefix the Windows tokens map into (launcher imports)
+    #[serde(default)]
+    pub wine_prefix: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ScanMatch {
+    pub template: String,
+    pub stats: LocationSaveStats,
+}
+
+/// A game whose saves were found on disk, shaped to be passed to `add_game`.
+#[derive(Serialize, Debug)]
+pub struct ScanSuggestion {
+    pub name: String,
+    /// Most recently modified match; use as `local_path`
+    pub local_path: String,
+    pub platform: String,
+    pub cover_url: Option<String>,
+    pub steam_app_id: Option<u32>,
+    pub matches: Vec<ScanMatch>,
+    pub already_added: bool,
+}
+
+pub(crate) fn bundled_candidates() -> Vec<ScanCandidate> {
+    serde_json::from_str(KNOWN_GAMES_JSON).unwrap_or_default()
+}
+
+#[command]
+pub async fn scan_for_games(
+    app: AppHandle,
+    candidates: Option<Vec<ScanCandidate>>,
+    pcgw_titles: Option<Vec<String>>,
+    include_bundled: Option<bool>,
+    include_manifest: Option<bool>,
+    include_steam: Option<bool>,
+) -> Result<Vec<ScanSuggestion>, String> {
+    let mut all = candidates.unwrap_or_default();
+
+    // The imported Ludusavi manifest covers thousands of games, all offline
+    let manifest = if include_manifest.unwrap_or(false) || include_steam.unwrap_or(false) {
+        let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+        manifest_candidates(&conn)?
+    } else {
+        vec![]
+    };
+
+    // Installed Steam games go first so their paths are attributed to them
+    if include_steam.unwrap_or(false) {
+        all.extend(installed_candidates(&manifest));
+    }
+
+    if include_bundled.unwrap_or(true) {
+        all.extend(bundled_candidates());
+    }
+
+    if include_manifest.unwrap_or(false) {
+        all.extend(manifest);
+    }
+
+    // PCGW lookups are best-effort: a title that fails just contributes nothing
+    for title in pcgw_titles.unwrap_or_default() {
+        match pcgw_get_save_locations(title.clone()).await {
+            Ok(res) => all.push(ScanCandidate {
+                name: res.title,
+                templates: res.paths.into_iter().map(|p| p.raw).collect(),
+                platform: None,
+                steam_app_id: res.steam_app_id,
+                cover_url: res.cover_url,
+                install_dir: None,
+                wine_prefix: None,
+            }),
+            Err(e) => println!("Scan: PCGW lookup for '{}' failed: {}", title, e),
+        }
+    }
+
+    let (known_names, known_paths) = load_existing_games(&app)?;
+
+    // Walking save folders touches the disk, keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || scan_candidates(all, &known_names, &known_paths))
+        .await
+        .map_err(|e| format!("Scan failed: {}", e))
+}
+
+pub(crate) fn load_existing_games(app: &AppHandle) -> Result<(HashSet<String>, HashSet<String>), String> {
+    let conn = db::get_connection(app).map_err(|e| e.to_string())?;
+
+    let mut names = HashSet::new();
+    let mut stmt = conn
+        .prepare("SELECT lower(name) FROM games_cache")
+        .map_err(|e| e.to_string())?;
+    for name in stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .flatten()
+    {
+        names.insert(name);
+    }
+
+    let mut paths = HashSet::new();
+    let mut stmt = conn
+        .prepare("SELECT path FROM game_locations")
+        .map_err(|e| e.to_string())?;
+    for path in stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .flatten()
+    {
+        paths.insert(normalize_for_compare(&path));
+    }
+
+    Ok((names, paths))
+}
+
+pub(crate) fn scan_candidates(
+    candidates: Vec<ScanCandidate>,
+    known_names: &HashSet<String>,
+    known_paths: &HashSet<String>,
+) -> Vec<ScanSuggestion> {
+    let mut suggestions: Vec<ScanSuggestion> = vec![];
+    let mut seen_paths: HashSet<String> = HashSet::new();
+    let base_ctx = PathContext::current(None);
+
+    for candidate in candidates {
+        let mut ctx = base_ctx.for_app(candidate.steam_app_id);
+        if let Some(prefix) = candidate.wine_prefix.as_deref().filter(|_| !cfg!(windows)) {
+            ctx = ctx.with_proton_prefix(Path::new(prefix));
+        }
+        if let Some(dir) = candidate.install_dir.as_deref() {
+            ctx = ctx.with_game_dir(Path::new(dir));
+        }
+        let mut matches = vec![];
+
+        for template in &candidate.templates {
+            let Ok(path) = ctx.resolve(template) else {
+                continue;
+            };
+            let path_str = path.to_string_lossy().to_string();
+            if !seen_paths.insert(normalize_for_compare(&path_str)) {
+                continue;
+            }
+
+            let stats = collect_location_stats("saves", &path_str, None);
+            // Empty folders are often leftovers from uninstalled games
+            if !stats.exists || stats.file_count == 0 {
+                continue;
+            }
+            matches.push(ScanMatch {
+                template: template.clone(),
+                stats,
+            });
+        }
+
+        let Some(best) = matches
+            .iter()
+            .max_by_key(|m| m.stats.newest_mtime_ms.unwrap_or(0))
+        else {
+            continue;
+        };
+
+        let already_added = known_names.contains(&candidate.name.to_lowercase())
+            || matches
+                .iter()
+                .any(|m| known_paths.contains(&normalize_for_compare(&m.stats.path)));
+
+        suggestions.push(ScanSuggestion {
+            local_path: best.stats.path.clone(),
+            name: candidate.name,
+            platform: candidate.platform.unwrap_or_else(|| {
+                if candidate.steam_app_id.is_some() { "steam" } else { "other" }.to_string()
+            }),
+            cover_url: candidate.cover_url,
+            steam_app_id: candidate.steam_app_id,
+            matches,
+            already_added,
+        });
+    }
+
+    suggestions.sort_by(|a, b| a.already_added.cmp(&b.already_added).then_with(|| a.name.cmp(&b.name)));
+    suggestions
+}
+
+fn normalize_for_compare(path: &str) -> String {
+    let p = path.replace('\\', "/").trim_end_matches('/').to_string();
+    if cfg!(windows) {
+        p.to_lowercase()
+    } else {
+        p
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_list_parses() {
+        let candidates: Vec<ScanCandidate> = serde_json::from_str(KNOWN_GAMES_JSON).unwrap();
+        assert!(!candidates.is_empty());
+        assert!(candidates.iter().all(|c| !c.name.is_empty() && !c.templates.is_empty()));
+        assert_eq!(bundled_candidates().len(), candidates.len());
+    }
+}
@@ -1,8 +1,18 @@
+use crate::commands::scan::ScanCandidate;
+use crate::db;
+use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::path::PathBuf;
+use sync_saves_core::services::steam_library::{self, SteamInstalledApp};
+use sync_saves_core::services::steam_metadata::{self, SteamAppDetails};
+use sync_saves_core::utils::path_template::find_steam_root;
+use tauri::{AppHandle, Manager};
 
 const STEAM_STORE_SEARCH_API: &str = "https://store.steampowered.com/api/storesearch/";
 
+/// Cached store details are refreshed after this many days.
+const METADATA_TTL_DAYS: i64 = 7;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct SteamSearchResult {
     pub id: u32,
@@ -41,11 +51,7 @@ pub async fn steam_search_games(query: String) -> Result<Vec<SteamSearchResult>,
         return Ok(vec![]);
     }
 
-    let client = reqwest::Client::builder()
-        .user_agent("sync-saves/0.1 (Steam lookup)")
-        .timeout(Duration::from_secs(10))
-        .build()
-        .map_err(|e| format!("Failed to build HTTP client: {e}"))?;
+    let client = steam_metadata::http_client()?;
 
     let resp = client
         .get(STEAM_STORE_SEARCH_API)
@@ -68,7 +74,7 @@ pub async fn steam_search_games(query: String) -> Result<Vec<SteamSearchResult>,
         .map_err(|e| format!("Failed to parse Steam response: {e}"))?;
 
     let results: Vec<SteamSearchResult> = json.items.into_iter().map(|item| {
-        let cover_url = Some(format!("https://shared.fastly.steamstatic.com/store_item_assets/steam/apps/{}/library_600x900.jpg", item.id));
+        let cover_url = Some(steam_cover_url(item.id));
 
         SteamSearchResult {
             id: item.id,
@@ -81,3 +87,255 @@ pub async fn steam_search_games(query: String) -> Result<Vec<SteamSearchResult>,
     println!("Steam search found {} items", results.len());
     Ok(results)
 }
+
+/// Best-guess portrait cover URL, used where we can't afford a request per game.
+/// `cache_game_cover` verifies it and falls back to other image variants.
+pub(crate) fn steam_cover_url(app_id: u32) -> String {
+    format!("https://shared.fastly.steamstatic.com/store_item_assets/steam/apps/{}/library_600x900.jpg", app_id)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SteamLibraryInfo {
+    pub steam_root: String,
+    pub libraries: Vec<String>,
+    pub user_ids: Vec<String>,
+    pub apps: Vec<SteamInstalledApp>,
+}
+
+/// Lists games installed through the local Steam client (no network needed).
+#[tauri::command]
+pub async fn steam_list_installed_games() -> Result<SteamLibraryInfo, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let root = find_steam_root().ok_or("Steam installation not found")?;
+        let libraries = steam_library::library_folders(&root);
+        let apps = steam_library::installed_apps(&root);
+        println!("Steam library: {} apps in {} libraries", apps.len(), libraries.len());
+
+        Ok(SteamLibraryInfo {
+            steam_root: root.to_string_lossy().to_string(),
+            libraries: libraries.iter().map(|l| l.to_string_lossy().to_string()).collect(),
+            user_ids: steam_library::user_ids(&root),
+            apps,
+        })
+    })
+    .await
+    .map_err(|e| format!("Steam library scan failed: {}", e))?
+}
+
+/// Installed Steam apps as scan candidates. Steam Cloud folders are used as
+/// save paths; `manifest` adds known locations for the same app ids.
+pub(crate) fn installed_candidates(manifest: &[ScanCandidate]) -> Vec<ScanCandidate> {
+    let Some(root) = find_steam_root() else {
+        return vec![];
+    };
+
+    steam_library::installed_apps(&root)
+        .into_iter()
+        .map(|app| {
+            let mut templates: Vec<String> = manifest
+                .iter()
+                .filter(|c| c.steam_app_id == Some(app.app_id))
+                .flat_map(|c| c.templates.iter().cloned())
+                .collect();
+            templates.extend(
+                app.cloud_save_paths
+                    .iter()
+                    .map(|p| p.to_string_lossy().to_string()),
+            );
+
+            ScanCandidate {
+                name: app.name,
+                templates,
+                platform: Some("steam".to_string()),
+                steam_app_id: Some(app.app_id),
+                cover_url: Some(steam_cover_url(app.app_id)),
+                install_dir: Some(app.install_dir.to_string_lossy().to_string()),
+                wine_prefix: None,
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SteamAppMetadata {
+    pub app_id: u32,
+    pub details: Option<SteamAppDetails>,
+    /// Remote URL the cached cover was downloaded from
+    pub cover_url: Option<String>,
+    pub cover_path: Option<String>,
+    pub fetched_at: String,
+    /// True when a refresh failed and cached data was returned instead
+    pub stale: bool,
+}
+
+/// Store details and a locally cached cover for a Steam app.
+#[tauri::command]
+pub async fn steam_get_app_metadata(
+    app: AppHandle,
+    app_id: u32,
+    refresh: Option<bool>,
+) -> Result<SteamAppMetadata, String> {
+    ensure_app_metadata(&app, app_id, refresh.unwrap_or(false)).await
+}
+
+/// Makes sure a game's cover is cached on disk and returns it as a `data:` URL.
+/// Games linked to a Steam app get their `cover_url` replaced by the image
+/// variant that actually exists.
+#[tauri::command]
+pub async fn cache_game_cover(app: AppHandle, game_id: String) -> Result<Option<String>, String> {
+    let (steam_app_id, cover_url, cover_cache_path) = {
+        let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT steam_app_id, cover_url, cover_cache_path FROM games_cache WHERE id = ?1",
+            [&game_id],
+            |row| {
+                Ok((
+                    row.get::<_, Option<u32>>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            },
+        )
+        .map_err(|e| e.to_string())?
+    };
+
+    // Already cached: no network needed
+    if let Some(path) = cover_cache_path.as_deref().map(PathBuf::from) {
+        if path.exists() {
+            return Ok(steam_metadata::cover_data_url(&path));
+        }
+    }
+
+    let (path, source_url) = if let Some(app_id) = steam_app_id {
+        let meta = ensure_app_metadata(&app, app_id, false).await?;
+        match meta.cover_path {
+            Some(path) => (PathBuf::from(path), meta.cover_url),
+            None => return Ok(None),
+        }
+    } else if let Some(url) = cover_url.filter(|u| u.starts_with("http")) {
+        let client = steam_metadata::http_client()?;
+        let dest = steam_metadata::cover_cache_file(&cache_dir(&app)?, &game_id);
+        let used = steam_metadata::download_first_image(&client, &[url], &dest).await?;
+        (dest, Some(used))
+    } else {
+        return Ok(None);
+    };
+
+    let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE games_cache SET cover_cache_path = ?1, cover_url = COALESCE(?2, cover_url) WHERE id = ?3",
+        rusqlite::params![path.to_string_lossy().to_string(), source_url, game_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(steam_metadata::cover_data_url(&path))
+}
+
+pub(crate) async fn ensure_app_metadata(
+    app: &AppHandle,
+    app_id: u32,
+    refresh: bool,
+) -> Result<SteamAppMetadata, String> {
+    let cached = load_cached_metadata(app, app_id)?;
+
+    if let Some(cached) = &cached {
+        let fresh = chrono::DateTime::parse_from_rfc3339(&cached.fetched_at)
+            .map(|t| chrono::Utc::now().signed_duration_since(t) < chrono::Duration::days(METADATA_TTL_DAYS))
+            .unwrap_or(false);
+        let has_cover = cached
+            .cover_path
+            .as_deref()
+            .map(|p| PathBuf::from(p).exists())
+            .unwrap_or(false);
+        if fresh && has_cover && !refresh {
+            return Ok(cached.clone());
+        }
+    }
+
+    let client = steam_metadata::http_client()?;
+    let details = match steam_metadata::fetch_app_details(&client, app_id).await {
+        Ok(details) => details,
+        Err(e) => {
+            println!("Steam metadata: appdetails for {} failed: {}", app_id, e);
+            // Offline: whatever we have is better than nothing
+            return match cached {
+                Some(mut cached) => {
+                    cached.stale = true;
+                    Ok(cached)
+                }
+                None => Err(e),
+            };
+        }
+    };
+
+    let dest = steam_metadata::cover_cache_file(&cache_dir(app)?, &app_id.to_string());
+    let candidates = steam_metadata::cover_url_candidates(app_id, details.as_ref());
+    let (cover_path, cover_url) = match steam_metadata::download_first_image(&client, &candidates, &dest).await {
+        Ok(url) => (Some(dest.to_string_lossy().to_string()), Some(url)),
+        Err(e) => {
+            println!("Steam metadata: no cover for {}: {}", app_id, e);
+            let cached = cached.as_ref();
+            (
+                cached.and_then(|c| c.cover_path.clone()),
+                cached.and_then(|c| c.cover_url.clone()),
+            )
+        }
+    };
+
+    let meta = SteamAppMetadata {
+        app_id,
+        details,
+        cover_url,
+        cover_path,
+        fetched_at: chrono::Utc::now().to_rfc3339(),
+        stale: false,
+    };
+
+    let data = meta
+        .details
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let conn = db::get_connection(app).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO steam_app_cache (app_id, data, cover_path, cover_source_url, fetched_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![app_id, data, meta.cover_path, meta.cover_url, meta.fetched_at],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(meta)
+}
+
+fn load_cached_metadata(app: &AppHandle, app_id: u32) -> Result<Option<SteamAppMetadata>, String> {
+    let conn = db::get_connection(app).map_err(|e| e.to_string())?;
+    let row = conn
+        .query_row(
+            "SELECT data, cover_path, cover_source_url, fetched_at FROM steam_app_cache WHERE app_id = ?1",
+            [app_id],
+            |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    Ok(row.map(|(data, cover_path, cover_url, fetched_at)| SteamAppMetadata {
+        app_id,
+        details: data.and_then(|d| serde_json::from_str(&d).ok()),
+        cover_url,
+        cover_path,
+        fetched_at,
+        stale: false,
+    }))
+}
+
+fn cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path().app_cache_dir().map_err(|e| e.to_string())
+}
@@ -1,7 +1,8 @@
-use crate::db;
+use crate::{db, tray};
+use sync_saves_core::device;
+use sync_saves_core::settings::{self, AppSettings};
 use sysinfo::System;
 use tauri::{command, AppHandle};
-use uuid::Uuid;
 // FIX 1: Use ManagerExt instead of AutostartExt
 use tauri_plugin_autostart::ManagerExt;
 
@@ -34,27 +35,7 @@ pub fn get_system_info(app: AppHandle) -> SystemInfo {
 /// Gets the existing device ID or creates a new one if it doesn't exist
 pub(crate) fn get_or_create_device_id(app: &AppHandle) -> Result<String, String> {
     let conn = db::get_connection(app).map_err(|e| e.to_string())?;
-
-    // Try to get existing device ID
-    let mut stmt = conn
-        .prepare("SELECT value FROM device_config WHERE key = 'device_id'")
-        .map_err(|e| e.to_string())?;
-
-    let existing_id: Result<String, _> = stmt.query_row([], |row| row.get(0));
-
-    match existing_id {
-        Ok(id) => Ok(id),
-        Err(_) => {
-            // Generate a new UUID
-            let new_id = Uuid::new_v4().to_string();
-            conn.execute(
-                "INSERT INTO device_config (key, value) VALUES ('device_id', ?1)",
-                [&new_id],
-            )
-            .map_err(|e| e.to_string())?;
-            Ok(new_id)
-        }
-    }
+    device::device_id(&conn)
 }
 
 #[command]
@@ -91,81 +72,17 @@ pub fn set_device_name(app: AppHandle, name: String) -> Result<bool, String> {
     Ok(true)
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
-pub struct AppSettings {
-    pub launch_on_startup: bool,
-    pub desktop_notifications: bool,
-    pub auto_sync_enabled: bool,
-}
-
-impl Default for AppSettings {
-    fn default() -> Self {
-        AppSettings {
-            launch_on_startup: true,
-            desktop_notifications: false,
-            auto_sync_enabled: true,
-        }
-    }
-}
-
 #[command]
 pub fn get_app_settings(app: AppHandle) -> Result<AppSettings, String> {
     let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
-
-    let mut settings = AppSettings::default();
-
-    // Read each setting from the database
-    if let Ok(mut stmt) =
-        conn.prepare("SELECT key, value FROM device_config WHERE key LIKE 'setting_%'")
-    {
-        let rows = stmt
-            .query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-            })
-            .map_err(|e| e.to_string())?;
-
-        for row in rows {
-            if let Ok((key, value)) = row {
-                match key.as_str() {
-                    "setting_launch_startup" => {
-                        settings.launch_on_startup = value == "true";
-                    }
-                    "setting_notifications" => {
-                        settings.desktop_notifications = value == "true";
-                    }
-                    "setting_auto_sync" => {
-                        settings.auto_sync_enabled = value == "true";
-                    }
-                    _ => {}
-                }
-            }
-        }
-    }
-
-    Ok(settings)
+    settings::load(&conn)
 }
 
 #[command]
 pub fn save_app_settings(app: AppHandle, settings: AppSettings) -> Result<bool, String> {
     let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
-
-    conn.execute(
-        "INSERT OR REPLACE INTO device_config (key, value) VALUES ('setting_launch_startup', ?1)",
-        [settings.launch_on_startup.to_string()],
-    )
-    .map_err(|e| e.to_string())?;
-
-    conn.execute(
-        "INSERT OR REPLACE INTO device_config (key, value) VALUES ('setting_notifications', ?1)",
-        [settings.desktop_notifications.to_string()],
-    )
-    .map_err(|e| e.to_string())?;
-
-    conn.execute(
-        "INSERT OR REPLACE INTO device_config (key, value) VALUES ('setting_auto_sync', ?1)",
-        [settings.auto_sync_enabled.to_string()],
-    )
-    .map_err(|e| e.to_string())?;
+    settings::save(&conn, &settings)?;
+    tray::refresh(&app);
 
     // Handle autostart
     // FIX 2: Use .autolaunch() instead of .autostart()
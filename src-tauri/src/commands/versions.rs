@@ -0,0 +1,96 @@
+use crate::db;
+use crate::{notifications, tray};
+use base64::{engine::general_purpose, Engine as _};
+use sync_saves_core::services::retention::RetentionPolicy;
+use sync_saves_core::notifications::SyncEvent;
+use sync_saves_core::sync::AuthConfig;
+use sync_saves_core::versions::{self, LabeledVersion, PruneReport, RestoredVersion, VersionDiff, VersionMeta};
+use tauri::{command, AppHandle};
+
+/// Compares two downloaded versions of a game's save (base64 zips, older one
+/// first): files added, removed or modified with size deltas, per-key JSON and
+/// line diffs for text saves, and what changed in their analysis data.
+#[command]
+pub async fn diff_versions(
+    app: AppHandle,
+    game_id: String,
+    version_a: String,
+    version_b: String,
+    archive_a: String,
+    archive_b: String,
+) -> Result<VersionDiff, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+        let bytes_a = general_purpose::STANDARD.decode(archive_a).map_err(|e| e.to_string())?;
+        let bytes_b = general_purpose::STANDARD.decode(archive_b).map_err(|e| e.to_string())?;
+        versions::diff(&conn, &game_id, version_a, version_b, &bytes_a, &bytes_b)
+    })
+    .await
+    .map_err(|e| format!("Diff failed: {}", e))?
+}
+
+#[command]
+pub fn get_retention_policy(app: AppHandle, game_id: String) -> Result<Option<RetentionPolicy>, String> {
+    let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+    versions::load_retention_policy(&conn, &game_id)
+}
+
+/// Sets the game's retention policy; `None` goes back to the default.
+#[command]
+pub fn set_retention_policy(app: AppHandle, game_id: String, policy: Option<RetentionPolicy>) -> Result<(), String> {
+    let conn = db::get_connection(&app).map_err(|e| e.to_string())?;
+    versions::store_retention_policy(&conn, &game_id, policy.as_ref())
+}
+
+/// Deletes the game's cloud versions (storage objects and rows) that its
+/// retention policy doesn't keep. `policy` overrides the stored one; with
+/// `dry_run` nothing is deleted and the report lists what would be.
+#[command]
+pub async fn prune_versions(
+    app: AppHandle,
+    game_id: String,
+    auth: AuthConfig,
+    policy: Option<RetentionPolicy>,
+    dry_run: bool,
+) -> Result<PruneReport, String> {
+    versions::prune(&db::get_db_path(&app), &game_id, &auth, policy, dry_run).await
+}
+
+/// The game's labeled, annotated or pinned versions, newest first. With `auth`
+/// the local cache is refreshed from the cloud first; offline it is used as is.
+#[command]
+pub async fn list_labeled_versions(
+    app: AppHandle,
+    game_id: String,
+    auth: Option<AuthConfig>,
+) -> Result<Vec<LabeledVersion>, String> {
+    versions::list_labeled(&db::get_db_path(&app), &game_id, auth.as_ref()).await
+}
+
+/// Sets a version's label, notes and pin in the cloud and the local cache.
+/// Labels are unique per game, ignoring case.
+#[command]
+pub async fn set_version_metadata(
+    app: AppHandle,
+    game_id: String,
+    version_id: String,
+    auth: AuthConfig,
+    metadata: VersionMeta,
+) -> Result<VersionMeta, String> {
+    versions::set_metadata(&db::get_db_path(&app), &game_id, &version_id, &auth, metadata).await
+}
+
+/// Downloads a cloud version, given by id or label, and restores it over the game's saves.
+#[command]
+pub async fn restore_version(
+    app: AppHandle,
+    game_id: String,
+    auth: AuthConfig,
+    version: String,
+) -> Result<RestoredVersion, String> {
+    let restored = versions::restore_cloud_version(&db::get_db_path(&app), &game_id, &auth, Some(&version)).await?;
+    let label = restored.label.clone();
+    notifications::sync_event(&app, &game_id, SyncEvent::Restored { label });
+    tray::refresh(&app);
+    Ok(restored)
+}
@@ -0,0 +1,26 @@
+use rusqlite::{Connection, Result};
+use std::path::PathBuf;
+use sync_saves_core::db;
+use sync_saves_core::paths::AppPaths;
+use tauri::{AppHandle, Manager};
+
+/// The folders Tauri resolved for this app.
+pub fn app_paths(app: &AppHandle) -> Result<AppPaths, String> {
+    let path = app.path();
+    Ok(AppPaths::new(
+        path.app_data_dir().map_err(|e| e.to_string())?,
+        path.app_cache_dir().map_err(|e| e.to_string())?,
+    ))
+}
+
+pub fn init_db(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    db::init(&get_db_path(app))
+}
+
+pub fn get_db_path(app: &AppHandle) -> PathBuf {
+    app_paths(app).expect("failed to get app data dir").db_path()
+}
+
+pub fn get_connection(app: &AppHandle) -> Result<Connection> {
+    db::open(&get_db_path(app))
+}
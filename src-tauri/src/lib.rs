@@ -2,11 +2,27 @@ use tauri::{Emitter, Manager};
 
 mod commands;
 mod db;
-mod services;
-mod utils;
+mod notifications;
+mod queue;
+mod tray;
+mod wrapper;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Steam launch option mode, see wrapper.rs
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let wrapped = match wrapper::parse(&args) {
+        Some(Ok(wrapper::RunArgs::Wrap { game_id, command })) => {
+            std::process::exit(wrapper::wrap(&game_id, &command))
+        }
+        Some(Ok(wrapper::RunArgs::Step(step))) => Some(step),
+        Some(Err(e)) => {
+            eprintln!("{}\n{}", e, wrapper::USAGE);
+            std::process::exit(2);
+        }
+        None => None,
+    };
+
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_os::init())
@@ -18,10 +34,24 @@ pub fn run() {
             // Debug: Emit all args to frontend to help troubleshoot
             let _ = app.emit("deep-link://debug", args.clone());
 
-            let _ = app
-                .get_webview_window("main")
-                .expect("no main window")
-                .set_focus();
+            // A step of a wrapped game launch; the game takes focus, not the window
+            match wrapper::parse(args.get(1..).unwrap_or_default()) {
+                Some(Ok(wrapper::RunArgs::Step(step))) => {
+                    wrapper::start(app.clone(), step, false);
+                    return;
+                }
+                Some(Ok(wrapper::RunArgs::Wrap { .. })) => {
+                    eprintln!("Ignoring run request without a step");
+                    return;
+                }
+                Some(Err(e)) => {
+                    eprintln!("Ignoring run request: {}", e);
+                    return;
+                }
+                None => {}
+            }
+
+            tray::show_window(app);
 
             // Iterate over all args to find the deep link
             for arg in args.iter() {
@@ -29,6 +59,10 @@ pub fn run() {
                 if arg.contains("sync-saves://") {
                     // Clean up quotes if present
                     let clean_url = arg.replace(&['\"', '\''][..], "").trim().to_string();
+                    if let Some(game_id) = commands::launch::launch_link_game_id(&clean_url) {
+                        commands::launch::launch_from_link(app.clone(), game_id);
+                        continue;
+                    }
                     let _ = app.emit("deep-link://new-url", clean_url);
                 }
             }
@@ -37,12 +71,47 @@ pub fn run() {
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             Some(vec!["--silent"]),
         ))
-        .setup(|app| {
+        .setup(move |app| {
             // Initialize Database
             db::init_db(app.handle())?;
+            app.manage(notifications::Notifications::default());
+            tray::init(app.handle())?;
+
+            // Autostart launches live in the tray until opened
+            if std::env::args().any(|arg| arg == "--silent") {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.hide();
+                }
+            }
+
+            // Started for a step of a Steam launch with no app running: stay out of sight
+            if let Some(step) = wrapped {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.hide();
+                }
+                wrapper::start(app.handle().clone(), step, true);
+            }
 
             // Start File Watcher
-            services::watcher::start_watcher(app.handle().clone());
+            let handle = app.handle().clone();
+            sync_saves_core::services::watcher::start_watcher(db::get_db_path(&handle), move |game_id| {
+                if let Some(reason) = tray::pause_reason(&handle, Some(game_id)) {
+                    println!("{}, skipping {}", reason, game_id);
+                    return;
+                }
+                let _ = handle.emit("sync-required", game_id);
+            });
+
+            // Send syncs queued while offline once the backend is back
+            queue::start(app.handle().clone());
+
+            // Clear leftovers from failed syncs and deleted games
+            let paths = db::app_paths(app.handle())?;
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = sync_saves_core::maintenance::cleanup_local(&paths) {
+                    eprintln!("Startup cleanup failed: {}", e);
+                }
+            });
 
             // Register deep link listener to emit to frontend
             // Note: tauri-plugin-deep-link > 2.0 automatically emits "deep-link://new-url"
@@ -67,8 +136,38 @@ pub fn run() {
                 }
             }
 
+            // A launch link that started the app; later ones arrive through single-instance
+            for arg in std::env::args().skip(1) {
+                if let Some(game_id) = commands::launch::launch_link_game_id(&arg) {
+                    commands::launch::launch_from_link(app.handle().clone(), game_id);
+                }
+            }
+
+            // macOS delivers links as events instead of arguments
+            #[cfg(target_os = "macos")]
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                let handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    for url in event.urls() {
+                        if let Some(game_id) = commands::launch::launch_link_game_id(url.as_str()) {
+                            commands::launch::launch_from_link(handle.clone(), game_id);
+                        }
+                    }
+                });
+            }
+
             Ok(())
         })
+        // Closing the window keeps the app, and its watcher, running in the tray
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                if window.label() == "main" {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             commands::system::get_system_info,
             commands::system::get_device_id,
@@ -80,19 +179,56 @@ pub fn run() {
             commands::system::open_folder,
             commands::auth::set_current_user,
             commands::auth::get_current_user,
+            commands::auth::share_cloud_session,
             crate::commands::games::get_all_games,
             crate::commands::games::add_game,
             crate::commands::games::delete_game,
             crate::commands::games::update_game,
             crate::commands::games::get_game_save_stats,
+            crate::commands::games::get_game_locations,
+            crate::commands::games::add_game_location,
+            crate::commands::games::remove_game_location,
+            crate::commands::games::resolve_path_templates,
             crate::commands::games::get_version_analysis,
             crate::commands::games::save_version_analysis,
             crate::commands::games::delete_version_analyses,
+            crate::commands::launch::prepare_launch,
             crate::commands::pcgw::pcgw_search_games,
             crate::commands::pcgw::pcgw_get_save_locations,
+            crate::commands::scan::scan_for_games,
+            crate::commands::launchers::list_launcher_games,
+            crate::commands::launchers::scan_launcher_games,
+            crate::commands::emulators::get_emulator_profiles,
+            crate::commands::emulators::list_emulator_save_files,
+            crate::commands::emulators::add_emulator_game,
+            crate::commands::manifest::import_ludusavi_manifest,
+            crate::commands::manifest::manifest_status,
+            crate::commands::manifest::manifest_search_games,
+            crate::commands::manifest::manifest_get_save_locations,
+            crate::commands::manifest::manifest_find_by_steam_app_id,
             crate::commands::sync::sync_game,
+            crate::commands::sync::get_pending_sync_count,
+            crate::commands::sync::flush_sync_queue,
+            crate::commands::sync::get_failed_sync_count,
+            crate::commands::sync::retry_failed_syncs,
+            crate::commands::sync::discard_failed_syncs,
             crate::commands::sync::restore_game,
+            crate::commands::analysis::analyze_version,
+            crate::commands::analysis::analyze_local_save,
+            crate::commands::analysis::list_save_decoders,
+            crate::commands::analysis::decode_save_file,
+            crate::commands::versions::diff_versions,
+            crate::commands::versions::get_retention_policy,
+            crate::commands::versions::set_retention_policy,
+            crate::commands::versions::prune_versions,
+            crate::commands::versions::list_labeled_versions,
+            crate::commands::versions::set_version_metadata,
+            crate::commands::versions::restore_version,
+            crate::commands::maintenance::collect_garbage,
             crate::commands::steam::steam_search_games,
+            crate::commands::steam::steam_list_installed_games,
+            crate::commands::steam::steam_get_app_metadata,
+            crate::commands::steam::cache_game_cover,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,61 @@
+//! Desktop notifications for sync outcomes, so background syncs are visible
+//! with the window hidden. Shown only with `desktop_notifications` on.
+
+use crate::commands::system::get_app_settings;
+use crate::db;
+use std::sync::Mutex;
+use std::time::Instant;
+use sync_saves_core::games;
+use sync_saves_core::notifications::{SyncEvent, Throttle};
+use sync_saves_core::settings;
+use sync_saves_core::sync::SyncResult;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+#[derive(Default)]
+pub(crate) struct Notifications(Mutex<Throttle>);
+
+fn enabled(app: &AppHandle) -> bool {
+    get_app_settings(app.clone()).is_ok_and(|s| s.desktop_notifications)
+}
+
+/// Shows a notification right away, bypassing the per-game throttle.
+pub(crate) fn show(app: &AppHandle, title: &str, body: &str) {
+    if !enabled(app) {
+        return;
+    }
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        eprintln!("Failed to show notification: {}", e);
+    }
+}
+
+/// Reports a sync outcome of a game; the throttle decides whether it shows.
+pub(crate) fn sync_event(app: &AppHandle, game_id: &str, event: SyncEvent) {
+    let Some(state) = app.try_state::<Notifications>() else {
+        return;
+    };
+    let name = db::get_connection(app)
+        .ok()
+        .and_then(|conn| games::get_local_game(&conn, game_id).ok())
+        .map_or_else(|| game_id.to_string(), |game| game.name);
+
+    // Throttled even when disabled, so turning them on doesn't replay a burst
+    let notice = match state.0.lock() {
+        Ok(mut throttle) => throttle.notice(game_id, &name, &event, Instant::now()),
+        Err(_) => return,
+    };
+    if let Some(notice) = notice {
+        show(app, &notice.title, &notice.body);
+    }
+}
+
+/// Reports the outcome of an upload; skipped syncs aren't worth a notice.
+pub(crate) fn sync_result(app: &AppHandle, game_id: &str, result: &Result<SyncResult, String>) {
+    match result {
+        Ok(result) if result.skipped || result.queued => {}
+        Ok(_) => sync_event(app, game_id, SyncEvent::Uploaded),
+        // Held back on purpose, not a failure
+        Err(e) if e.starts_with(settings::PAUSED) => {}
+        Err(e) => sync_event(app, game_id, SyncEvent::Failed(e.clone())),
+    }
+}
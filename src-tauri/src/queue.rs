@@ -0,0 +1,89 @@
+//! Sends the syncs queued while offline once the backend answers again.
+
+use crate::commands::auth::app_auth;
+use crate::{db, notifications, tray};
+use rusqlite::Connection;
+use std::time::Duration;
+use sync_saves_core::queue;
+use tauri::{AppHandle, Emitter};
+
+/// How often the backend is probed while syncs are waiting
+const CHECK_EVERY: Duration = Duration::from_secs(30);
+
+fn count(app: &AppHandle, f: fn(&Connection, Option<&str>) -> Result<u32, String>) -> u32 {
+    db::get_connection(app)
+        .ok()
+        .and_then(|conn| f(&conn, None).ok())
+        .unwrap_or(0)
+}
+
+pub(crate) fn pending_count(app: &AppHandle) -> u32 {
+    count(app, queue::pending_count)
+}
+
+/// Tells the UI how many syncs are waiting and how many were set aside.
+pub(crate) fn changed(app: &AppHandle) {
+    let _ = app.emit(
+        "sync-queue-changed",
+        serde_json::json!({
+            "pending": pending_count(app),
+            "failed": count(app, queue::failed_count),
+        }),
+    );
+}
+
+/// Uploads the queue with the app's session, if there is one and the backend answers.
+pub(crate) async fn flush(app: &AppHandle) -> Result<u32, String> {
+    if pending_count(app) == 0 {
+        return Ok(0);
+    }
+    let Some(auth) = app_auth(app)? else {
+        return Ok(pending_count(app));
+    };
+
+    let report = queue::flush(&db::get_db_path(app), &auth, None).await?;
+    for queued in &report.results {
+        tray::record(app, &queued.game_id, &queued.result);
+        notifications::sync_result(app, &queued.game_id, &queued.result);
+        let _ = app.emit(
+            "sync-finished",
+            serde_json::json!({
+                "game_id": queued.game_id,
+                "result": queued.result.as_ref().ok(),
+                "error": queued.result.as_ref().err(),
+            }),
+        );
+    }
+    if report.given_up > 0 {
+        notifications::show(
+            app,
+            "Uploads Failed",
+            &format!(
+                "{} queued upload(s) kept failing and were set aside. Open Sync Saves to retry or discard them.",
+                report.given_up
+            ),
+        );
+    }
+    if !report.results.is_empty() {
+        tray::refresh(app);
+        changed(app);
+    }
+    Ok(report.remaining)
+}
+
+/// Checks for a way back online every [`CHECK_EVERY`] while syncs are waiting.
+pub(crate) fn start(app: AppHandle) {
+    // Nothing is being sent yet; claims left by a previous run are stale
+    let released = db::get_connection(&app)
+        .map_err(|e| e.to_string())
+        .and_then(|conn| queue::release_claims(&conn));
+    if let Err(e) = released {
+        eprintln!("Releasing queued syncs failed: {}", e);
+    }
+    std::thread::spawn(move || loop {
+        std::thread::sleep(CHECK_EVERY);
+        if let Err(e) = tauri::async_runtime::block_on(flush(&app)) {
+            eprintln!("Flushing the sync queue failed: {}", e);
+        }
+    });
+}
@@ -1,3 +0,0 @@
-pub mod compression;
-pub mod extraction;
-pub mod watcher;
@@ -0,0 +1,312 @@
+//! Tray icon listing the games with their sync status and quick actions. It is
+//! rebuilt whenever a sync starts or finishes, so it stays current while the
+//! window is hidden.
+
+use crate::commands::auth::app_auth;
+use crate::commands::system::open_folder;
+use crate::{db, notifications, queue};
+use chrono::{DateTime, Duration, Local, Utc};
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use sync_saves_core::games::{self, LocalGame};
+use sync_saves_core::queue as sync_queue;
+use sync_saves_core::settings::{self, AppSettings};
+use sync_saves_core::sync::{self, SyncResult};
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager};
+
+const TRAY_ID: &str = "main";
+const PAUSE_FOR: Duration = Duration::hours(1);
+
+#[derive(Default)]
+struct State {
+    syncing: HashSet<String>,
+    /// Games whose last sync failed, with the error
+    failed: HashMap<String, String>,
+}
+
+#[derive(Default)]
+pub(crate) struct TrayState(Mutex<State>);
+
+fn with_state<T>(app: &AppHandle, f: impl FnOnce(&mut State) -> T) -> Option<T> {
+    let state = app.try_state::<TrayState>()?;
+    let mut state = state.0.lock().ok()?;
+    Some(f(&mut state))
+}
+
+/// Why syncs of `game_id` (or of every game) are held back right now.
+pub(crate) fn pause_reason(app: &AppHandle, game_id: Option<&str>) -> Option<String> {
+    let conn = db::get_connection(app).ok()?;
+    settings::pause_reason(&conn, game_id).ok().flatten()
+}
+
+fn update_settings(app: &AppHandle, f: impl FnOnce(&mut AppSettings)) {
+    let result = db::get_connection(app).map_err(|e| e.to_string()).and_then(|conn| {
+        let mut settings = settings::load(&conn)?;
+        f(&mut settings);
+        settings::save(&conn, &settings)
+    });
+    if let Err(e) = result {
+        eprintln!("Failed to update settings: {}", e);
+    }
+    let _ = app.emit("settings-changed", ());
+    refresh(app);
+}
+
+fn set_game_paused(app: &AppHandle, game_id: &str, paused: bool) {
+    let updates = games::UpdateGameParams {
+        sync_paused: Some(paused),
+        ..Default::default()
+    };
+    let result = db::get_connection(app)
+        .map_err(|e| e.to_string())
+        .and_then(|conn| games::update_game(&conn, game_id, updates));
+    if let Err(e) = result {
+        eprintln!("Failed to pause {}: {}", game_id, e);
+    }
+    let _ = app.emit("sync-finished", serde_json::json!({ "game_id": game_id }));
+    refresh(app);
+}
+
+/// Marks the game as syncing while `sync` runs and records how it went.
+pub(crate) async fn track<F>(app: &AppHandle, game_id: &str, sync: F) -> Result<SyncResult, String>
+where
+    F: std::future::Future<Output = Result<SyncResult, String>>,
+{
+    with_state(app, |s| s.syncing.insert(game_id.to_string()));
+    refresh(app);
+
+    let result = sync.await;
+
+    with_state(app, |s| s.syncing.remove(game_id));
+    record(app, game_id, &result);
+    refresh(app);
+    result
+}
+
+/// Remembers whether the game's last sync failed.
+pub(crate) fn record(app: &AppHandle, game_id: &str, result: &Result<SyncResult, String>) {
+    with_state(app, |s| match result {
+        Ok(_) => s.failed.remove(game_id),
+        Err(e) if e.starts_with(settings::PAUSED) => s.failed.remove(game_id),
+        Err(e) => s.failed.insert(game_id.to_string(), e.clone()),
+    });
+    queue::changed(app);
+}
+
+fn ago(time: &str) -> String {
+    let Ok(time) = DateTime::parse_from_rfc3339(time) else {
+        return "Synced".to_string();
+    };
+    let elapsed = Utc::now().signed_duration_since(time);
+    match elapsed.num_minutes() {
+        m if m < 1 => "Synced just now".to_string(),
+        m if m < 60 => format!("Synced {} min ago", m),
+        m if m < 24 * 60 => format!("Synced {} h ago", m / 60),
+        m => format!("Synced {} d ago", m / (24 * 60)),
+    }
+}
+
+/// What the offline queue holds for a game, if anything.
+fn queue_status(conn: &Connection, game_id: &str) -> Option<&'static str> {
+    if sync_queue::failed_count(conn, Some(game_id)).ok()? > 0 {
+        Some("Upload failed, retry in app")
+    } else if sync_queue::pending_count(conn, Some(game_id)).ok()? > 0 {
+        Some("Waiting for network")
+    } else {
+        None
+    }
+}
+
+fn status(game: &LocalGame, state: &State, queued: Option<&str>) -> String {
+    if state.syncing.contains(&game.id) {
+        "Syncing…".to_string()
+    } else if game.sync_paused {
+        "Paused".to_string()
+    } else if let Some(queued) = queued {
+        queued.to_string()
+    } else if state.failed.contains_key(&game.id) {
+        "Sync failed".to_string()
+    } else if let Some(time) = &game.last_synced_at {
+        ago(time)
+    } else {
+        "Never synced".to_string()
+    }
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let conn = db::get_connection(app).ok();
+    let games = conn
+        .as_ref()
+        .and_then(|conn| games::list_games(conn).ok())
+        .unwrap_or_default();
+    let queued: Vec<Option<&str>> = games
+        .iter()
+        .map(|g| conn.as_ref().and_then(|conn| queue_status(conn, &g.id)))
+        .collect();
+    let statuses: Vec<String> = with_state(app, |s| {
+        games.iter().zip(queued).map(|(g, queued)| status(g, s, queued)).collect()
+    })
+    .unwrap_or_default();
+    let settings = conn
+        .as_ref()
+        .and_then(|conn| settings::load(conn).ok())
+        .unwrap_or_default();
+
+    let menu = Menu::new(app)?;
+    if games.is_empty() {
+        menu.append(&MenuItem::new(app, "No games yet", false, None::<&str>)?)?;
+    }
+    for (game, status) in games.iter().zip(statuses) {
+        let sync_now = MenuItem::with_id(app, format!("sync:{}", game.id), "Sync now", true, None::<&str>)?;
+        let open = MenuItem::with_id(app, format!("open:{}", game.id), "Open save folder", true, None::<&str>)?;
+        let pause = match game.sync_paused {
+            true => MenuItem::with_id(app, format!("resume:{}", game.id), "Resume syncing", true, None::<&str>)?,
+            false => MenuItem::with_id(app, format!("pause:{}", game.id), "Pause syncing", true, None::<&str>)?,
+        };
+        let submenu = Submenu::with_items(app, format!("{} — {}", game.name, status), true, &[&sync_now, &open, &pause])?;
+        menu.append(&submenu)?;
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, "sync_all", "Sync all", !games.is_empty(), None::<&str>)?)?;
+    if settings.sync_paused {
+        menu.append(&MenuItem::with_id(app, "resume", "Resume syncing", true, None::<&str>)?)?;
+    } else if let Some(until) = settings.paused_until() {
+        let text = format!("Resume syncing (paused until {})", until.format("%H:%M"));
+        menu.append(&MenuItem::with_id(app, "resume", text, true, None::<&str>)?)?;
+    } else {
+        menu.append(&MenuItem::with_id(app, "pause_hour", "Pause syncing for 1 hour", true, None::<&str>)?)?;
+        menu.append(&MenuItem::with_id(app, "pause", "Pause syncing", true, None::<&str>)?)?;
+    }
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, "show", "Open Sync Saves", true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+/// Rebuilds the tray menu from the current games and sync states.
+pub(crate) fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => eprintln!("Failed to build tray menu: {}", e),
+    }
+}
+
+pub(crate) fn show_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Syncs the games one after another with the app's session.
+fn sync_games(app: &AppHandle, game_ids: Vec<String>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let auth = match app_auth(&app) {
+            Ok(Some(auth)) => auth,
+            Ok(None) => {
+                notifications::show(&app, "Sync Saves", "Sign in to sync your saves");
+                return;
+            }
+            Err(e) => {
+                eprintln!("Tray sync failed: {}", e);
+                return;
+            }
+        };
+        let db_path = db::get_db_path(&app);
+        if let Some(reason) = pause_reason(&app, None) {
+            notifications::show(&app, "Sync Saves", &reason);
+            return;
+        }
+        for game_id in game_ids {
+            let result = track(&app, &game_id, sync::run_sync(&db_path, &game_id, &auth, None)).await;
+            notifications::sync_result(&app, &game_id, &result);
+            let _ = app.emit(
+                "sync-finished",
+                serde_json::json!({
+                    "game_id": game_id,
+                    "result": result.as_ref().ok(),
+                    "error": result.as_ref().err(),
+                }),
+            );
+        }
+    });
+}
+
+fn on_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        "sync_all" => {
+            let ids = db::get_connection(app)
+                .ok()
+                .and_then(|conn| games::list_games(&conn).ok())
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|g| g.sync_enabled && !g.sync_paused)
+                .map(|g| g.id)
+                .collect();
+            sync_games(app, ids);
+        }
+        "pause_hour" => update_settings(app, |s| {
+            s.sync_paused_until = Some((Local::now() + PAUSE_FOR).to_rfc3339());
+        }),
+        "pause" => update_settings(app, |s| s.sync_paused = true),
+        "resume" => update_settings(app, |s| {
+            s.sync_paused = false;
+            s.sync_paused_until = None;
+        }),
+        "show" => show_window(app),
+        "quit" => app.exit(0),
+        _ => {
+            if let Some(game_id) = id.strip_prefix("sync:") {
+                sync_games(app, vec![game_id.to_string()]);
+            } else if let Some(game_id) = id.strip_prefix("pause:") {
+                set_game_paused(app, game_id, true);
+            } else if let Some(game_id) = id.strip_prefix("resume:") {
+                set_game_paused(app, game_id, false);
+            } else if let Some(game_id) = id.strip_prefix("open:") {
+                let path = db::get_connection(app)
+                    .ok()
+                    .and_then(|conn| games::get_local_game(&conn, game_id).ok())
+                    .map(|g| g.local_path);
+                if let Some(Err(e)) = path.map(open_folder) {
+                    notifications::show(app, "Could not open save folder", &e);
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn init(app: &AppHandle) -> tauri::Result<()> {
+    app.manage(TrayState::default());
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Sync Saves")
+        .menu(&build_menu(app)?)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| on_menu_event(app, event.id().as_ref()))
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
@@ -1 +0,0 @@
-// Placeholder utils module
@@ -0,0 +1,238 @@
+//! Steam launch option mode: `sync-saves run --game <id> -- %command%` pulls the
+//! cloud's latest save, runs the game, waits for it and uploads its saves.
+//!
+//! The wrapper starts the game itself, so it keeps Steam's working directory,
+//! environment and process tree, and exits with the game. The pull and the
+//! push are steps handed to this binary again (`run --game <id> --step pull
+//! --done <file>`): the single-instance plugin forwards them to the running
+//! app, or the step starts the app hidden just for itself. Whichever instance
+//! handles a step writes its exit code to the done file the wrapper waits on.
+
+use crate::commands::auth::app_auth;
+use crate::commands::launch::{pull_before_launch, push_on_exit};
+use crate::db;
+use crate::notifications;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use sync_saves_core::games;
+use sync_saves_core::sync::AuthConfig;
+use tauri::{AppHandle, Manager};
+
+pub(crate) const USAGE: &str = "Usage: sync-saves run --game <id> -- <command>...";
+
+/// How long the wrapper waits for a pull or push before going on without it
+const STEP_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// How long a step waits for the frontend to share a fresh session
+const SESSION_WAIT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum StepKind {
+    Pull,
+    Push,
+}
+
+impl StepKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            StepKind::Pull => "pull",
+            StepKind::Push => "push",
+        }
+    }
+}
+
+/// A sync step of a wrapped run, done by the app.
+pub(crate) struct Step {
+    pub game_id: String,
+    pub kind: StepKind,
+    /// Where the exit code is written once the step is done
+    pub done: PathBuf,
+}
+
+pub(crate) enum RunArgs {
+    /// Started by Steam: runs the game between a pull and a push
+    Wrap { game_id: String, command: Vec<String> },
+    Step(Step),
+}
+
+/// Parses `run` arguments (without the program name); `None` for any other
+/// invocation of the app.
+pub(crate) fn parse(args: &[String]) -> Option<Result<RunArgs, String>> {
+    if args.first().map(String::as_str) != Some("run") {
+        return None;
+    }
+    Some(parse_run(&args[1..]))
+}
+
+fn parse_run(args: &[String]) -> Result<RunArgs, String> {
+    let mut game_id = None;
+    let mut kind = None;
+    let mut done = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--" => break,
+            "--game" => game_id = Some(iter.next().ok_or("--game needs a game id")?.clone()),
+            "--step" => {
+                kind = match iter.next().map(String::as_str) {
+                    Some("pull") => Some(StepKind::Pull),
+                    Some("push") => Some(StepKind::Push),
+                    other => return Err(format!("Invalid --step {}", other.unwrap_or_default())),
+                }
+            }
+            "--done" => done = Some(PathBuf::from(iter.next().ok_or("--done needs a file")?)),
+            other => return Err(format!("Unexpected argument: {}", other)),
+        }
+    }
+    let command: Vec<String> = iter.cloned().collect();
+
+    let game_id = game_id.ok_or("Missing --game")?;
+    match (kind, done) {
+        (Some(kind), Some(done)) => Ok(RunArgs::Step(Step { game_id, kind, done })),
+        (Some(_), None) => Err("--step needs --done".to_string()),
+        (None, _) if command.is_empty() => Err("Missing the game command after --".to_string()),
+        (None, _) => Ok(RunArgs::Wrap { game_id, command }),
+    }
+}
+
+/// Pulls, runs `command` until it exits and pushes; returns the game's exit code.
+pub(crate) fn wrap(game_id: &str, command: &[String]) -> i32 {
+    // The game starts with the local saves when the pull fails
+    run_step(game_id, StepKind::Pull);
+
+    let code = match Command::new(&command[0]).args(&command[1..]).status() {
+        Ok(status) => status.code().unwrap_or(1),
+        Err(e) => {
+            eprintln!("Failed to start {}: {}", command[0], e);
+            return 1;
+        }
+    };
+
+    run_step(game_id, StepKind::Push);
+    code
+}
+
+/// Hands a step to the app and waits until it is done; whether it succeeded.
+fn run_step(game_id: &str, kind: StepKind) -> bool {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("Failed to find the sync-saves executable: {}", e);
+            return false;
+        }
+    };
+    let done = std::env::temp_dir().join(format!("sync-saves-{}-{}.done", kind.as_str(), uuid::Uuid::new_v4()));
+    let mut child = match Command::new(exe)
+        .args(["run", "--game", game_id, "--step", kind.as_str(), "--done"])
+        .arg(&done)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("Failed to start sync-saves: {}", e);
+            return false;
+        }
+    };
+
+    // A forwarded step is done by the running app after the child exits; a
+    // standalone one may keep running if its window gets opened
+    let started = Instant::now();
+    let mut exited = false;
+    let code = loop {
+        if let Some(code) = fs::read_to_string(&done).ok().and_then(|c| c.trim().parse::<i32>().ok()) {
+            break Some(code);
+        }
+        if !exited {
+            match child.try_wait() {
+                Ok(Some(status)) if !status.success() => break None,
+                Ok(Some(_)) => exited = true,
+                Ok(None) => {}
+                Err(_) => break None,
+            }
+        }
+        if started.elapsed() > STEP_TIMEOUT {
+            eprintln!("Gave up waiting for the {} of {}", kind.as_str(), game_id);
+            break None;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+    let _ = fs::remove_file(&done);
+    code == Some(0)
+}
+
+/// Does the step and writes its exit code to the done file. With `standalone`
+/// the app was started just for this and quits afterwards unless its window
+/// was opened.
+pub(crate) fn start(app: AppHandle, step: Step, standalone: bool) {
+    tauri::async_runtime::spawn(async move {
+        let code = run(&app, &step).await;
+        if let Err(e) = write_done(&step.done, code) {
+            eprintln!("Failed to report the {} of {}: {}", step.kind.as_str(), step.game_id, e);
+        }
+        let window_open = app
+            .get_webview_window("main")
+            .and_then(|w| w.is_visible().ok())
+            .unwrap_or(false);
+        if standalone && !window_open {
+            app.exit(code);
+        }
+    });
+}
+
+/// Written next to the file and renamed, so the wrapper never reads half of it.
+fn write_done(path: &Path, code: i32) -> Result<(), String> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, code.to_string()).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+async fn run(app: &AppHandle, step: &Step) -> i32 {
+    let game_id = &step.game_id;
+    let name = db::get_connection(app)
+        .ok()
+        .and_then(|conn| games::get_local_game(&conn, game_id).ok())
+        .map_or_else(|| game_id.clone(), |game| game.name);
+
+    let auth = wait_for_session(app.clone()).await;
+    match step.kind {
+        StepKind::Pull => match pull_before_launch(app, game_id, auth).await {
+            Ok((_, None, _)) => 0,
+            Ok((_, Some(e), _)) | Err(e) => {
+                notifications::show(app, &format!("{} is using local saves", name), &e);
+                1
+            }
+        },
+        StepKind::Push => match push_on_exit(app, game_id, auth).await {
+            Ok(_) => 0,
+            Err(e) => {
+                // Shown even though a single failure doesn't notify elsewhere
+                notifications::show(app, &format!("Could not upload {} saves", name), &e);
+                1
+            }
+        },
+    }
+}
+
+/// The app's session once it is valid. The stored one expires after an hour
+/// and only the frontend can refresh it, which a just started app does on load.
+async fn wait_for_session(app: AppHandle) -> Option<AuthConfig> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let started = Instant::now();
+        loop {
+            match app_auth(&app) {
+                Ok(Some(auth)) => return Some(auth),
+                Ok(None) if started.elapsed() < SESSION_WAIT => std::thread::sleep(POLL_INTERVAL),
+                Ok(None) => return None,
+                Err(e) => {
+                    eprintln!("Failed to read the app session: {}", e);
+                    return None;
+                }
+            }
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
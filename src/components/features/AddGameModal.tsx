@@ -32,6 +32,7 @@ interface AddGameModalProps {
     path: string;
     autoSync: boolean;
     coverUrl?: string;
+    steamAppId?: number;
   }) => void;
 }
 
@@ -60,6 +61,7 @@ export default function AddGameModal({
   const [pcgwCoverUrl, setPcgwCoverUrl] = useState<string | undefined>(
     undefined
   );
+  const [steamAppId, setSteamAppId] = useState<number | undefined>(undefined);
 
   // Debounce effect
   useEffect(() => {
@@ -93,6 +95,7 @@ export default function AddGameModal({
 
     // Set Cover
     setPcgwCoverUrl(game.cover_url);
+    setSteamAppId(game.id);
 
     // Trigger PCGW Search automatically
     handlePcgwSelectGame(game.name, game.cover_url); // Pass cover in case PCGW overrides or fails
@@ -127,6 +130,7 @@ export default function AddGameModal({
         path: newGamePath,
         autoSync,
         coverUrl: pcgwCoverUrl || undefined,
+        steamAppId,
       });
       // Reset form
       setNewGameName('');
@@ -138,6 +142,7 @@ export default function AddGameModal({
       setPcgwSelectedTitle('');
       setPcgwPaths([]);
       setPcgwCoverUrl(undefined);
+      setSteamAppId(undefined);
     } catch (error) {
       console.error('Failed to add game:', error);
     } finally {
@@ -172,7 +177,7 @@ export default function AddGameModal({
       setNewGameName(res.title);
       if (res.paths.length === 0) {
         setPcgwError(
-          'PCGamingWiki page found, but no Windows save paths were detected.'
+          'PCGamingWiki page found, but no save paths were detected.'
         );
       }
     } catch (e) {
@@ -219,6 +224,7 @@ export default function AddGameModal({
                       onChange={(e) => {
                         setSteamQuery(e.target.value);
                         setNewGameName(e.target.value); // Allow custom names too
+                        setSteamAppId(undefined);
                         if (!showSteamDropdown) setShowSteamDropdown(true);
                       }}
                       onFocus={() => {
@@ -14,6 +14,7 @@ import {
   FileText,
   Cloud,
   Zap,
+  Play,
 } from 'lucide-react';
 import {
   type Game,
@@ -23,7 +24,11 @@ import {
 import { useSyncStore } from '@/stores/syncStore';
 import { toast } from '@/stores/toastStore';
 import GameSettingsModal from './GameSettingsModal';
-import { getGameSaveStats, type GameSaveStatsDto } from '@/lib/tauri-games';
+import {
+  getGameSaveStats,
+  prepareLaunch,
+  type GameSaveStatsDto,
+} from '@/lib/tauri-games';
 import { formatBytes, isTauriRuntime, timeAgo } from '@/lib/utils';
 import { confirmRestore, confirmRemove } from '@/lib/confirm';
 
@@ -34,6 +39,11 @@ const platformConfig: Record<
   steam: { label: 'STEAM', color: 'bg-blue-500', glow: 'shadow-blue-500/50' },
   epic: { label: 'EPIC', color: 'bg-slate-600', glow: 'shadow-slate-500/50' },
   gog: { label: 'GOG', color: 'bg-purple-600', glow: 'shadow-purple-500/50' },
+  emulator: {
+    label: 'EMULATOR',
+    color: 'bg-amber-600',
+    glow: 'shadow-amber-500/50',
+  },
   other: { label: 'OTHER', color: 'bg-gray-600', glow: 'shadow-gray-500/50' },
 };
 
@@ -108,6 +118,9 @@ function GameCard({ game }: { game: Game }) {
   const performRestore = useSyncStore((state) => state.performRestore);
   const removeGame = useGamesStore((state) => state.removeGame);
   const configureGamePath = useGamesStore((state) => state.configureGamePath);
+  const configureFromTemplates = useGamesStore(
+    (state) => state.configureFromTemplates,
+  );
   const [isSettingsOpen, setIsSettingsOpen] = useState(false);
   const [isConfiguringPath, setIsConfiguringPath] = useState(false);
   const [saveStats, setSaveStats] = useState<
@@ -212,6 +225,14 @@ function GameCard({ game }: { game: Game }) {
 
     setIsConfiguringPath(true);
     try {
+      const cloudId = game.cloud_game_id || game.id;
+      // Where other devices keep the saves, when that folder exists here too
+      const found = await configureFromTemplates(cloudId);
+      if (found) {
+        toast.success('Path Configured', `Found ${game.name} saves at ${found}`);
+        return;
+      }
+
       const { open } = await import('@tauri-apps/plugin-dialog');
       const selected = await open({
         directory: true,
@@ -220,7 +241,6 @@ function GameCard({ game }: { game: Game }) {
       });
 
       if (selected && typeof selected === 'string') {
-        const cloudId = game.cloud_game_id || game.id;
         await configureGamePath(cloudId, selected);
         toast.success('Path Configured', `${game.name} is now ready to sync`);
       }
@@ -233,11 +253,49 @@ function GameCard({ game }: { game: Game }) {
     } finally {
       setIsConfiguringPath(false);
     }
-  }, [game.id, game.cloud_game_id, game.name, isTauri, configureGamePath]);
+  }, [
+    game.id,
+    game.cloud_game_id,
+    game.name,
+    isTauri,
+    configureGamePath,
+    configureFromTemplates,
+  ]);
+
+  const handlePlay = useCallback(async () => {
+    try {
+      const report = await prepareLaunch(game.id);
+      if (report.pull?.action === 'pulled') {
+        toast.success('Saves Updated', `Pulled the latest cloud save for ${game.name}`);
+      } else if (report.pull?.action === 'kept_local') {
+        toast.warning(
+          'Local Saves Kept',
+          'The cloud has a newer save, but local changes were not synced yet',
+        );
+      } else if (report.pull_error) {
+        toast.warning('Playing Offline', report.pull_error);
+      }
+      if (!report.tracked) {
+        toast.info(
+          'Sync After Playing',
+          `Could not track ${game.name}; sync manually when you are done`,
+        );
+      }
+    } catch (error) {
+      console.error('Launch failed:', error);
+      toast.error(
+        'Launch Failed',
+        error instanceof Error ? error.message : String(error),
+      );
+    }
+  }, [game.id, game.name]);
 
   const handleDropdownAction = useCallback(
     (key: React.Key) => {
       switch (key) {
+        case 'play':
+          handlePlay();
+          break;
         case 'open':
           handleOpenFolder();
           break;
@@ -249,7 +307,7 @@ function GameCard({ game }: { game: Game }) {
           break;
       }
     },
-    [handleOpenFolder, handleDelete],
+    [handlePlay, handleOpenFolder, handleDelete],
   );
 
   return (
@@ -257,9 +315,9 @@ function GameCard({ game }: { game: Game }) {
       <div className='relative bg-bg-card border border-white/[0.08] rounded-2xl flex flex-col overflow-hidden group cursor-pointer transition-all duration-300 hover:border-primary-500/40 hover:shadow-xl hover:shadow-primary-500/10'>
         {/* Cover Image */}
         <div className='relative h-36 overflow-hidden'>
-          {game.cover_url ? (
+          {game.cover_local || game.cover_url ? (
             <img
-              src={game.cover_url}
+              src={game.cover_local || game.cover_url}
               alt={game.name}
               className='w-full h-full object-cover transition-transform duration-500 ease-out group-hover:scale-105'
             />
@@ -476,6 +534,14 @@ function GameCard({ game }: { game: Game }) {
                     aria-label='Game actions'
                     onAction={handleDropdownAction}
                   >
+                    {isTauri && (game.executable_path || game.steam_app_id) ? (
+                      <Dropdown.Item id='play' textValue='Play'>
+                        <div className='flex items-center gap-2'>
+                          <Play className='w-4 h-4' />
+                          <span>Play</span>
+                        </div>
+                      </Dropdown.Item>
+                    ) : null}
                     <Dropdown.Item id='open' textValue='Open Folder'>
                       <div className='flex items-center gap-2'>
                         <FolderOpen className='w-4 h-4' />
@@ -36,6 +36,10 @@ export default function GameSettingsModal({
   const [localPath, setLocalPath] = useState(game.local_path);
   const [platform, setPlatform] = useState<GamePlatform>(game.platform);
   const [syncEnabled, setSyncEnabled] = useState(game.sync_enabled);
+  const [syncPaused, setSyncPaused] = useState(game.sync_paused ?? false);
+  const [executablePath, setExecutablePath] = useState(
+    game.executable_path || ''
+  );
 
   // Analysis tab state
   const [scriptPath, setScriptPath] = useState(game.custom_script_path || '');
@@ -51,6 +55,8 @@ export default function GameSettingsModal({
       setLocalPath(game.local_path);
       setPlatform(game.platform);
       setSyncEnabled(game.sync_enabled);
+      setSyncPaused(game.sync_paused ?? false);
+      setExecutablePath(game.executable_path || '');
       setScriptPath(game.custom_script_path || '');
 
       if (game.analysis_config) {
@@ -76,6 +82,8 @@ export default function GameSettingsModal({
         local_path: localPath,
         platform,
         sync_enabled: syncEnabled,
+        sync_paused: syncPaused,
+        executable_path: executablePath,
         slug: name.toLowerCase().replace(/\s+/g, '-'),
         custom_script_path: scriptPath,
         analysis_config: {
@@ -107,6 +115,8 @@ export default function GameSettingsModal({
     localPath !== game.local_path ||
     platform !== game.platform ||
     syncEnabled !== game.sync_enabled ||
+    syncPaused !== (game.sync_paused ?? false) ||
+    executablePath !== (game.executable_path || '') ||
     scriptPath !== (game.custom_script_path || '') ||
     targetAnalysisFile !== (game.analysis_config?.target_path || '') ||
     trackedKeys.size !== (game.analysis_config?.tracked_keys.length || 0) ||
@@ -170,10 +180,14 @@ export default function GameSettingsModal({
                   localPath={localPath}
                   platform={platform}
                   syncEnabled={syncEnabled}
+                  syncPaused={syncPaused}
+                  executablePath={executablePath}
                   onNameChange={setName}
                   onLocalPathChange={setLocalPath}
                   onPlatformChange={setPlatform}
                   onSyncEnabledChange={setSyncEnabled}
+                  onSyncPausedChange={setSyncPaused}
+                  onExecutablePathChange={setExecutablePath}
                 />
               )}
 
@@ -5,7 +5,6 @@ import {
   formatBytes,
   downloadVersionBlob,
 } from '@/lib/cloudSync';
-import { createWrapperScript, flattenObject } from '@/lib/script-utils';
 import { useAuthStore } from '@/stores/authStore';
 import { useSyncStore } from '@/stores/syncStore';
 import { useGamesStore } from '@/stores/gamesStore';
@@ -21,8 +20,11 @@ import {
 } from 'lucide-react';
 import { toast } from '@/stores/toastStore';
 import { formatDistanceToNow } from 'date-fns';
-import { isTauriRuntime } from '@/lib/utils';
-import { getVersionAnalysis, saveVersionAnalysis } from '@/lib/tauri-games';
+import { arrayBufferToBase64, isTauriRuntime } from '@/lib/utils';
+import {
+  analyzeVersion as runVersionAnalysis,
+  getVersionAnalysis,
+} from '@/lib/tauri-games';
 import { confirmAction } from '@/lib/confirm';
 
 import { motion, AnimatePresence } from 'framer-motion';
@@ -138,7 +140,6 @@ export default function VersionHistory({
       return;
     }
 
-    const scriptPath = game.custom_script_path;
     const config = game.analysis_config;
 
     setAnalyzingIds((prev) => new Set(prev).add(version.id));
@@ -147,183 +148,16 @@ export default function VersionHistory({
       let extractedData: any = null;
 
       if (isTauriRuntime()) {
-        const { writeFile, remove, mkdir, readFile, readDir } =
-          await import('@tauri-apps/plugin-fs');
-        const { appDataDir, join } = await import('@tauri-apps/api/path');
-        const { Command } = await import('@tauri-apps/plugin-shell');
-
-        const appData = await appDataDir();
-        const tempDir = await join(appData, 'temp_timeline', version.id); // Unique folder per version
-        await mkdir(tempDir, { recursive: true });
-
-        // Ensure wrapper exists
-        const wrapperPath = await createWrapperScript(scriptPath, gameId);
-
-        try {
-          const blob = await downloadVersionBlob(version.file_path);
-          if (!blob) throw new Error('Failed to download blob');
-
-          // Aggressive sanitization
-          const safeTargetName = config.target_path.replace(
-            /[^a-zA-Z0-9.-]/g,
-            '_'
-          );
-          let tempFilePath = await join(
-            tempDir,
-            `v_${version.id}_${safeTargetName}.zip`
-          );
-
-          await writeFile(tempFilePath, new Uint8Array(blob));
-
-          // ZIP Detection & Extraction checking header signature
-          const u8Header = new Uint8Array(blob as ArrayBuffer).slice(0, 4);
-          const isZip =
-            blob.byteLength > 4 &&
-            u8Header[0] === 0x50 &&
-            u8Header[1] === 0x4b &&
-            u8Header[2] === 0x03 &&
-            u8Header[3] === 0x04;
-
-          if (isZip) {
-            console.log(
-              `[Analysis] ZIP header detected for ${version.id}. Extracting...`
-            );
-            const unzipScriptPath = await join(
-              tempDir,
-              `unzip_${version.id}.ps1`
-            );
-            const unzipScript = `
-param($ZipPath)
-$OutputEncoding = [System.Text.Encoding]::UTF8
-[Console]::OutputEncoding = [System.Text.Encoding]::UTF8
-$Dest = "\${ZipPath}_extracted"
-Write-Host "Extracting $ZipPath to $Dest"
-if (!(Test-Path $ZipPath)) { Write-Error "Zip file not found"; exit 1 }
-Expand-Archive -LiteralPath $ZipPath -DestinationPath $Dest -Force -ErrorAction Stop
-Write-Host "Extraction complete. Contents:"
-Get-ChildItem -Recurse $Dest | Select-Object FullName
-`;
-            await writeFile(
-              unzipScriptPath,
-              new TextEncoder().encode(unzipScript)
-            );
-
-            const unzipCmd = Command.create('run-powershell', [
-              '-ExecutionPolicy',
-              'Bypass',
-              '-File',
-              unzipScriptPath,
-              tempFilePath,
-            ]);
-            const unzipOutput = await unzipCmd.execute();
-            console.log('[Analysis] Unzip Output:', unzipOutput.stdout);
-
-            if (unzipOutput.code !== 0) throw new Error('Extraction failed');
-
-            // Find full path to the target file recursively
-            const extractedDir = `${tempFilePath}_extracted`;
-            const findFile = async (
-              dir: string,
-              filename: string
-            ): Promise<string | null> => {
-              const entries = await readDir(dir);
-              for (const entry of entries) {
-                const fullEntryPath = await join(dir, entry.name);
-                if (
-                  entry.isFile &&
-                  entry.name.toLowerCase().includes(filename.toLowerCase())
-                ) {
-                  return fullEntryPath;
-                }
-                if (entry.isDirectory) {
-                  const found = await findFile(fullEntryPath, filename);
-                  if (found) return found;
-                }
-              }
-              return null;
-            };
-
-            const targetBasename =
-              config.target_path.split(/[/\\]/).pop() || config.target_path;
-            const absoluteTargetPath = await findFile(
-              extractedDir,
-              targetBasename
-            );
-
-            if (!absoluteTargetPath)
-              throw new Error('Target file not found in ZIP');
-
-            const scriptArgs = [
-              '-ExecutionPolicy',
-              'Bypass',
-              '-File',
-              wrapperPath,
-              absoluteTargetPath,
-            ];
-            const cmd = Command.create('run-powershell', scriptArgs);
-            const output = await cmd.execute();
-
-            if (output.code !== 0)
-              throw new Error(output.stderr || 'Script failed');
-
-            const match = output.stdout.match(
-              /Decoded JSON written to:\s*(.+?)\s*$/m
-            );
-            if (match && match[1]) {
-              const jsonPath = match[1].trim();
-              const fileContent = await readFile(jsonPath);
-              const jsonData = JSON.parse(
-                new TextDecoder().decode(fileContent)
-              );
-              const flattened = flattenObject(jsonData);
-              extractedData = {};
-              config.tracked_keys.forEach((key) => {
-                if (flattened[key] !== undefined)
-                  extractedData[key] = flattened[key];
-              });
-              await remove(jsonPath);
-            }
-
-            // Cleanup
-            await remove(tempDir, { recursive: true });
-          } else {
-            // Direct file analysis
-            const scriptArgs = [
-              '-ExecutionPolicy',
-              'Bypass',
-              '-File',
-              wrapperPath,
-              tempFilePath,
-            ];
-            const cmd = Command.create('run-powershell', scriptArgs);
-            const output = await cmd.execute();
-
-            if (output.code !== 0)
-              throw new Error(output.stderr || 'Script failed');
-
-            const match = output.stdout.match(
-              /Decoded JSON written to:\s*(.+?)\s*$/m
-            );
-            if (match && match[1]) {
-              const jsonPath = match[1].trim();
-              const fileContent = await readFile(jsonPath);
-              const jsonData = JSON.parse(
-                new TextDecoder().decode(fileContent)
-              );
-              const flattened = flattenObject(jsonData);
-              extractedData = {};
-              config.tracked_keys.forEach((key) => {
-                if (flattened[key] !== undefined)
-                  extractedData[key] = flattened[key];
-              });
-              await remove(jsonPath);
-            }
-            await remove(tempFilePath);
-          }
-        } catch (err) {
-          console.error('[Analysis] Error:', err);
-          throw err;
-        }
+        const blob = await downloadVersionBlob(version.file_path);
+        if (!blob) throw new Error('Failed to download blob');
+
+        // The backend extracts the version, runs the analyzer and caches the result
+        const result = await runVersionAnalysis(
+          gameId,
+          version.id,
+          arrayBufferToBase64(blob)
+        );
+        extractedData = result.data;
       } else {
         // Web Mock
         await new Promise((r) => setTimeout(r, 1000));
@@ -340,11 +174,6 @@ Get-ChildItem -Recurse $Dest | Select-Object FullName
         }));
         setAnalyzedCount((prev) => prev + 1);
 
-        // Save to Local Database for persistence
-        if (isTauriRuntime()) {
-          await saveVersionAnalysis(version.id, extractedData);
-        }
-
         // Save to Cloud for cross-device sharing
         try {
           const { updateSaveVersionAnalysis } = await import('@/lib/cloudSync');
@@ -1,5 +1,5 @@
 import { Switch, Label, Button, Tooltip, Select, ListBox } from '@heroui/react';
-import { FolderOpen, Info, AlertTriangle } from 'lucide-react';
+import { FolderOpen, Info, AlertTriangle, Gamepad2, X } from 'lucide-react';
 import { open } from '@tauri-apps/plugin-dialog';
 import { SaveInput } from '@/components/common/SaveInput';
 import { isProtectedPath } from '@/lib/utils';
@@ -9,6 +9,7 @@ const platforms: { value: GamePlatform; label: string }[] = [
   { value: 'steam', label: 'Steam' },
   { value: 'epic', label: 'Epic Games' },
   { value: 'gog', label: 'GOG' },
+  { value: 'emulator', label: 'Emulator' },
   { value: 'other', label: 'Other' },
 ];
 
@@ -17,10 +18,14 @@ interface GeneralTabProps {
   localPath: string;
   platform: GamePlatform;
   syncEnabled: boolean;
+  syncPaused: boolean;
+  executablePath: string;
   onNameChange: (value: string) => void;
   onLocalPathChange: (value: string) => void;
   onPlatformChange: (value: GamePlatform) => void;
   onSyncEnabledChange: (value: boolean) => void;
+  onSyncPausedChange: (value: boolean) => void;
+  onExecutablePathChange: (value: string) => void;
 }
 
 /**
@@ -31,10 +36,14 @@ export function GeneralTab({
   localPath,
   platform,
   syncEnabled,
+  syncPaused,
+  executablePath,
   onNameChange,
   onLocalPathChange,
   onPlatformChange,
   onSyncEnabledChange,
+  onSyncPausedChange,
+  onExecutablePathChange,
 }: GeneralTabProps) {
   const handleSelectFolder = async () => {
     try {
@@ -53,6 +62,23 @@ export function GeneralTab({
     }
   };
 
+  const handleSelectExecutable = async () => {
+    try {
+      const selected = await open({
+        directory: false,
+        multiple: false,
+        title: 'Select Game Executable',
+        defaultPath: executablePath || undefined,
+      });
+
+      if (selected) {
+        onExecutablePathChange(selected as string);
+      }
+    } catch (error) {
+      console.error('Failed to open file picker:', error);
+    }
+  };
+
   return (
     <div className='p-6 overflow-y-auto'>
       <div className='space-y-6'>
@@ -136,6 +162,47 @@ export function GeneralTab({
           </Select>
         </div>
 
+        {/* Executable */}
+        <div className='space-y-2'>
+          <div className='flex items-center justify-between'>
+            <Label className='text-sm text-gray-400 font-medium'>
+              Game Executable
+            </Label>
+            <Tooltip>
+              <Tooltip.Trigger>
+                <Info className='w-3.5 h-3.5 text-gray-600 cursor-help' />
+              </Tooltip.Trigger>
+              <Tooltip.Content>
+                Started by Play. Steam games without one start through Steam
+              </Tooltip.Content>
+            </Tooltip>
+          </div>
+          <div className='flex gap-2'>
+            <SaveInput
+              placeholder='C:\Games\...\game.exe'
+              value={executablePath}
+              onChange={(e) => onExecutablePathChange(e.target.value)}
+              readOnly
+              className='flex-1'
+            />
+            {executablePath && (
+              <Button
+                aria-label='Clear executable'
+                onPress={() => onExecutablePathChange('')}
+                className='bg-bg-elevated text-gray-400 border border-white/10 h-12 w-12 min-w-12 rounded-xl hover:text-white transition-colors flex flex-row items-center justify-center'
+              >
+                <X className='w-5 h-5' />
+              </Button>
+            )}
+            <Button
+              onPress={handleSelectExecutable}
+              className='bg-primary-900/30 text-primary-400 border border-primary-500/20 h-12 w-12 min-w-12 rounded-xl hover:bg-primary-900/50 transition-colors flex flex-row items-center justify-center'
+            >
+              <Gamepad2 className='w-5 h-5' />
+            </Button>
+          </div>
+        </div>
+
         {/* Auto-Sync Toggle */}
         <div className='p-4 rounded-xl bg-bg-elevated/30 border border-white/5 flex items-center justify-between'>
           <div className='space-y-0.5'>
@@ -157,6 +224,26 @@ export function GeneralTab({
             </Switch.Control>
           </Switch>
         </div>
+
+        {/* Pause Toggle */}
+        <div className='p-4 rounded-xl bg-bg-elevated/30 border border-white/5 flex items-center justify-between'>
+          <div className='space-y-0.5'>
+            <p className='text-sm font-semibold text-white'>Pause Syncing</p>
+            <p className='text-[11px] text-gray-500 font-medium'>
+              Hold back every upload of this game, even manual ones
+            </p>
+          </div>
+          <Switch
+            aria-label='Toggle Pause'
+            isSelected={syncPaused}
+            onChange={onSyncPausedChange}
+            size='sm'
+          >
+            <Switch.Control>
+              <Switch.Thumb />
+            </Switch.Control>
+          </Switch>
+        </div>
       </div>
     </div>
   );
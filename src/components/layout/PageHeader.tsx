@@ -1,6 +1,13 @@
 import { Button } from "@heroui/react";
-import { RefreshCw } from "lucide-react";
+import { RefreshCw, RotateCcw, Trash2 } from "lucide-react";
 import { useSyncStore } from "@/stores/syncStore";
+import { toast } from "@/stores/toastStore";
+import { confirmDangerousAction } from "@/lib/confirm";
+import {
+  discardFailedSyncs,
+  flushSyncQueue,
+  retryFailedSyncs,
+} from "@/lib/tauri-games";
 
 interface PageHeaderProps {
   title: string;
@@ -15,7 +22,24 @@ export default function PageHeader({
   showSyncButton = true,
   rightContent,
 }: PageHeaderProps) {
-  const { status, message, isBackendConnected } = useSyncStore();
+  const { status, message, isBackendConnected, pendingSyncs, failedSyncs } =
+    useSyncStore();
+
+  const handleDiscard = async () => {
+    const confirmed = await confirmDangerousAction(
+      `Discard ${failedSyncs} failed upload(s)? Those saves will not reach the cloud.`,
+      "Discard Failed Uploads"
+    );
+    if (!confirmed) return;
+    try {
+      await discardFailedSyncs();
+    } catch (error) {
+      toast.error(
+        "Discard Failed",
+        error instanceof Error ? error.message : String(error)
+      );
+    }
+  };
 
   const statusColor =
     {
@@ -52,10 +76,19 @@ export default function PageHeader({
             <div className="hidden sm:flex items-center gap-3 bg-bg-elevated px-4 py-2 rounded-xl border border-white/5">
               <span className={`w-2.5 h-2.5 rounded-full ${statusColor}`} />
               <span className="text-sm font-medium text-gray-300">
-                {message ||
-                  (status === "idle" ? "All Synced" : status.toUpperCase())}
+                {pendingSyncs > 0 && status === "idle"
+                  ? `${pendingSyncs} waiting for network`
+                  : message ||
+                    (status === "idle" ? "All Synced" : status.toUpperCase())}
               </span>
-              <Button isIconOnly size="sm" variant="ghost">
+              <Button
+                isIconOnly
+                size="sm"
+                variant="ghost"
+                aria-label="Retry queued syncs"
+                isDisabled={pendingSyncs === 0}
+                onPress={() => flushSyncQueue().catch(() => {})}
+              >
                 <RefreshCw
                   className={`w-4 h-4 ${
                     status === "syncing" ? "animate-spin" : ""
@@ -65,6 +98,32 @@ export default function PageHeader({
             </div>
           )}
 
+          {showSyncButton && failedSyncs > 0 && (
+            <div className="flex items-center gap-2 bg-danger/10 px-4 py-2 rounded-xl border border-danger/20">
+              <span className="text-sm font-medium text-danger">
+                {failedSyncs} failed upload{failedSyncs === 1 ? "" : "s"}
+              </span>
+              <Button
+                isIconOnly
+                size="sm"
+                variant="ghost"
+                aria-label="Retry failed uploads"
+                onPress={() => retryFailedSyncs().catch(() => {})}
+              >
+                <RotateCcw className="w-4 h-4" />
+              </Button>
+              <Button
+                isIconOnly
+                size="sm"
+                variant="ghost"
+                aria-label="Discard failed uploads"
+                onPress={handleDiscard}
+              >
+                <Trash2 className="w-4 h-4" />
+              </Button>
+            </div>
+          )}
+
           {rightContent && (
             <div className="flex flex-col sm:flex-row sm:items-center items-stretch gap-2 sm:gap-3 w-full sm:w-auto">
               {rightContent}
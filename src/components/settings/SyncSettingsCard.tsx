@@ -1,5 +1,6 @@
-import { Switch } from '@heroui/react';
+import { Label, Switch } from '@heroui/react';
 import { Clock } from 'lucide-react';
+import { SaveInput } from '@/components/common/SaveInput';
 import type { SystemInfo, AppSettings } from '@/lib/tauri';
 
 interface SyncSettingsCardProps {
@@ -44,6 +45,40 @@ export function SettingToggleRow({
   );
 }
 
+/**
+ * Number input for a KB/s limit, where empty or 0 means no limit.
+ */
+function LimitInput({
+  label,
+  value,
+  onChange,
+}: {
+  label: string;
+  value: number;
+  onChange: (value: number) => void;
+}) {
+  return (
+    <div className='space-y-2 flex flex-col flex-1'>
+      <Label className='text-sm text-gray-400 font-medium'>{label}</Label>
+      <SaveInput
+        type='number'
+        min={0}
+        placeholder='No limit'
+        value={value > 0 ? String(value) : ''}
+        onChange={(e) =>
+          onChange(Math.max(0, Math.floor(Number(e.target.value) || 0)))
+        }
+      />
+    </div>
+  );
+}
+
+function pausedUntil(settings: AppSettings): Date | null {
+  if (!settings.sync_paused_until) return null;
+  const until = new Date(settings.sync_paused_until);
+  return until > new Date() ? until : null;
+}
+
 /**
  * Card component for sync/behavior settings.
  */
@@ -51,6 +86,8 @@ export function SyncSettingsCard({
   settings,
   onUpdateSetting,
 }: SyncSettingsCardProps) {
+  const until = pausedUntil(settings);
+
   return (
     <div className='bg-bg-elevated/40 backdrop-blur-xl border border-white/5 rounded-2xl shadow-2xl shadow-black/20'>
       <div className='p-4 sm:p-6 lg:p-8 text-center sm:text-left'>
@@ -90,6 +127,44 @@ export function SyncSettingsCard({
               onUpdateSetting('auto_sync_enabled', isSelected)
             }
           />
+
+          <SettingToggleRow
+            title='Pause Syncing'
+            description={
+              until && !settings.sync_paused
+                ? `Paused until ${until.toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' })}`
+                : 'Hold back every upload until turned off'
+            }
+            isSelected={settings.sync_paused || until !== null}
+            onChange={(isSelected) => {
+              onUpdateSetting('sync_paused', isSelected);
+              if (!isSelected) onUpdateSetting('sync_paused_until', null);
+            }}
+          />
+
+          <SettingToggleRow
+            title='Pause on Metered Connections'
+            description='Hold back uploads while the system reports a metered network'
+            isSelected={settings.pause_on_metered}
+            onChange={(isSelected) =>
+              onUpdateSetting('pause_on_metered', isSelected)
+            }
+          />
+
+          <div className='flex flex-col sm:flex-row gap-4 text-left'>
+            <LimitInput
+              label='Upload limit (KB/s)'
+              value={settings.upload_limit_kbps}
+              onChange={(value) => onUpdateSetting('upload_limit_kbps', value)}
+            />
+            <LimitInput
+              label='Download limit (KB/s)'
+              value={settings.download_limit_kbps}
+              onChange={(value) =>
+                onUpdateSetting('download_limit_kbps', value)
+              }
+            />
+          </div>
         </div>
       </div>
     </div>
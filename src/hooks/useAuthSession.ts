@@ -3,6 +3,26 @@ import { useAuthStore } from '@/stores/authStore';
 import { useGamesStore } from '@/stores/gamesStore';
 import { getSession, onAuthStateChange, supabase } from '@/lib/supabase';
 import { toast } from '@/stores/toastStore';
+import { shareCloudSession } from '@/lib/tauri';
+import { isTauriRuntime } from '@/lib/utils';
+
+// Mirrors the session into the local database for sync-saves-cli
+function shareSession(session: any) {
+  if (!isTauriRuntime()) return;
+  const url = import.meta.env.VITE_SUPABASE_URL;
+  const key = import.meta.env.VITE_SUPABASE_ANON_KEY;
+  if (!session?.user || !url || !key) {
+    shareCloudSession(null);
+    return;
+  }
+  shareCloudSession({
+    url,
+    key,
+    user_id: session.user.id,
+    access_token: session.access_token,
+    expires_at: session.expires_at,
+  });
+}
 
 /**
  * Hook that manages authentication session lifecycle:
@@ -93,6 +113,9 @@ export function useAuthSession() {
       data: { subscription },
     } = onAuthStateChange((event, session: any) => {
       console.log('Auth Event:', event);
+      if (session?.user || event === 'SIGNED_OUT') {
+        shareSession(session);
+      }
       if (session?.user) {
         setUser({
           id: session.user.id,
@@ -136,6 +159,9 @@ export function useDeepLinkAuth() {
           url = url.replace(/['"]/g, '').trim();
           console.log('Deep link sanitized:', url);
 
+          // Launch links are handled by the backend
+          if (url.startsWith('sync-saves://launch/')) return;
+
           try {
             toast.success(
               'Authenticating...',
@@ -1,6 +1,8 @@
 import { useEffect } from 'react';
 import { useSyncStore } from '@/stores/syncStore';
+import { useGamesStore } from '@/stores/gamesStore';
 import { toast } from '@/stores/toastStore';
+import { getFailedSyncCount, getPendingSyncCount } from '@/lib/tauri-games';
 
 /**
  * Hook that handles automatic sync when file changes are detected.
@@ -11,6 +13,10 @@ export function useAutoSync() {
 
   useEffect(() => {
     let unlisten: (() => void) | undefined;
+    let unlistenExited: (() => void) | undefined;
+    let unlistenLaunchFailed: (() => void) | undefined;
+    let unlistenTraySync: (() => void) | undefined;
+    let unlistenQueue: (() => void) | undefined;
 
     const setupAutoSyncListener = async () => {
       const { listen } = await import('@tauri-apps/api/event');
@@ -21,8 +27,7 @@ export function useAutoSync() {
 
         try {
           await performSync(gameId);
-          await sendDesktopNotification();
-          
+          // The backend sends the desktop notification
           toast.success(
             'Auto-Sync Complete',
             'Your save has been backed up to the cloud'
@@ -35,43 +40,57 @@ export function useAutoSync() {
           );
         }
       });
+
+      // Games started with Play upload their saves from the backend on exit
+      unlistenExited = await listen<{ game_id: string; error?: string | null }>(
+        'game-exited',
+        async (event) => {
+          const { game_id, error } = event.payload;
+          const name =
+            useGamesStore.getState().games.find((g) => g.id === game_id)
+              ?.name ?? 'Game';
+          if (error?.startsWith('Sync is paused')) {
+            toast.info('Upload Skipped', `${name}: ${error}`);
+          } else if (error) {
+            toast.error('Upload After Playing Failed', `${name}: ${error}`);
+          } else {
+            toast.success('Saves Uploaded', `${name} was backed up after playing`);
+          }
+          await useGamesStore.getState().loadGames();
+        }
+      );
+
+      useSyncStore.getState().setPendingSyncs(await getPendingSyncCount());
+      useSyncStore.getState().setFailedSyncs(await getFailedSyncCount());
+      unlistenQueue = await listen<{ pending: number; failed: number }>(
+        'sync-queue-changed',
+        (event) => {
+          useSyncStore.getState().setPendingSyncs(event.payload.pending);
+          useSyncStore.getState().setFailedSyncs(event.payload.failed);
+        }
+      );
+
+      // Syncs started from the tray menu or sent from the offline queue
+      unlistenTraySync = await listen('sync-finished', async () => {
+        await useGamesStore.getState().loadGames();
+      });
+
+      unlistenLaunchFailed = await listen<{ game_id: string; error: string }>(
+        'launch-failed',
+        (event) => {
+          toast.error('Launch Failed', event.payload.error);
+        }
+      );
     };
 
     setupAutoSyncListener();
 
     return () => {
       unlisten?.();
+      unlistenExited?.();
+      unlistenLaunchFailed?.();
+      unlistenTraySync?.();
+      unlistenQueue?.();
     };
   }, [performSync]);
 }
-
-/**
- * Sends a desktop notification if enabled in settings and permission is granted.
- */
-async function sendDesktopNotification(): Promise<void> {
-  try {
-    const { getAppSettings } = await import('@/lib/tauri');
-    const settings = await getAppSettings();
-
-    if (!settings.desktop_notifications) return;
-
-    const { isPermissionGranted, requestPermission, sendNotification } = 
-      await import('@tauri-apps/plugin-notification');
-    
-    let permission = await isPermissionGranted();
-    if (!permission) {
-      permission = (await requestPermission()) === 'granted';
-    }
-
-    if (permission) {
-      sendNotification({
-        title: 'Sync Saves: Auto-Backup',
-        body: 'Your save for this game has been successfully backed up to the cloud!',
-        icon: 'cloud',
-      });
-    }
-  } catch (e) {
-    // Silently fail - notifications are non-critical
-    console.warn('Failed to send desktop notification:', e);
-  }
-}
@@ -97,33 +97,19 @@ export async function createSaveVersion(params: {
   filePath: string
   fileSize: number
   checksum: string
-  file_modified_at?: string
 }) {
-  // unset previous latest
-  await (supabase
-    .from('save_versions') as any)
-    .update({ is_latest: false })
-    .eq('game_id', params.cloudGameId)
-    .eq('is_latest', true)
-
-  const res = await (supabase
-    .from('save_versions') as any)
-    .insert({
-      id: params.id,
-      game_id: params.cloudGameId,
-      device_id: params.deviceId,
-      // version removed
-      file_path: params.filePath,
-      file_size: params.fileSize,
-      checksum: params.checksum,
-      is_latest: true,
-      file_modified_at: params.file_modified_at,
-    })
-    .select('id')
-    .single()
+  // Swaps the latest version and inserts the row in one transaction
+  const res = await (supabase as any).rpc('create_save_version', {
+    p_id: params.id,
+    p_game_id: params.cloudGameId,
+    p_device_id: params.deviceId,
+    p_file_path: params.filePath,
+    p_file_size: params.fileSize,
+    p_checksum: params.checksum,
+  })
 
   if (res.error) throw res.error
-  return res.data
+  return { id: res.data.id as string }
 }
 
 export async function createSyncLog(params: {
@@ -0,0 +1,55 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { LocalGameDto } from './tauri-games';
+
+export interface EmulatorDirectoryStatusDto {
+  label: string;
+  template?: string | null;
+  path?: string | null;
+  exists: boolean;
+}
+
+export interface EmulatorProfileStatusDto {
+  id: string;
+  name: string;
+  pattern_hint: string;
+  installed: boolean;
+  directories: EmulatorDirectoryStatusDto[];
+}
+
+export interface EmulatorSaveFileDto {
+  rel_path: string;
+  size: number;
+  modified_ms?: number | null;
+}
+
+export async function getEmulatorProfiles(): Promise<
+  EmulatorProfileStatusDto[]
+> {
+  return await invoke<EmulatorProfileStatusDto[]>('get_emulator_profiles');
+}
+
+export async function listEmulatorSaveFiles(
+  profileId: string,
+  label: string,
+  filePattern?: string
+): Promise<EmulatorSaveFileDto[]> {
+  return await invoke<EmulatorSaveFileDto[]>('list_emulator_save_files', {
+    profileId,
+    label,
+    filePattern,
+  });
+}
+
+export async function addEmulatorGame(
+  profileId: string,
+  name: string,
+  filePattern: string,
+  coverUrl?: string
+): Promise<LocalGameDto> {
+  return await invoke<LocalGameDto>('add_emulator_game', {
+    profileId,
+    name,
+    filePattern,
+    coverUrl,
+  });
+}
@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import type { VersionMetaDto } from './tauri-versions';
 
 export interface LocalGameDto {
   id: string;
@@ -9,9 +10,14 @@ export interface LocalGameDto {
   local_path: string;
   sync_enabled: boolean;
   last_synced_id?: string;
+  last_synced_at?: string | null;
   status: string;
   custom_script_path?: string;
-  analysis_config?: { target_path: string; tracked_keys: string[] };
+  analysis_config?: AnalysisConfigDto;
+  steam_app_id?: number | null;
+  emulator_profile?: string | null;
+  executable_path?: string | null;
+  sync_paused?: boolean;
 }
 
 export async function getAllGames(): Promise<LocalGameDto[]> {
@@ -23,11 +29,29 @@ export async function getAllGames(): Promise<LocalGameDto[]> {
   }
 }
 
+export interface GameLocationDto {
+  id: string;
+  game_id: string;
+  label: string;
+  path: string;
+  template?: string | null;
+  sort_order: number;
+  file_pattern?: string | null;
+}
+
+export interface NewGameLocationDto {
+  label: string;
+  path: string;
+  file_pattern?: string;
+}
+
 export async function addGame(
   name: string,
   localPath: string,
   platform: string,
-  coverUrl?: string
+  coverUrl?: string,
+  locations?: NewGameLocationDto[],
+  steamAppId?: number
 ): Promise<LocalGameDto> {
   try {
     return await invoke<LocalGameDto>('add_game', {
@@ -35,6 +59,8 @@ export async function addGame(
       localPath,
       platform,
       coverUrl,
+      locations,
+      steamAppId,
     });
   } catch (error) {
     console.error('Failed to add game:', error);
@@ -59,20 +85,73 @@ export interface SyncResultDto {
   cloud_game_id?: string;
   device_id?: string;
   skipped?: boolean;
+  /** The backend couldn't be reached; the upload waits in the queue */
+  queued?: boolean;
 }
 
 export async function syncGame(
   gameId: string,
-  auth: AuthConfig
+  auth: AuthConfig,
+  metadata?: VersionMetaDto
 ): Promise<SyncResultDto> {
   try {
-    return await invoke<SyncResultDto>('sync_game', { gameId, auth });
+    return await invoke<SyncResultDto>('sync_game', { gameId, auth, metadata });
   } catch (error) {
     console.error('Failed to sync game:', error);
     throw error;
   }
 }
 
+/** Syncs waiting for the network, of one game or of every game. */
+export async function getPendingSyncCount(gameId?: string): Promise<number> {
+  try {
+    return await invoke<number>('get_pending_sync_count', { gameId });
+  } catch (error) {
+    console.error('Failed to get pending syncs:', error);
+    return 0;
+  }
+}
+
+/** Tries the queued syncs now; resolves to how many are still waiting. */
+export async function flushSyncQueue(): Promise<number> {
+  try {
+    return await invoke<number>('flush_sync_queue');
+  } catch (error) {
+    console.error('Failed to flush sync queue:', error);
+    throw error;
+  }
+}
+
+/** Queued syncs set aside after failing too often, of one game or of every game. */
+export async function getFailedSyncCount(gameId?: string): Promise<number> {
+  try {
+    return await invoke<number>('get_failed_sync_count', { gameId });
+  } catch (error) {
+    console.error('Failed to get failed syncs:', error);
+    return 0;
+  }
+}
+
+/** Queues the failed syncs again and tries them; resolves to how many are still waiting. */
+export async function retryFailedSyncs(gameId?: string): Promise<number> {
+  try {
+    return await invoke<number>('retry_failed_syncs', { gameId });
+  } catch (error) {
+    console.error('Failed to retry failed syncs:', error);
+    throw error;
+  }
+}
+
+/** Drops the failed syncs and their archives; resolves to how many were dropped. */
+export async function discardFailedSyncs(gameId?: string): Promise<number> {
+  try {
+    return await invoke<number>('discard_failed_syncs', { gameId });
+  } catch (error) {
+    console.error('Failed to discard failed syncs:', error);
+    throw error;
+  }
+}
+
 export async function restoreGame(
   gameId: string,
   base64Data: string
@@ -101,7 +180,11 @@ export interface UpdateGameParams {
   sync_enabled?: boolean;
   cover_url?: string;
   custom_script_path?: string;
-  analysis_config?: { target_path: string; tracked_keys: string[] };
+  analysis_config?: AnalysisConfigDto;
+  steam_app_id?: number;
+  /** An empty string clears it */
+  executable_path?: string;
+  sync_paused?: boolean;
 }
 
 export async function updateGame(
@@ -137,6 +220,16 @@ export interface SilksongStatsDto {
   progress?: SilksongProgressDto | null;
 }
 
+export interface LocationSaveStatsDto {
+  label: string;
+  path: string;
+  exists: boolean;
+  is_dir: boolean;
+  file_count: number;
+  total_bytes: number;
+  newest_mtime_ms?: number | null;
+}
+
 export interface GameSaveStatsDto {
   path: string;
   exists: boolean;
@@ -144,6 +237,7 @@ export interface GameSaveStatsDto {
   file_count: number;
   total_bytes: number;
   newest_mtime_ms?: number | null;
+  locations: LocationSaveStatsDto[];
   silksong?: SilksongStatsDto | null;
 }
 
@@ -158,6 +252,123 @@ export async function getGameSaveStats(
   }
 }
 
+export async function getGameLocations(
+  gameId: string
+): Promise<GameLocationDto[]> {
+  try {
+    return await invoke<GameLocationDto[]>('get_game_locations', { gameId });
+  } catch (error) {
+    console.error('Failed to get game locations:', error);
+    throw error;
+  }
+}
+
+export async function addGameLocation(
+  gameId: string,
+  label: string,
+  path: string,
+  filePattern?: string
+): Promise<GameLocationDto> {
+  try {
+    return await invoke<GameLocationDto>('add_game_location', {
+      gameId,
+      label,
+      path,
+      filePattern,
+    });
+  } catch (error) {
+    console.error('Failed to add game location:', error);
+    throw error;
+  }
+}
+
+export async function removeGameLocation(locationId: string): Promise<boolean> {
+  try {
+    return await invoke<boolean>('remove_game_location', { locationId });
+  } catch (error) {
+    console.error('Failed to remove game location:', error);
+    throw error;
+  }
+}
+
+/** A save location as published to the cloud, e.g. `{{p|appdata}}/Game` */
+export interface CloudPathTemplate {
+  label: string;
+  template: string;
+}
+
+export interface ResolvedPathDto {
+  template: string;
+  path?: string | null;
+  exists: boolean;
+  error?: string | null;
+}
+
+export async function resolvePathTemplates(
+  templates: string[],
+  steamAppId?: number
+): Promise<ResolvedPathDto[]> {
+  return await invoke<ResolvedPathDto[]>('resolve_path_templates', {
+    templates,
+    steamAppId,
+  });
+}
+
+export interface AnalysisConfigDto {
+  target_path: string;
+  tracked_keys: string[];
+  fields?: Record<string, string>;
+  completion_key?: string | null;
+  play_time_key?: string | null;
+  timeout_secs?: number | null;
+}
+
+export interface AnalysisOutputDto {
+  analyzer: string;
+  target_file: string;
+  data: Record<string, any>;
+  completion_percentage?: number | null;
+  play_time_seconds?: number | null;
+  duration_ms: number;
+}
+
+/** Runs the game's analyzer on a downloaded version zip; the backend stores the result. */
+export async function analyzeVersion(
+  gameId: string,
+  versionId: string,
+  base64Data: string
+): Promise<AnalysisOutputDto> {
+  return await invoke<AnalysisOutputDto>('analyze_version', {
+    gameId,
+    versionId,
+    base64Data,
+  });
+}
+
+/** Runs the game's analyzer on the save currently on disk and updates its stats. */
+export async function analyzeLocalSave(
+  gameId: string
+): Promise<AnalysisOutputDto> {
+  return await invoke<AnalysisOutputDto>('analyze_local_save', { gameId });
+}
+
+export interface SaveDecoderDto {
+  id: string;
+  name: string;
+}
+
+/** Built-in decoders, usable as `builtin:<id>` in place of a script path. */
+export async function listSaveDecoders(): Promise<SaveDecoderDto[]> {
+  return await invoke<SaveDecoderDto[]>('list_save_decoders');
+}
+
+export async function decodeSaveFile(
+  path: string,
+  decoder?: string
+): Promise<any> {
+  return await invoke<any>('decode_save_file', { path, decoder });
+}
+
 export async function getVersionAnalysis(
   versionId: string
 ): Promise<any | null> {
@@ -175,11 +386,12 @@ export async function getVersionAnalysis(
 
 export async function saveVersionAnalysis(
   versionId: string,
-  data: any
+  data: any,
+  gameId?: string
 ): Promise<void> {
   try {
     const analysisData = JSON.stringify(data);
-    await invoke('save_version_analysis', { versionId, analysisData });
+    await invoke('save_version_analysis', { versionId, analysisData, gameId });
   } catch (error) {
     console.error('Failed to save version analysis:', error);
   }
@@ -195,3 +407,18 @@ export async function deleteVersionAnalyses(
     return 0;
   }
 }
+
+export interface LaunchReportDto {
+  pull?: {
+    action: 'up_to_date' | 'pulled' | 'kept_local' | 'no_cloud_versions';
+    version_id?: string | null;
+  } | null;
+  pull_error?: string | null;
+  target: string;
+  tracked: boolean;
+}
+
+/** Pulls newer cloud saves, starts the game and uploads its saves once it exits. */
+export async function prepareLaunch(gameId: string): Promise<LaunchReportDto> {
+  return await invoke<LaunchReportDto>('prepare_launch', { gameId });
+}
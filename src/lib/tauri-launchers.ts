@@ -0,0 +1,30 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { ScanSuggestionDto } from './tauri-scan';
+
+export type LauncherId = 'heroic' | 'lutris' | 'gog-galaxy' | 'epic';
+
+export interface LauncherGameDto {
+  launcher: LauncherId;
+  platform: string;
+  launcher_id: string;
+  title: string;
+  install_path?: string | null;
+  wine_prefix?: string | null;
+  save_paths: string[];
+}
+
+export async function listLauncherGames(
+  launchers?: LauncherId[]
+): Promise<LauncherGameDto[]> {
+  return await invoke<LauncherGameDto[]>('list_launcher_games', { launchers });
+}
+
+export async function scanLauncherGames(options?: {
+  launchers?: LauncherId[];
+  includeManifest?: boolean;
+}): Promise<ScanSuggestionDto[]> {
+  return await invoke<ScanSuggestionDto[]>('scan_launcher_games', {
+    launchers: options?.launchers,
+    includeManifest: options?.includeManifest,
+  });
+}
@@ -0,0 +1,56 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { PcgwSaveLocationsDto } from './tauri-pcgw';
+
+export interface ManifestImportResultDto {
+  games: number;
+  duration_ms: number;
+}
+
+export interface ManifestStatusDto {
+  games: number;
+  imported_at?: string | null;
+}
+
+export interface ManifestSearchResultDto {
+  title: string;
+  steam_app_id?: number | null;
+}
+
+export async function importLudusaviManifest(
+  path: string
+): Promise<ManifestImportResultDto> {
+  return await invoke<ManifestImportResultDto>('import_ludusavi_manifest', {
+    path,
+  });
+}
+
+export async function getManifestStatus(): Promise<ManifestStatusDto> {
+  return await invoke<ManifestStatusDto>('manifest_status');
+}
+
+export async function manifestSearchGames(
+  query: string,
+  limit = 8
+): Promise<ManifestSearchResultDto[]> {
+  return await invoke<ManifestSearchResultDto[]>('manifest_search_games', {
+    query,
+    limit,
+  });
+}
+
+export async function manifestGetSaveLocations(
+  title: string
+): Promise<PcgwSaveLocationsDto> {
+  return await invoke<PcgwSaveLocationsDto>('manifest_get_save_locations', {
+    title,
+  });
+}
+
+export async function manifestFindBySteamAppId(
+  steamAppId: number
+): Promise<PcgwSaveLocationsDto | null> {
+  return await invoke<PcgwSaveLocationsDto | null>(
+    'manifest_find_by_steam_app_id',
+    { steamAppId }
+  );
+}
@@ -7,14 +7,18 @@ export interface PcgwSearchResultDto {
 
 export interface PcgwSavePathDto {
   os: string;
+  platform: 'windows' | 'linux' | 'macos' | 'steam_play' | 'other';
   raw: string;
   expanded?: string | null;
+  current_os: boolean;
 }
 
 export interface PcgwSaveLocationsDto {
   title: string;
   paths: PcgwSavePathDto[];
   cover_url?: string;
+  steam_app_id?: number | null;
+  registry_keys: string[];
 }
 
 export async function pcgwSearchGames(
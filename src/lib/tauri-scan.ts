@@ -0,0 +1,43 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { LocationSaveStatsDto } from './tauri-games';
+
+export interface ScanCandidateDto {
+  name: string;
+  templates: string[];
+  platform?: string;
+  steam_app_id?: number;
+  cover_url?: string;
+  install_dir?: string;
+  wine_prefix?: string;
+}
+
+export interface ScanMatchDto {
+  template: string;
+  stats: LocationSaveStatsDto;
+}
+
+export interface ScanSuggestionDto {
+  name: string;
+  local_path: string;
+  platform: string;
+  cover_url?: string | null;
+  steam_app_id?: number | null;
+  matches: ScanMatchDto[];
+  already_added: boolean;
+}
+
+export async function scanForGames(options?: {
+  candidates?: ScanCandidateDto[];
+  pcgwTitles?: string[];
+  includeBundled?: boolean;
+  includeManifest?: boolean;
+  includeSteam?: boolean;
+}): Promise<ScanSuggestionDto[]> {
+  return await invoke<ScanSuggestionDto[]>('scan_for_games', {
+    candidates: options?.candidates,
+    pcgwTitles: options?.pcgwTitles,
+    includeBundled: options?.includeBundled,
+    includeManifest: options?.includeManifest,
+    includeSteam: options?.includeSteam,
+  });
+}
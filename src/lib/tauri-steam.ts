@@ -19,3 +19,63 @@ export async function steamSearchGames(
     return [];
   }
 }
+
+export interface SteamInstalledAppDto {
+  app_id: number;
+  name: string;
+  install_dir: string;
+  library: string;
+  size_on_disk?: number | null;
+  cloud_save_paths: string[];
+  proton_prefix?: string | null;
+}
+
+export interface SteamLibraryInfoDto {
+  steam_root: string;
+  libraries: string[];
+  user_ids: string[];
+  apps: SteamInstalledAppDto[];
+}
+
+export async function steamListInstalledGames(): Promise<SteamLibraryInfoDto> {
+  return await invoke<SteamLibraryInfoDto>('steam_list_installed_games');
+}
+
+export interface SteamAppDetailsDto {
+  app_id: number;
+  name: string;
+  header_image?: string | null;
+  capsule_image?: string | null;
+  platforms: { windows: boolean; mac: boolean; linux: boolean };
+  release_date?: string | null;
+  coming_soon: boolean;
+}
+
+export interface SteamAppMetadataDto {
+  app_id: number;
+  details?: SteamAppDetailsDto | null;
+  cover_url?: string | null;
+  cover_path?: string | null;
+  fetched_at: string;
+  stale: boolean;
+}
+
+export async function steamGetAppMetadata(
+  appId: number,
+  refresh?: boolean
+): Promise<SteamAppMetadataDto> {
+  return await invoke<SteamAppMetadataDto>('steam_get_app_metadata', {
+    appId,
+    refresh,
+  });
+}
+
+/** Returns the game's cover as a `data:` URL, downloading it first if needed. */
+export async function cacheGameCover(gameId: string): Promise<string | null> {
+  try {
+    return await invoke<string | null>('cache_game_cover', { gameId });
+  } catch (error) {
+    console.warn('Failed to cache game cover:', error);
+    return null;
+  }
+}
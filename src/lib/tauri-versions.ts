@@ -0,0 +1,188 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { AuthConfig } from './tauri-games';
+
+export interface ValueChangeDto {
+  key: string;
+  before?: any;
+  after?: any;
+  delta?: number | null;
+}
+
+export interface TextLineDto {
+  op: '+' | '-';
+  line_a?: number | null;
+  line_b?: number | null;
+  text: string;
+}
+
+export type ContentDiffDto =
+  | { kind: 'json'; changes: ValueChangeDto[]; truncated: boolean }
+  | { kind: 'text'; lines: TextLineDto[]; truncated: boolean };
+
+export interface FileChangeDto {
+  path: string;
+  status: 'added' | 'removed' | 'modified';
+  size_a?: number | null;
+  size_b?: number | null;
+  size_delta: number;
+  content?: ContentDiffDto | null;
+}
+
+export interface VersionDiffDto {
+  version_a: string;
+  version_b: string;
+  files: FileChangeDto[];
+  unchanged: number;
+  total_size_a: number;
+  total_size_b: number;
+  analysis?: ValueChangeDto[] | null;
+}
+
+/** Compares two downloaded versions (base64 zips), older version first. */
+export async function diffVersions(
+  gameId: string,
+  versionA: string,
+  versionB: string,
+  archiveA: string,
+  archiveB: string
+): Promise<VersionDiffDto> {
+  return await invoke<VersionDiffDto>('diff_versions', {
+    gameId,
+    versionA,
+    versionB,
+    archiveA,
+    archiveB,
+  });
+}
+
+export interface GcReportDto {
+  analyses_removed: number;
+  temp_files_removed: number;
+  bytes_freed: number;
+  cloud_checked: boolean;
+}
+
+/**
+ * Removes local analyses of deleted games and stale temp archives. With
+ * `auth`, analyses of versions no longer in the cloud are removed too.
+ */
+export async function collectGarbage(auth?: AuthConfig): Promise<GcReportDto> {
+  return await invoke<GcReportDto>('collect_garbage', { auth });
+}
+
+export interface RetentionPolicyDto {
+  keep_last: number;
+  keep_daily_days: number;
+  keep_weekly_weeks: number;
+}
+
+export interface PrunedVersionDto {
+  id: string;
+  created_at: string;
+  file_size: number;
+}
+
+export interface PruneReportDto {
+  game_id: string;
+  dry_run: boolean;
+  policy: RetentionPolicyDto;
+  kept: number;
+  pruned: PrunedVersionDto[];
+  reclaimed_bytes: number;
+}
+
+/** The game's retention policy, or null when it uses the default. */
+export async function getRetentionPolicy(
+  gameId: string
+): Promise<RetentionPolicyDto | null> {
+  return await invoke<RetentionPolicyDto | null>('get_retention_policy', {
+    gameId,
+  });
+}
+
+export async function setRetentionPolicy(
+  gameId: string,
+  policy: RetentionPolicyDto | null
+): Promise<void> {
+  await invoke('set_retention_policy', { gameId, policy });
+}
+
+/**
+ * Deletes cloud versions the retention policy doesn't keep. Latest and
+ * pinned versions are always kept; use `dryRun` to preview.
+ */
+export async function pruneVersions(
+  gameId: string,
+  auth: AuthConfig,
+  dryRun: boolean,
+  policy?: RetentionPolicyDto
+): Promise<PruneReportDto> {
+  return await invoke<PruneReportDto>('prune_versions', {
+    gameId,
+    auth,
+    policy,
+    dryRun,
+  });
+}
+
+export interface VersionMetaDto {
+  label?: string | null;
+  notes?: string | null;
+  is_pinned?: boolean;
+}
+
+export interface LabeledVersionDto extends VersionMetaDto {
+  version_id: string;
+  created_at?: string | null;
+  completion_percentage?: number | null;
+  play_time_seconds?: number | null;
+}
+
+/**
+ * Labeled, annotated or pinned versions of a game, newest first. Pass `auth`
+ * to refresh from the cloud; without it the local cache is used.
+ */
+export async function listLabeledVersions(
+  gameId: string,
+  auth?: AuthConfig
+): Promise<LabeledVersionDto[]> {
+  return await invoke<LabeledVersionDto[]>('list_labeled_versions', {
+    gameId,
+    auth,
+  });
+}
+
+/** Pinned versions are never pruned; labels are unique per game. */
+export async function setVersionMetadata(
+  gameId: string,
+  versionId: string,
+  auth: AuthConfig,
+  metadata: VersionMetaDto
+): Promise<VersionMetaDto> {
+  return await invoke<VersionMetaDto>('set_version_metadata', {
+    gameId,
+    versionId,
+    auth,
+    metadata,
+  });
+}
+
+export interface RestoredVersionDto {
+  version_id: string;
+  label?: string | null;
+  created_at: string;
+  file_size: number;
+}
+
+/** Downloads and restores a cloud version given by id or label. */
+export async function restoreVersion(
+  gameId: string,
+  auth: AuthConfig,
+  version: string
+): Promise<RestoredVersionDto> {
+  return await invoke<RestoredVersionDto>('restore_version', {
+    gameId,
+    auth,
+    version,
+  });
+}
@@ -55,6 +55,25 @@ export interface AppSettings {
   launch_on_startup: boolean
   desktop_notifications: boolean
   auto_sync_enabled: boolean
+  sync_paused: boolean
+  /** RFC 3339 time a temporary pause ends */
+  sync_paused_until: string | null
+  /** KB/s, 0 for no limit */
+  upload_limit_kbps: number
+  /** KB/s, 0 for no limit */
+  download_limit_kbps: number
+  pause_on_metered: boolean
+}
+
+export const DEFAULT_APP_SETTINGS: AppSettings = {
+  launch_on_startup: true,
+  desktop_notifications: false,
+  auto_sync_enabled: true,
+  sync_paused: false,
+  sync_paused_until: null,
+  upload_limit_kbps: 0,
+  download_limit_kbps: 0,
+  pause_on_metered: true
 }
 
 export async function getAppSettings(): Promise<AppSettings> {
@@ -62,11 +81,7 @@ export async function getAppSettings(): Promise<AppSettings> {
     return await invoke<AppSettings>('get_app_settings')
   } catch (error) {
     console.error('Failed to get app settings:', error)
-    return {
-      launch_on_startup: true,
-      desktop_notifications: false,
-      auto_sync_enabled: true
-    }
+    return DEFAULT_APP_SETTINGS
   }
 }
 
@@ -78,3 +93,20 @@ export async function saveAppSettings(settings: AppSettings): Promise<boolean> {
     return false
   }
 }
+
+export interface CloudSession {
+  url: string
+  key: string
+  user_id: string
+  access_token: string
+  expires_at?: number | null
+}
+
+/** Lets the CLI use the app's sign-in; pass null on sign-out. */
+export async function shareCloudSession(session: CloudSession | null): Promise<void> {
+  try {
+    await invoke('share_cloud_session', { session })
+  } catch (error) {
+    console.error('Failed to share cloud session:', error)
+  }
+}
@@ -33,6 +33,17 @@ export function isTauriRuntime(): boolean {
   return typeof window !== 'undefined' && (window as any).__TAURI_INTERNALS__ !== undefined
 }
 
+/** Base64-encodes binary data for commands that take `base64Data`. */
+export function arrayBufferToBase64(buffer: ArrayBuffer): string {
+  const bytes = new Uint8Array(buffer)
+  let binary = ''
+  const chunk = 0x8000
+  for (let i = 0; i < bytes.length; i += chunk) {
+    binary += String.fromCharCode(...bytes.subarray(i, i + chunk))
+  }
+  return btoa(binary)
+}
+
 export function timeAgo(
   date?: string | number | Date | null,
   options: { empty?: string } = {}
@@ -34,14 +34,16 @@ export default function Games() {
     path: string;
     autoSync: boolean;
     coverUrl?: string;
+    steamAppId?: number;
   }) => {
     try {
       await addGame({
         name: gameData.name,
         local_path: gameData.path,
-        platform: 'other',
+        platform: gameData.steamAppId ? 'steam' : 'other',
         sync_enabled: gameData.autoSync,
         cover_url: gameData.coverUrl,
+        steam_app_id: gameData.steamAppId,
       });
       modalState.close();
     } catch (error) {
@@ -11,6 +11,7 @@ import {
   getAppSettings,
   saveAppSettings,
   type AppSettings,
+  DEFAULT_APP_SETTINGS,
 } from '@/lib/tauri';
 import { useGamesStore } from '@/stores/gamesStore';
 import {
@@ -42,11 +43,7 @@ export default function Settings() {
   const [loadingDevices, setLoadingDevices] = useState(true);
 
   // Settings state
-  const [settings, setSettings] = useState<AppSettings>({
-    launch_on_startup: true,
-    desktop_notifications: false,
-    auto_sync_enabled: true,
-  });
+  const [settings, setSettings] = useState<AppSettings>(DEFAULT_APP_SETTINGS);
 
   useEffect(() => {
     getSystemInfo().then(setSysInfo);
@@ -57,6 +54,23 @@ export default function Settings() {
     getAppSettings().then(setSettings);
   }, [setStoreDeviceName]);
 
+  // Pausing from the tray changes the settings behind this page
+  useEffect(() => {
+    if (hasChanges) return;
+    let cancelled = false;
+    let unlisten: (() => void) | undefined;
+    import('@tauri-apps/api/event').then(async ({ listen }) => {
+      unlisten = await listen('settings-changed', () => {
+        getAppSettings().then(setSettings);
+      });
+      if (cancelled) unlisten();
+    });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [hasChanges]);
+
   useEffect(() => {
     if (user?.id) {
       registerCurrentDevice(user.id).then(() => {
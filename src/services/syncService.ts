@@ -15,6 +15,7 @@ import { registerCurrentDevice } from '@/lib/devices';
 export interface SyncResult {
   success: boolean;
   skipped?: boolean;
+  queued?: boolean;
   message: string;
   fileSize?: number;
   checksum?: string;
@@ -93,6 +94,7 @@ export async function executeSync(
     return {
       success: true,
       skipped: result.skipped,
+      queued: result.queued,
       message: result.message,
       fileSize: result.file_size,
       checksum: result.checksum,
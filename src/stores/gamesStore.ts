@@ -3,7 +3,10 @@ import {
   getAllGames,
   addGame as tauriAddGame,
   deleteGame as tauriDeleteGame,
+  type CloudPathTemplate,
+  type NewGameLocationDto,
 } from '@/lib/tauri-games';
+import { cacheGameCover } from '@/lib/tauri-steam';
 import { isTauriRuntime } from '@/lib/utils';
 
 export type SyncStatus =
@@ -14,7 +17,7 @@ export type SyncStatus =
   | 'idle'
   | 'not_configured';
 export type SyncAction = 'upload' | 'download' | 'skip' | 'conflict';
-export type GamePlatform = 'steam' | 'epic' | 'gog' | 'other';
+export type GamePlatform = 'steam' | 'epic' | 'gog' | 'emulator' | 'other';
 
 export interface Game {
   id: string;
@@ -24,14 +27,24 @@ export interface Game {
   platform: GamePlatform;
   local_path: string;
   sync_enabled: boolean;
+  sync_paused?: boolean; // Held back from every sync until resumed
   last_synced_at?: string;
   last_synced_id?: string;
   status: SyncStatus;
   cloud_game_id?: string; // ID from cloud (Supabase games table)
+  steam_app_id?: number;
+  path_templates?: CloudPathTemplate[]; // Save locations published by other devices
+  cover_local?: string; // Cached cover as a data: URL, works offline
+  emulator_profile?: string; // e.g. 'retroarch', when platform is 'emulator'
+  executable_path?: string; // Started by "Play" when there's no Steam app id
   custom_script_path?: string;
   analysis_config?: {
     target_path: string; // Relative path or filename of the file to analyze within the save
     tracked_keys: string[]; // List of flattened keys to extract
+    fields?: Record<string, string>; // Output name -> path, e.g. '$.playerData.geo'
+    completion_key?: string; // Key or path of the completion percentage
+    play_time_key?: string; // Key or path of the play time in seconds
+    timeout_secs?: number;
   };
 }
 
@@ -83,7 +96,13 @@ interface GamesState {
   ) => Promise<void>;
   loadGames: () => Promise<void>;
   loadCloudGames: () => Promise<void>;
-  configureGamePath: (cloudGameId: string, localPath: string) => Promise<void>;
+  configureGamePath: (
+    cloudGameId: string,
+    localPath: string,
+    templates?: { primary: string; locations: NewGameLocationDto[] },
+  ) => Promise<void>;
+  // Sets the game up where other devices' templates point, if that exists here
+  configureFromTemplates: (cloudGameId: string) => Promise<string | null>;
   refreshMetrics: () => Promise<void>;
   deviceName: string;
   setDeviceName: (name: string) => void;
@@ -108,6 +127,22 @@ interface GamesState {
   ) => Promise<void>;
 }
 
+// Loads locally cached covers (downloading them when missing) one game at a time
+async function loadCachedCovers(
+  games: Game[],
+  set: (fn: (state: GamesState) => Partial<GamesState>) => void,
+) {
+  for (const game of games) {
+    const coverLocal = await cacheGameCover(game.id);
+    if (!coverLocal) continue;
+    set((state) => ({
+      games: state.games.map((g) =>
+        g.id === game.id ? { ...g, cover_local: coverLocal } : g,
+      ),
+    }));
+  }
+}
+
 export const useGamesStore = create<GamesState>((set, get) => ({
   games: [],
   activities: [],
@@ -214,13 +249,18 @@ export const useGamesStore = create<GamesState>((set, get) => ({
           platform: g.platform as GamePlatform,
           local_path: g.local_path,
           sync_enabled: g.sync_enabled,
+          sync_paused: g.sync_paused ?? false,
           status: g.status as SyncStatus,
-          last_synced_at: undefined,
+          last_synced_at: g.last_synced_at ?? undefined,
           last_synced_id: g.last_synced_id,
           custom_script_path: g.custom_script_path,
           analysis_config: g.analysis_config,
+          steam_app_id: g.steam_app_id ?? undefined,
+          emulator_profile: g.emulator_profile ?? undefined,
+          executable_path: g.executable_path ?? undefined,
         }));
         set({ games, totalGames: games.length });
+        void loadCachedCovers(games, set);
       }
     } catch (e) {
       console.error('Failed to load games', e);
@@ -246,7 +286,7 @@ export const useGamesStore = create<GamesState>((set, get) => ({
       const { data: cloudGames, error: gamesError } = await (
         supabase.from('games') as any
       )
-        .select('id, name, slug, cover_url')
+        .select('id, name, slug, cover_url, path_templates, steam_app_id')
         .eq('user_id', user.id);
 
       if (gamesError) throw gamesError;
@@ -306,6 +346,8 @@ export const useGamesStore = create<GamesState>((set, get) => ({
             local_path: '',
             sync_enabled: true,
             status: 'not_configured',
+            steam_app_id: cg.steam_app_id ?? undefined,
+            path_templates: cg.path_templates ?? [],
           });
         }
       }
@@ -321,7 +363,7 @@ export const useGamesStore = create<GamesState>((set, get) => ({
     }
   },
 
-  configureGamePath: async (cloudGameId: string, localPath: string) => {
+  configureGamePath: async (cloudGameId, localPath, templates) => {
     const { supabase } = await import('@/lib/supabase');
     const { useAuthStore } = await import('@/stores/authStore');
     const { registerCurrentDevice } = await import('@/lib/devices');
@@ -356,7 +398,15 @@ export const useGamesStore = create<GamesState>((set, get) => ({
     // Add to local SQLite if Tauri runtime
     if (isTauriRuntime()) {
       try {
-        await tauriAddGameFn(game.name, localPath, game.platform);
+        // Templates are kept as given so they resolve again on this device
+        await tauriAddGameFn(
+          game.name,
+          templates?.primary ?? localPath,
+          game.platform,
+          undefined,
+          templates?.locations,
+          game.steam_app_id,
+        );
       } catch (e) {
         console.warn('Failed to add game to local DB:', e);
       }
@@ -369,6 +419,34 @@ export const useGamesStore = create<GamesState>((set, get) => ({
     });
   },
 
+  configureFromTemplates: async (cloudGameId: string) => {
+    const { resolvePathTemplates } = await import('@/lib/tauri-games');
+
+    const game = get().games.find(
+      (g) => g.cloud_game_id === cloudGameId || g.id === cloudGameId,
+    );
+    const templates = game?.path_templates ?? [];
+    if (!isTauriRuntime() || templates.length === 0) return null;
+
+    const resolved = await resolvePathTemplates(
+      templates.map((t) => t.template),
+      game?.steam_app_id,
+    );
+    // Only trusted when the primary folder exists on this device
+    const primary = resolved[0];
+    if (!primary?.path || !primary.exists) return null;
+
+    const locations = templates
+      .slice(1)
+      .filter((_, i) => resolved[i + 1]?.path)
+      .map((t) => ({ label: t.label, path: t.template }));
+    await get().configureGamePath(cloudGameId, primary.path, {
+      primary: templates[0].template,
+      locations,
+    });
+    return primary.path;
+  },
+
   refreshMetrics: async () => {
     const { supabase } = await import('@/lib/supabase');
     const { useAuthStore } = await import('@/stores/authStore');
@@ -419,6 +497,8 @@ export const useGamesStore = create<GamesState>((set, get) => ({
           newGame.local_path,
           newGame.platform,
           newGame.cover_url,
+          undefined,
+          newGame.steam_app_id,
         );
         const game: Game = {
           id: added.id,
@@ -432,11 +512,13 @@ export const useGamesStore = create<GamesState>((set, get) => ({
           last_synced_id: undefined,
           custom_script_path: added.custom_script_path,
           analysis_config: added.analysis_config,
+          steam_app_id: added.steam_app_id ?? undefined,
         };
         set((state) => ({
           games: [...state.games, game],
           totalGames: state.totalGames + 1,
         }));
+        void loadCachedCovers([game], set);
       } else {
         const game: Game = {
           ...newGame,
@@ -13,12 +13,18 @@ interface SyncState {
   progress: number;
   message: string;
   isBackendConnected: boolean;
+  /** Syncs queued while offline */
+  pendingSyncs: number;
+  /** Queued syncs set aside after failing too often */
+  failedSyncs: number;
   syncCooldowns: Record<string, number>; // gameId -> lastSyncTimestamp
 
   setStatus: (status: SyncState['status']) => void;
   setProgress: (progress: number) => void;
   setMessage: (message: string) => void;
   setBackendConnected: (connected: boolean) => void;
+  setPendingSyncs: (count: number) => void;
+  setFailedSyncs: (count: number) => void;
   performSync: (gameId: string, options?: { force?: boolean }) => Promise<void>;
   performRestore: (
     gameId: string,
@@ -38,12 +44,16 @@ export const useSyncStore = create<SyncState>()((set, get) => ({
   progress: 0,
   message: '',
   isBackendConnected: false,
+  pendingSyncs: 0,
+  failedSyncs: 0,
   syncCooldowns: {},
 
   setStatus: (status) => set({ status }),
   setProgress: (progress) => set({ progress }),
   setMessage: (message) => set({ message }),
   setBackendConnected: (isBackendConnected) => set({ isBackendConnected }),
+  setPendingSyncs: (pendingSyncs) => set({ pendingSyncs }),
+  setFailedSyncs: (failedSyncs) => set({ failedSyncs }),
 
   performSync: async (gameId: string, options = {}) => {
     // Clear any existing debounce timer for this game
@@ -109,7 +119,12 @@ async function handleSyncResult(
   const game = useGamesStore.getState().games.find((g) => g.id === gameId);
 
   if (result.success) {
-    if (result.skipped) {
+    if (result.queued) {
+      // Uploaded from the queue once the backend answers again
+      set({ status: 'idle', progress: 0, message: result.message });
+      useGamesStore.getState().updateGame(gameId, { status: 'pending' });
+      toast.info('Saved for Later', `${game?.name || 'Game'}: ${result.message}`);
+    } else if (result.skipped) {
       set({ status: 'idle', progress: 0, message: result.message });
 
       // Log skip action
@@ -81,6 +81,10 @@ export interface SaveVersionRow {
   file_size: number
   checksum: string
   is_latest: boolean
+  is_pinned?: boolean
+  label?: string | null
+  notes?: string | null
+  parent_version_id?: string | null
   file_modified_at?: string
   analysis_data?: any | null
   created_at: string
@@ -93,11 +97,17 @@ export interface SaveVersionInsert {
   file_size: number
   checksum: string
   is_latest: boolean
+  is_pinned?: boolean
+  label?: string | null
+  notes?: string | null
   file_modified_at?: string
   analysis_data?: any | null
 }
 export interface SaveVersionUpdate {
   is_latest?: boolean
+  is_pinned?: boolean
+  label?: string | null
+  notes?: string | null
   analysis_data?: any | null
 }
 
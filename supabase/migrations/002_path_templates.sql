@@ -0,0 +1,10 @@
+-- =============================================
+-- MIGRATION: path templates
+-- Caminhos de save portáveis ({{p|appdata}}, {{p|home}}, {{p|steam}}, ...)
+-- resolvidos localmente por cada dispositivo (incluindo prefixos Proton)
+-- =============================================
+
+ALTER TABLE games ADD COLUMN IF NOT EXISTS path_templates JSONB DEFAULT '[]'::jsonb;
+ALTER TABLE games ADD COLUMN IF NOT EXISTS steam_app_id INTEGER;
+
+ALTER TABLE game_paths ADD COLUMN IF NOT EXISTS path_template TEXT;
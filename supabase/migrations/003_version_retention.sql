@@ -0,0 +1,8 @@
+-- =============================================
+-- MIGRATION: version retention
+-- Versões fixadas nunca são removidas por prune_versions
+-- =============================================
+
+ALTER TABLE save_versions ADD COLUMN IF NOT EXISTS is_pinned BOOLEAN DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_save_versions_pinned ON save_versions(game_id) WHERE is_pinned = TRUE;
@@ -0,0 +1,23 @@
+-- =============================================
+-- MIGRATION: version labels
+-- Rótulos e anotações para versões importantes (ex.: "antes do chefe final")
+-- =============================================
+
+ALTER TABLE save_versions ADD COLUMN IF NOT EXISTS label TEXT;
+ALTER TABLE save_versions ADD COLUMN IF NOT EXISTS notes TEXT;
+
+-- Um rótulo identifica uma única versão do jogo (usado por restore_version)
+CREATE UNIQUE INDEX IF NOT EXISTS idx_save_versions_game_label
+    ON save_versions(game_id, lower(label)) WHERE label IS NOT NULL;
+
+-- Rótulos, notas e fixação são editados depois do upload
+DROP POLICY IF EXISTS "Users can update own save_versions" ON save_versions;
+CREATE POLICY "Users can update own save_versions"
+    ON save_versions FOR UPDATE
+    USING (
+        EXISTS (
+            SELECT 1 FROM games g
+            WHERE g.id = save_versions.game_id
+            AND g.user_id = auth.uid()
+        )
+    );
@@ -0,0 +1,71 @@
+-- =============================================
+-- MIGRATION: atomic versions
+-- Cria a versão e troca a mais recente numa única transação, para que dois
+-- dispositivos sincronizando ao mesmo tempo não deixem o jogo sem versão
+-- mais recente (ou com duas)
+-- =============================================
+
+ALTER TABLE save_versions ADD COLUMN IF NOT EXISTS parent_version_id UUID
+    REFERENCES save_versions(id) ON DELETE SET NULL;
+
+-- Corrige jogos que a corrida já deixou com mais de uma versão mais recente
+UPDATE save_versions v SET is_latest = FALSE
+WHERE v.is_latest = TRUE
+AND EXISTS (
+    SELECT 1 FROM save_versions n
+    WHERE n.game_id = v.game_id
+    AND n.is_latest = TRUE
+    AND (n.created_at, n.id) > (v.created_at, v.id)
+);
+
+-- No máximo uma versão mais recente por jogo
+CREATE UNIQUE INDEX IF NOT EXISTS idx_save_versions_one_latest
+    ON save_versions(game_id) WHERE is_latest = TRUE;
+
+-- =============================================
+-- FUNCTION: create_save_version
+-- Registra a versão enviada como a mais recente do jogo. Roda com as
+-- permissões de quem chama, então o RLS continua valendo; o lock na linha
+-- do jogo serializa as criações concorrentes
+-- =============================================
+CREATE OR REPLACE FUNCTION create_save_version(
+    p_id UUID,
+    p_game_id UUID,
+    p_device_id UUID,
+    p_file_path TEXT,
+    p_file_size BIGINT,
+    p_checksum TEXT,
+    p_label TEXT DEFAULT NULL,
+    p_notes TEXT DEFAULT NULL,
+    p_is_pinned BOOLEAN DEFAULT FALSE
+)
+RETURNS save_versions AS $$
+DECLARE
+    v_parent UUID;
+    v_row save_versions;
+BEGIN
+    PERFORM 1 FROM games WHERE id = p_game_id FOR UPDATE;
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Game % not found', p_game_id USING ERRCODE = 'P0002';
+    END IF;
+
+    UPDATE save_versions SET is_latest = FALSE
+    WHERE game_id = p_game_id AND is_latest = TRUE
+    RETURNING id INTO v_parent;
+
+    INSERT INTO save_versions (
+        id, game_id, device_id, file_path, file_size, checksum,
+        is_latest, label, notes, is_pinned, parent_version_id
+    )
+    VALUES (
+        p_id, p_game_id, p_device_id, p_file_path, p_file_size, p_checksum,
+        TRUE, p_label, p_notes, COALESCE(p_is_pinned, FALSE), v_parent
+    )
+    RETURNING * INTO v_row;
+
+    RETURN v_row;
+END;
+$$ LANGUAGE plpgsql SECURITY INVOKER;
+
+GRANT EXECUTE ON FUNCTION create_save_version(UUID, UUID, UUID, TEXT, BIGINT, TEXT, TEXT, TEXT, BOOLEAN)
+    TO authenticated;